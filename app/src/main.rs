@@ -1,9 +1,15 @@
 use std::sync::Arc;
 
 use config::Config;
-use dao::{BanWordFilterDao, ChatSettingsDao, Database, TokenDao, TwitchDataDao, UserDao};
-use service::{AuthService, BanWordService, ChatService, SessionService, TwitchService};
-use twitch_api::TwitchApi;
+use dao::{
+    BanWordFilterDao, ChatSettingsDao, ChatSettingsTemplateDao, Database, IngestLockDao,
+    NotificationDao, TokenDao, TwitchDataDao, UserDao,
+};
+use service::{
+    AuthService, BanWordService, ChatService, ClusterChatSource, EventService, OverlayService,
+    SessionService, SettingsBus, TrashPurge, TwitchService,
+};
+use twitch_api::{ChatSource, TwitchApi, TwitchChat};
 use types::error::AppResult;
 use utils::crypt::Crypt;
 use utils::jwt::JwtMaker;
@@ -24,9 +30,25 @@ async fn main() -> AppResult {
     let twitch_data_dao = Arc::new(TwitchDataDao::new(database.postgres()));
     let ban_word_filter_dao = Arc::new(BanWordFilterDao::new(database.postgres()));
     let chat_settings_dao = Arc::new(ChatSettingsDao::new(database.postgres()));
+    let chat_settings_template_dao = Arc::new(ChatSettingsTemplateDao::new(database.postgres()));
 
     let twitch_api = Arc::new(TwitchApi::new(config.twitch_config().clone()));
 
+    let twitch_chat: Arc<dyn ChatSource> = Arc::new(TwitchChat::new());
+    let (settings_bus, chat_source) = if config.database_config().listen_notify() {
+        let notification_dao = Arc::new(NotificationDao::new(database.postgres()));
+        let ingest_lock_dao = Arc::new(IngestLockDao::new(database.postgres()));
+        let chat_source: Arc<dyn ChatSource> = Arc::new(
+            ClusterChatSource::new(twitch_chat, ingest_lock_dao, notification_dao.clone()).await?,
+        );
+        (
+            Arc::new(SettingsBus::with_postgres(notification_dao).await?),
+            chat_source,
+        )
+    } else {
+        (Arc::new(SettingsBus::new()), twitch_chat)
+    };
+
     let auth_service = Arc::new(AuthService::new(
         jwt,
         twitch_api.clone(),
@@ -35,9 +57,44 @@ async fn main() -> AppResult {
         token_dao.clone(),
     ));
     let session_service = Arc::new(SessionService::new(token_dao.clone()));
-    let twitch_service = Arc::new(TwitchService::new(twitch_api.clone()));
-    let ban_word_service = Arc::new(BanWordService::new(ban_word_filter_dao.clone()));
-    let chat_service = Arc::new(ChatService::new(chat_settings_dao.clone()));
+    let twitch_service = Arc::new(TwitchService::new(
+        twitch_api.clone(),
+        twitch_data_dao.clone(),
+    ));
+    let ban_word_service = Arc::new(BanWordService::new(
+        ban_word_filter_dao.clone(),
+        settings_bus.clone(),
+    ));
+    let chat_service = Arc::new(ChatService::new(
+        chat_settings_dao.clone(),
+        ban_word_filter_dao.clone(),
+        chat_settings_template_dao.clone(),
+        twitch_api.clone(),
+        settings_bus.clone(),
+    ));
+    let event_service = Arc::new(EventService::new(
+        twitch_api.clone(),
+        twitch_service.clone(),
+        config.twitch_config(),
+    ));
+    let overlay_service = Arc::new(OverlayService::new(
+        chat_source,
+        twitch_api.clone(),
+        event_service.clone(),
+        settings_bus.clone(),
+        chat_settings_dao.clone(),
+        ban_word_filter_dao.clone(),
+        user_dao.clone(),
+    ));
+
+    overlay_service.spawn_event_relay();
+
+    TrashPurge::new(
+        chat_settings_dao.clone(),
+        ban_word_filter_dao.clone(),
+        config.database_config().trash_retention_days(),
+    )
+    .spawn();
 
     web_server::run(
         config.http_config().clone(),
@@ -47,6 +104,8 @@ async fn main() -> AppResult {
             twitch: twitch_service,
             ban_word: ban_word_service,
             chat: chat_service,
+            event: event_service,
+            overlay: overlay_service,
         },
     )
     .await;
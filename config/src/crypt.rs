@@ -15,7 +15,7 @@ impl CryptConfig {
     }
 
     pub fn secret(&self) -> &str {
-        return &self.secret;
+        &self.secret
     }
 }
 
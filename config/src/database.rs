@@ -5,17 +5,36 @@ use types::error::AppResult;
 #[derive(Debug, PartialEq, Clone)]
 pub struct DatabaseConfig {
     postgres_url: String,
+    listen_notify: bool,
+    trash_retention_days: u32,
 }
 
 impl DatabaseConfig {
     pub fn load() -> AppResult<Self> {
         Ok(DatabaseConfig {
             postgres_url: env::var("DATABASE_URL").expect("fail get DATABASE_URL"),
+            listen_notify: env::var("DATABASE_LISTEN_NOTIFY")
+                .map(|value| value == "true")
+                .unwrap_or(false),
+            trash_retention_days: env::var("DATABASE_TRASH_RETENTION_DAYS")
+                .ok()
+                .and_then(|value| value.parse().ok())
+                .unwrap_or(30),
         })
     }
 
     pub fn postgres_url(&self) -> &str {
-        return &self.postgres_url;
+        &self.postgres_url
+    }
+
+    /// Share changes between instances through Postgres `LISTEN/NOTIFY`.
+    pub fn listen_notify(&self) -> bool {
+        self.listen_notify
+    }
+
+    /// Days soft deleted rows are kept before they are purged.
+    pub fn trash_retention_days(&self) -> u32 {
+        self.trash_retention_days
     }
 }
 
@@ -31,6 +50,8 @@ mod tests {
     #[allow(dead_code)]
     struct TestData {
         postgres_url: String,
+        listen_notify: bool,
+        trash_retention_days: u32,
     }
 
     #[test]
@@ -38,11 +59,18 @@ mod tests {
         for _ in 1..100 {
             let data = Faker.fake::<TestData>();
             env::set_var("DATABASE_URL", &data.postgres_url);
+            env::set_var("DATABASE_LISTEN_NOTIFY", data.listen_notify.to_string());
+            env::set_var(
+                "DATABASE_TRASH_RETENTION_DAYS",
+                data.trash_retention_days.to_string(),
+            );
 
             let config = DatabaseConfig::load();
             assert!(config.is_ok());
             let config = config.unwrap();
             assert_eq!(config.postgres_url, data.postgres_url);
+            assert_eq!(config.listen_notify, data.listen_notify);
+            assert_eq!(config.trash_retention_days, data.trash_retention_days);
         }
     }
 }
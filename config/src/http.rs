@@ -20,15 +20,15 @@ impl HttpConfig {
     }
 
     pub fn host(&self) -> &str {
-        return &self.host;
+        &self.host
     }
 
     pub fn port(&self) -> &str {
-        return &self.port;
+        &self.port
     }
 
     pub fn allow_origin(&self) -> &str {
-        return &self.allow_origin;
+        &self.allow_origin
     }
 }
 
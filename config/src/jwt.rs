@@ -15,7 +15,7 @@ impl JwtConfig {
     }
 
     pub fn secret(&self) -> &str {
-        return &self.secret;
+        &self.secret
     }
 }
 
@@ -38,26 +38,26 @@ impl Config {
     }
 
     pub fn logging_config(&self) -> &LoggingConfig {
-        return &self.logging_config;
+        &self.logging_config
     }
 
     pub fn jwt_config(&self) -> &JwtConfig {
-        return &self.jwt_config;
+        &self.jwt_config
     }
 
     pub fn crypt_config(&self) -> &CryptConfig {
-        return &self.crypt_config;
+        &self.crypt_config
     }
 
     pub fn twitch_config(&self) -> &TwitchConfig {
-        return &self.twitch_config;
+        &self.twitch_config
     }
 
     pub fn database_config(&self) -> &DatabaseConfig {
-        return &self.database_config;
+        &self.database_config
     }
 
     pub fn http_config(&self) -> &HttpConfig {
-        return &self.http_config;
+        &self.http_config
     }
 }
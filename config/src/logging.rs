@@ -31,7 +31,7 @@ impl LoggingConfig {
     }
 
     pub fn level(&self) -> &Level {
-        return &self.level;
+        &self.level
     }
 }
 
@@ -7,6 +7,8 @@ pub struct TwitchConfig {
     callback_url: String,
     client_id: String,
     client_secret: String,
+    eventsub_callback_url: Option<String>,
+    eventsub_secret: Option<String>,
 }
 
 impl TwitchConfig {
@@ -15,19 +17,41 @@ impl TwitchConfig {
             callback_url: env::var("TWITCH_CALLBACK_URL").expect("fail get TWITCH_CALLBACK_URL"),
             client_id: env::var("TWITCH_CLIENT_ID").expect("fail get TWITCH_CLIENT_ID"),
             client_secret: env::var("TWITCH_CLIENT_SECRET").expect("fail get TWITCH_CLIENT_SECRET"),
+            eventsub_callback_url: env::var("TWITCH_EVENTSUB_CALLBACK_URL").ok(),
+            eventsub_secret: env::var("TWITCH_EVENTSUB_SECRET").ok(),
         })
     }
 
+    /// Config without EventSub webhooks, for callers which don't read it from
+    /// the environment.
+    pub fn new(callback_url: &str, client_id: &str, client_secret: &str) -> Self {
+        TwitchConfig {
+            callback_url: callback_url.to_string(),
+            client_id: client_id.to_string(),
+            client_secret: client_secret.to_string(),
+            eventsub_callback_url: None,
+            eventsub_secret: None,
+        }
+    }
+
     pub fn callback_url(&self) -> &str {
-        return &self.callback_url;
+        &self.callback_url
     }
 
     pub fn client_id(&self) -> &str {
-        return &self.client_id;
+        &self.client_id
     }
 
     pub fn client_secret(&self) -> &str {
-        return &self.client_secret;
+        &self.client_secret
+    }
+
+    pub fn eventsub_callback_url(&self) -> Option<&str> {
+        self.eventsub_callback_url.as_deref()
+    }
+
+    pub fn eventsub_secret(&self) -> Option<&str> {
+        self.eventsub_secret.as_deref()
     }
 }
 
@@ -45,6 +69,8 @@ mod tests {
         callback_url: String,
         client_id: String,
         client_secret: String,
+        eventsub_callback_url: String,
+        eventsub_secret: String,
     }
 
     #[test]
@@ -54,6 +80,8 @@ mod tests {
             env::set_var("TWITCH_CALLBACK_URL", &data.callback_url);
             env::set_var("TWITCH_CLIENT_ID", &data.client_id);
             env::set_var("TWITCH_CLIENT_SECRET", &data.client_secret);
+            env::set_var("TWITCH_EVENTSUB_CALLBACK_URL", &data.eventsub_callback_url);
+            env::set_var("TWITCH_EVENTSUB_SECRET", &data.eventsub_secret);
 
             let config = TwitchConfig::load();
             assert!(config.is_ok());
@@ -61,6 +89,11 @@ mod tests {
             assert_eq!(config.callback_url, data.callback_url);
             assert_eq!(config.client_id, data.client_id);
             assert_eq!(config.client_secret, data.client_secret);
+            assert_eq!(
+                config.eventsub_callback_url,
+                Some(data.eventsub_callback_url)
+            );
+            assert_eq!(config.eventsub_secret, Some(data.eventsub_secret));
         }
     }
 }
@@ -1,13 +1,18 @@
 use std::sync::Arc;
 
 use axum::http::StatusCode;
+use chrono::NaiveDateTime;
 use sqlx::{PgConnection, Pool, Postgres};
 use tracing::instrument;
 use uuid::Uuid;
 
-use types::domain::{BanWordFilter, BanWordFilterInfo, UpdateBanWordFilter};
+use types::domain::{
+    BanWordFilter, BanWordFilterInfo, BanWordFilterUsage, Page, PageRequest, UpdateBanWordFilter,
+};
 use types::error::{AppError, AppResult};
 
+use crate::cursor::{Cursor, PageQuery};
+
 pub struct BanWordFilterDao {
     pool: Arc<Pool<Postgres>>,
 }
@@ -40,7 +45,7 @@ impl BanWordFilterDao {
     pub async fn create(&self, user_id: &str, name: &str) -> AppResult<BanWordFilter> {
         let raw_ban_word_filter = sqlx::query_as!(
             RawBanWordFilter,
-            r#"INSERT INTO ban_word_filters (id, name, user_id) VALUES ($1, $2, $3) RETURNING id, name, user_id"#,
+            r#"INSERT INTO ban_word_filters (id, name, user_id) VALUES ($1, $2, $3) RETURNING id, name, user_id, revision"#,
             Uuid::new_v4(),
             name,
             user_id,
@@ -57,16 +62,52 @@ impl BanWordFilterDao {
         Ok(raw_ban_word_filter.into())
     }
 
+    /// Creates the filter with its ban words in the caller's transaction.
+    #[instrument(skip(self, update_ban_word_filter, conn))]
+    pub async fn create_in_transaction(
+        &self,
+        user_id: &str,
+        update_ban_word_filter: &UpdateBanWordFilter,
+        conn: &mut PgConnection,
+    ) -> AppResult<BanWordFilter> {
+        let raw_ban_word_filter = sqlx::query_as!(
+            RawBanWordFilter,
+            r#"INSERT INTO ban_word_filters (id, name, user_id) VALUES ($1, $2, $3) RETURNING id, name, user_id, revision"#,
+            Uuid::new_v4(),
+            update_ban_word_filter.name,
+            user_id,
+        )
+            .fetch_one(&mut *conn)
+            .await
+            .map_err(|e| match e {
+                sqlx::Error::Database(dbe) if dbe.constraint() == Some("ban_word_filters_id_key") => {
+                    BanWordFilterDao::ID_TAKEN_ERROR
+                }
+                _ => BanWordFilterDao::FAIL_QUERY_ERROR.clone().cause(e.into()),
+            })?;
+
+        let mut ban_word_filter: BanWordFilter = raw_ban_word_filter.into();
+
+        if !update_ban_word_filter.ban_words.is_empty() {
+            self.create_ban_words(&ban_word_filter.id, &update_ban_word_filter.ban_words, conn)
+                .await?;
+        }
+        ban_word_filter.ban_words = update_ban_word_filter.ban_words.clone();
+
+        Ok(ban_word_filter)
+    }
+
     #[instrument(skip(self))]
     pub async fn get(&self, id: &Uuid) -> AppResult<BanWordFilter> {
         let raw_ban_word_filter = sqlx::query_as!(
             RawBanWordFilter,
-            r#"SELECT id, name, user_id FROM ban_word_filters WHERE id = $1 LIMIT 1"#,
+            r#"SELECT id, name, user_id, revision FROM ban_word_filters WHERE id = $1 AND deleted_at IS NULL LIMIT 1"#,
             id,
         )
-        .fetch_one(self.pool.as_ref())
+        .fetch_optional(self.pool.as_ref())
         .await
-        .map_err(|e| BanWordFilterDao::FAIL_QUERY_ERROR.clone().cause(e.into()))?;
+        .map_err(|e| BanWordFilterDao::FAIL_QUERY_ERROR.clone().cause(e.into()))?
+        .ok_or(BanWordFilterDao::NOT_FOUND_ERROR)?;
 
         let ban_words = self.ban_words(id).await?;
 
@@ -78,23 +119,72 @@ impl BanWordFilterDao {
     }
 
     #[instrument(skip(self))]
-    pub async fn get_all_by_user_id(&self, user_id: &str) -> AppResult<Vec<BanWordFilterInfo>> {
+    pub async fn get_all_by_user_id(
+        &self,
+        user_id: &str,
+        page_request: &PageRequest,
+    ) -> AppResult<Page<BanWordFilterInfo>> {
+        let page_query = PageQuery::new(page_request)?;
+
         let raw_ban_word_filter_infos = sqlx::query_as!(
             RawBanWordFilterInfo,
-            r#"SELECT id, name, user_id FROM ban_word_filters WHERE user_id = $1"#,
+            r#"SELECT id as "id!", name as "name!", user_id as "user_id!", created_at as "created_at!", updated_at as "updated_at!", deleted_at, sort_key as "sort_key!" FROM (SELECT id, name, user_id, created_at, updated_at, deleted_at, CASE $2::varchar WHEN 'name' THEN lower(name) WHEN 'updatedAt' THEN to_char(updated_at, 'YYYY-MM-DD HH24:MI:SS.US') ELSE to_char(created_at, 'YYYY-MM-DD HH24:MI:SS.US') END AS sort_key FROM ban_word_filters WHERE user_id = $1 AND deleted_at IS NULL AND ($3::varchar IS NULL OR name ILIKE $3)) AS page WHERE $4::varchar IS NULL OR CASE WHEN $5 THEN (sort_key, id) < ($4, $6::uuid) ELSE (sort_key, id) > ($4, $6::uuid) END ORDER BY CASE WHEN $5 THEN sort_key END DESC, CASE WHEN $5 THEN id END DESC, sort_key, id LIMIT $7"#,
             user_id,
+            page_query.sort.to_str(),
+            page_query.search_pattern,
+            page_query.after.as_ref().map(|cursor| cursor.sort_key.clone()),
+            page_query.is_desc,
+            page_query.after.as_ref().map(|cursor| cursor.id),
+            page_query.limit,
         )
         .fetch_all(self.pool.as_ref())
         .await
         .map_err(|e| BanWordFilterDao::FAIL_QUERY_ERROR.clone().cause(e.into()))?;
 
-        let mut filters: Vec<BanWordFilterInfo> = Vec::new();
+        let mut rows: Vec<(BanWordFilterInfo, Cursor)> = Vec::new();
 
         for raw_ban_word_filter_info in raw_ban_word_filter_infos {
-            filters.push(raw_ban_word_filter_info.into());
+            let cursor = Cursor {
+                sort_key: raw_ban_word_filter_info.sort_key.clone(),
+                id: raw_ban_word_filter_info.id,
+            };
+            rows.push((raw_ban_word_filter_info.into(), cursor));
         }
 
-        Ok(filters)
+        Ok(page_query.page(rows))
+    }
+
+    /// Newest deleted first.
+    #[instrument(skip(self))]
+    pub async fn get_trash_by_user_id(&self, user_id: &str) -> AppResult<Vec<BanWordFilterInfo>> {
+        let raw_ban_word_filter_infos = sqlx::query_as!(
+            RawBanWordFilterInfo,
+            r#"SELECT id, name, user_id, created_at, updated_at, deleted_at, to_char(deleted_at, 'YYYY-MM-DD HH24:MI:SS.US') as "sort_key!" FROM ban_word_filters WHERE user_id = $1 AND deleted_at IS NOT NULL ORDER BY deleted_at DESC, id"#,
+            user_id,
+        )
+        .fetch_all(self.pool.as_ref())
+        .await
+        .map_err(|e| BanWordFilterDao::FAIL_QUERY_ERROR.clone().cause(e.into()))?;
+
+        Ok(raw_ban_word_filter_infos
+            .into_iter()
+            .map(|raw_ban_word_filter_info| raw_ban_word_filter_info.into())
+            .collect())
+    }
+
+    /// Chat settings outside of the trash which use the filter.
+    #[instrument(skip(self))]
+    pub async fn get_usages(&self, id: &Uuid) -> AppResult<Vec<BanWordFilterUsage>> {
+        let usages = sqlx::query_as!(
+            BanWordFilterUsage,
+            r#"SELECT id as chat_settings_id, name as chat_settings_name FROM chat_settings WHERE ban_word_filter_id = $1 AND deleted_at IS NULL ORDER BY name, id"#,
+            id,
+        )
+        .fetch_all(self.pool.as_ref())
+        .await
+        .map_err(|e| BanWordFilterDao::FAIL_QUERY_ERROR.clone().cause(e.into()))?;
+
+        Ok(usages)
     }
 
     #[instrument(skip(self, update_ban_word_filter))]
@@ -102,15 +192,18 @@ impl BanWordFilterDao {
         &self,
         id: &Uuid,
         update_ban_word_filter: &UpdateBanWordFilter,
+        expected_revision: Option<i32>,
     ) -> AppResult<BanWordFilter> {
-        let previous_ban_words = self.ban_words(id).await?;
-
         let mut tx = self.pool.begin().await.map_err(|e| {
             BanWordFilterDao::FAIL_BEGIN_TRANSACTION_ERROR
                 .clone()
                 .cause(e.into())
         })?;
 
+        self.check_revision(id, expected_revision, &mut tx).await?;
+
+        let previous_ban_words = self.ban_words(id).await?;
+
         // region: create ban words
         let mut to_create_ban_words: Vec<String> = Vec::new();
         for ban_word in update_ban_word_filter.ban_words.clone() {
@@ -118,8 +211,8 @@ impl BanWordFilterDao {
                 to_create_ban_words.push(ban_word.clone());
             }
         }
-        if to_create_ban_words.len() > 0 {
-            self.create_ban_words(id, &to_create_ban_words, &mut *tx)
+        if !to_create_ban_words.is_empty() {
+            self.create_ban_words(id, &to_create_ban_words, &mut tx)
                 .await?;
         }
         // endregion
@@ -131,15 +224,15 @@ impl BanWordFilterDao {
                 to_delete_ban_words.push(ban_word.clone());
             }
         }
-        if to_delete_ban_words.len() > 0 {
-            self.delete_ban_words(id, &to_delete_ban_words, &mut *tx)
+        if !to_delete_ban_words.is_empty() {
+            self.delete_ban_words(id, &to_delete_ban_words, &mut tx)
                 .await?;
         }
         // endregion
 
         let raw_ban_word_filter = sqlx::query_as!(
             RawBanWordFilter,
-            r#"UPDATE ban_word_filters SET name = $1 WHERE id = $2 RETURNING id, name, user_id"#,
+            r#"UPDATE ban_word_filters SET name = $1, revision = revision + 1, updated_at = now() WHERE id = $2 RETURNING id, name, user_id, revision"#,
             update_ban_word_filter.name,
             id,
         )
@@ -163,16 +256,89 @@ impl BanWordFilterDao {
     }
 
     #[instrument(skip(self))]
-    pub async fn delete(&self, id: &Uuid) -> AppResult {
-        let rec = sqlx::query!(r#"DELETE FROM ban_word_filters WHERE id = $1"#, id,)
-            .execute(self.pool.as_ref())
-            .await
-            .map_err(|e| BanWordFilterDao::FAIL_QUERY_ERROR.clone().cause(e.into()))?;
+    pub async fn delete(&self, id: &Uuid, expected_revision: Option<i32>) -> AppResult {
+        let mut tx = self.pool.begin().await.map_err(|e| {
+            BanWordFilterDao::FAIL_BEGIN_TRANSACTION_ERROR
+                .clone()
+                .cause(e.into())
+        })?;
+
+        self.check_revision(id, expected_revision, &mut tx).await?;
+
+        sqlx::query!(
+            r#"UPDATE ban_word_filters SET deleted_at = now() WHERE id = $1"#,
+            id,
+        )
+        .execute(&mut *tx)
+        .await
+        .map_err(|e| BanWordFilterDao::FAIL_QUERY_ERROR.clone().cause(e.into()))?;
+
+        tx.commit().await.map_err(|e| {
+            BanWordFilterDao::FAIL_COMMIT_TRANSACTION_ERROR
+                .clone()
+                .cause(e.into())
+        })
+    }
+
+    /// Takes the filter out of the trash.
+    #[instrument(skip(self))]
+    pub async fn restore(&self, id: &Uuid) -> AppResult<BanWordFilter> {
+        let rec = sqlx::query!(
+            r#"UPDATE ban_word_filters SET deleted_at = NULL, revision = revision + 1, updated_at = now() WHERE id = $1 AND deleted_at IS NOT NULL"#,
+            id,
+        )
+        .execute(self.pool.as_ref())
+        .await
+        .map_err(|e| BanWordFilterDao::FAIL_QUERY_ERROR.clone().cause(e.into()))?;
 
         if rec.rows_affected() == 0 {
-            Err(BanWordFilterDao::NOT_FOUND_ERROR)
-        } else {
-            Ok(())
+            return Err(BanWordFilterDao::NOT_IN_TRASH_ERROR);
+        }
+
+        self.get(id).await
+    }
+
+    /// Deletes filters which are in the trash for longer than `retention_days`,
+    /// the foreign key detaches them from chat settings.
+    #[instrument(skip(self))]
+    pub async fn purge(&self, retention_days: i32) -> AppResult<u64> {
+        let rec = sqlx::query!(
+            r#"DELETE FROM ban_word_filters WHERE deleted_at < now() - make_interval(days => $1)"#,
+            retention_days,
+        )
+        .execute(self.pool.as_ref())
+        .await
+        .map_err(|e| BanWordFilterDao::FAIL_QUERY_ERROR.clone().cause(e.into()))?;
+
+        Ok(rec.rows_affected())
+    }
+
+    /// Locks the row until the end of the transaction, so the revision can't
+    /// change between the check and the write. Filters in the trash are not
+    /// found.
+    #[instrument(skip(self, conn))]
+    async fn check_revision(
+        &self,
+        id: &Uuid,
+        expected_revision: Option<i32>,
+        conn: &mut PgConnection,
+    ) -> AppResult {
+        let rec = sqlx::query!(
+            r#"SELECT revision FROM ban_word_filters WHERE id = $1 AND deleted_at IS NULL FOR UPDATE"#,
+            id,
+        )
+        .fetch_optional(conn)
+        .await
+        .map_err(|e| BanWordFilterDao::FAIL_QUERY_ERROR.clone().cause(e.into()))?;
+
+        match rec {
+            None => Err(BanWordFilterDao::NOT_FOUND_ERROR),
+            Some(rec) if expected_revision.is_some_and(|revision| revision != rec.revision) => {
+                Err(BanWordFilterDao::REVISION_MISMATCH_ERROR
+                    .clone()
+                    .other("revision".to_string(), rec.revision.into()))
+            }
+            Some(_) => Ok(()),
         }
     }
 
@@ -234,15 +400,17 @@ struct RawBanWordFilter {
     pub id: Uuid,
     pub name: String,
     pub user_id: String,
+    pub revision: i32,
 }
 
-impl Into<BanWordFilter> for RawBanWordFilter {
-    fn into(self) -> BanWordFilter {
+impl From<RawBanWordFilter> for BanWordFilter {
+    fn from(raw: RawBanWordFilter) -> Self {
         BanWordFilter {
-            id: self.id,
-            name: self.name,
+            id: raw.id,
+            name: raw.name,
             ban_words: Vec::new(),
-            user_id: self.user_id,
+            user_id: raw.user_id,
+            revision: raw.revision,
         }
     }
 }
@@ -251,14 +419,21 @@ struct RawBanWordFilterInfo {
     pub id: Uuid,
     pub name: String,
     pub user_id: String,
+    pub created_at: NaiveDateTime,
+    pub updated_at: NaiveDateTime,
+    pub deleted_at: Option<NaiveDateTime>,
+    pub sort_key: String,
 }
 
-impl Into<BanWordFilterInfo> for RawBanWordFilterInfo {
-    fn into(self) -> BanWordFilterInfo {
+impl From<RawBanWordFilterInfo> for BanWordFilterInfo {
+    fn from(raw: RawBanWordFilterInfo) -> Self {
         BanWordFilterInfo {
-            id: self.id,
-            name: self.name,
-            user_id: self.user_id,
+            id: raw.id,
+            name: raw.name,
+            user_id: raw.user_id,
+            created_at: raw.created_at,
+            updated_at: raw.updated_at,
+            deleted_at: raw.deleted_at,
         }
     }
 }
@@ -290,4 +465,148 @@ ban_word_filter_dao_errors! {
     (FAIL_COMMIT_TRANSACTION_ERROR, StatusCode::INTERNAL_SERVER_ERROR, "fail commit ban word filter transaction");
     (ID_TAKEN_ERROR, StatusCode::CONFLICT, "id taken");
     (NOT_FOUND_ERROR, StatusCode::NOT_FOUND, "ban word filter not found");
+    (NOT_IN_TRASH_ERROR, StatusCode::NOT_FOUND, "ban word filter not in trash");
+    (REVISION_MISMATCH_ERROR, StatusCode::PRECONDITION_FAILED, "ban word filter was changed");
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use sqlx::PgPool;
+
+    use types::domain::{BanWordFilterUsage, ChatType, UpdateChatSettings};
+
+    use crate::{BanWordFilterDao, ChatSettingsDao, UserDao};
+
+    #[sqlx::test(migrations = "../migrations")]
+    async fn trash_restore_and_purge(pool: PgPool) {
+        let pool = Arc::new(pool);
+        UserDao::new(pool.clone())
+            .get_or_create("1", "owner")
+            .await
+            .unwrap();
+        let ban_word_filter_dao = BanWordFilterDao::new(pool.clone());
+        let chat_settings_dao = ChatSettingsDao::new(pool.clone());
+
+        let ban_word_filter = ban_word_filter_dao.create("1", "filter").await.unwrap();
+        let chat_settings = chat_settings_dao
+            .create("1", "chat", &ChatType::Default)
+            .await
+            .unwrap();
+        let mut update_chat_settings = UpdateChatSettings::from(chat_settings.clone());
+        update_chat_settings.hide.ban_word_filter_id = Some(ban_word_filter.id);
+        chat_settings_dao
+            .update(&chat_settings.id, &update_chat_settings, None)
+            .await
+            .unwrap();
+
+        assert_eq!(
+            ban_word_filter_dao
+                .restore(&ban_word_filter.id)
+                .await
+                .unwrap_err()
+                .status_code,
+            BanWordFilterDao::NOT_IN_TRASH_ERROR.status_code
+        );
+
+        ban_word_filter_dao
+            .delete(&ban_word_filter.id, None)
+            .await
+            .unwrap();
+        assert_eq!(
+            ban_word_filter_dao
+                .get(&ban_word_filter.id)
+                .await
+                .unwrap_err()
+                .status_code,
+            BanWordFilterDao::NOT_FOUND_ERROR.status_code
+        );
+        let trash = ban_word_filter_dao.get_trash_by_user_id("1").await.unwrap();
+        assert_eq!(trash.len(), 1);
+        assert!(trash[0].deleted_at.is_some());
+
+        let restored = ban_word_filter_dao
+            .restore(&ban_word_filter.id)
+            .await
+            .unwrap();
+        assert_eq!(restored.revision, ban_word_filter.revision + 1);
+        assert!(ban_word_filter_dao
+            .get_trash_by_user_id("1")
+            .await
+            .unwrap()
+            .is_empty());
+
+        // Only filters in the trash for longer than the retention are purged.
+        ban_word_filter_dao
+            .delete(&ban_word_filter.id, None)
+            .await
+            .unwrap();
+        assert_eq!(ban_word_filter_dao.purge(30).await.unwrap(), 0);
+        sqlx::query("UPDATE ban_word_filters SET deleted_at = now() - interval '31 days'")
+            .execute(pool.as_ref())
+            .await
+            .unwrap();
+        assert_eq!(ban_word_filter_dao.purge(30).await.unwrap(), 1);
+
+        let chat_settings = chat_settings_dao.get(&chat_settings.id).await.unwrap();
+        assert_eq!(chat_settings.hide.ban_word_filter_id, None);
+    }
+
+    #[sqlx::test(migrations = "../migrations")]
+    async fn usages_skip_trashed_chat_settings(pool: PgPool) {
+        let pool = Arc::new(pool);
+        UserDao::new(pool.clone())
+            .get_or_create("1", "owner")
+            .await
+            .unwrap();
+        let ban_word_filter_dao = BanWordFilterDao::new(pool.clone());
+        let chat_settings_dao = ChatSettingsDao::new(pool);
+
+        let ban_word_filter = ban_word_filter_dao.create("1", "filter").await.unwrap();
+        let mut chat_settings_ids = Vec::new();
+        for name in ["b", "a", "unused"] {
+            let chat_settings = chat_settings_dao
+                .create("1", name, &ChatType::Default)
+                .await
+                .unwrap();
+            if name != "unused" {
+                let mut update_chat_settings = UpdateChatSettings::from(chat_settings.clone());
+                update_chat_settings.hide.ban_word_filter_id = Some(ban_word_filter.id);
+                chat_settings_dao
+                    .update(&chat_settings.id, &update_chat_settings, None)
+                    .await
+                    .unwrap();
+            }
+            chat_settings_ids.push(chat_settings.id);
+        }
+
+        assert_eq!(
+            ban_word_filter_dao
+                .get_usages(&ban_word_filter.id)
+                .await
+                .unwrap(),
+            vec![
+                BanWordFilterUsage {
+                    chat_settings_id: chat_settings_ids[1],
+                    chat_settings_name: "a".to_string(),
+                },
+                BanWordFilterUsage {
+                    chat_settings_id: chat_settings_ids[0],
+                    chat_settings_name: "b".to_string(),
+                },
+            ]
+        );
+
+        chat_settings_dao
+            .delete(&chat_settings_ids[1], None)
+            .await
+            .unwrap();
+        let usages = ban_word_filter_dao
+            .get_usages(&ban_word_filter.id)
+            .await
+            .unwrap();
+        assert_eq!(usages.len(), 1);
+        assert_eq!(usages[0].chat_settings_name, "b");
+    }
 }
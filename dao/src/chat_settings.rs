@@ -1,16 +1,24 @@
 use std::sync::Arc;
 
 use axum::http::StatusCode;
+use chrono::NaiveDateTime;
+use sqlx::types::{Json, JsonValue};
 use sqlx::{PgConnection, Pool, Postgres};
 use tracing::instrument;
 use uuid::Uuid;
 
 use types::domain::{
-    ChatColorSettings, ChatFontSettings, ChatHideSettings, ChatSettings, ChatSettingsInfo,
-    ChatSizeSettings, ChatType, CustomNickname, UpdateChatSettings,
+    ChatAnimation, ChatAnimationSettings, ChatColorSettings, ChatFontSettings, ChatHideSettings,
+    ChatSettings, ChatSettingsInfo, ChatSettingsVersion, ChatSettingsVersionInfo, ChatSizeSettings,
+    ChatType, ChatTypeOptions, CustomNickname, Page, PageRequest, PointReward, StackingDirection,
+    UnknownChatAnimation, UnknownChatType, UnknownStackingDirection, UnknownViewerGroup,
+    UpdateBanWordFilter, UpdateChatSettings, ViewerGroup, ViewerGroupRule,
 };
 use types::error::{AppError, AppResult};
 
+use crate::cursor::{Cursor, PageQuery};
+use crate::BanWordFilterDao;
+
 pub struct ChatSettingsDao {
     pool: Arc<Pool<Postgres>>,
 }
@@ -46,53 +54,265 @@ impl ChatSettingsDao {
         name: &str,
         chat_type: &ChatType,
     ) -> AppResult<ChatSettings> {
+        let mut tx = self.pool.begin().await.map_err(|e| {
+            ChatSettingsDao::FAIL_BEGIN_TRANSACTION_ERROR
+                .clone()
+                .cause(e.into())
+        })?;
+
+        let chat_settings = self.insert(user_id, name, chat_type, &mut tx).await?;
+
+        self.create_version(&chat_settings, &mut tx).await?;
+
+        tx.commit().await.map_err(|e| {
+            ChatSettingsDao::FAIL_COMMIT_TRANSACTION_ERROR
+                .clone()
+                .cause(e.into())
+        })?;
+
+        Ok(chat_settings)
+    }
+
+    /// Creates the settings with all values at once, instead of the defaults.
+    #[instrument(skip(self, update_chat_settings))]
+    pub async fn create_with_settings(
+        &self,
+        user_id: &str,
+        update_chat_settings: &UpdateChatSettings,
+    ) -> AppResult<ChatSettings> {
+        let mut tx = self.pool.begin().await.map_err(|e| {
+            ChatSettingsDao::FAIL_BEGIN_TRANSACTION_ERROR
+                .clone()
+                .cause(e.into())
+        })?;
+
+        let chat_settings = self
+            .create_with_settings_in_transaction(user_id, update_chat_settings, &mut tx)
+            .await?;
+
+        tx.commit().await.map_err(|e| {
+            ChatSettingsDao::FAIL_COMMIT_TRANSACTION_ERROR
+                .clone()
+                .cause(e.into())
+        })?;
+
+        Ok(chat_settings)
+    }
+
+    /// Creates the settings with a new ban word filter of the user in one
+    /// transaction, so a failure leaves no filter behind.
+    #[instrument(skip(self, update_chat_settings, update_ban_word_filter))]
+    pub async fn create_with_ban_word_filter(
+        &self,
+        user_id: &str,
+        update_chat_settings: &UpdateChatSettings,
+        update_ban_word_filter: &UpdateBanWordFilter,
+    ) -> AppResult<ChatSettings> {
+        let mut tx = self.pool.begin().await.map_err(|e| {
+            ChatSettingsDao::FAIL_BEGIN_TRANSACTION_ERROR
+                .clone()
+                .cause(e.into())
+        })?;
+
+        let ban_word_filter = BanWordFilterDao::new(self.pool.clone())
+            .create_in_transaction(user_id, update_ban_word_filter, &mut tx)
+            .await?;
+
+        let mut update_chat_settings = update_chat_settings.clone();
+        update_chat_settings.hide.ban_word_filter_id = Some(ban_word_filter.id);
+
+        let chat_settings = self
+            .create_with_settings_in_transaction(user_id, &update_chat_settings, &mut tx)
+            .await?;
+
+        tx.commit().await.map_err(|e| {
+            ChatSettingsDao::FAIL_COMMIT_TRANSACTION_ERROR
+                .clone()
+                .cause(e.into())
+        })?;
+
+        Ok(chat_settings)
+    }
+
+    /// Copies the settings with their nicknames and viewer group rules under a
+    /// new name. Point rewards and the ban word filter belong to the owner, so
+    /// they are copied only for `is_owner`.
+    #[instrument(skip(self))]
+    pub async fn create_clone(
+        &self,
+        id: &Uuid,
+        user_id: &str,
+        name: &str,
+        is_owner: bool,
+    ) -> AppResult<ChatSettings> {
+        let clone_id = Uuid::new_v4();
+
+        let mut tx = self.pool.begin().await.map_err(|e| {
+            ChatSettingsDao::FAIL_BEGIN_TRANSACTION_ERROR
+                .clone()
+                .cause(e.into())
+        })?;
+
         let raw_chat_settings = sqlx::query_as!(
             RawChatSettings,
-            r#"INSERT INTO chat_settings (id, name, chat_type, user_id) VALUES ($1, $2, $3, $4) RETURNING id, name, chat_type, nickname_color, background_color, text_color, gradient_only_for_custom_nicknames, margin_top, margin_right, margin_bottom, margin_left, padding_top, padding_right, padding_bottom, padding_left, border_top_left_radius, border_top_right_radius, border_bottom_left_radius, border_bottom_right_radius, max_messages, hide_message_pattern, hide_point_rewards, hide_links, link_replacement, ban_word_replacement, ban_word_filter_id, font_family, nickname_font_weight, text_font_weight, font_size, user_id"#,
-            Uuid::new_v4(),
+            r#"INSERT INTO chat_settings (id, name, user_id, chat_type, chat_type_options, nickname_color, background_color, text_color, gradient_only_for_custom_nicknames, margin_top, margin_right, margin_bottom, margin_left, padding_top, padding_right, padding_bottom, padding_left, border_top_left_radius, border_top_right_radius, border_bottom_left_radius, border_bottom_right_radius, max_messages, hide_message_pattern, hide_point_rewards, hide_links, link_replacement, ban_word_replacement, ban_word_filter_id, font_family, nickname_font_weight, text_font_weight, font_size, message_ttl, enter_animation, enter_duration, exit_animation, exit_duration, stacking_direction) SELECT $1, $2, $3, chat_type, chat_type_options, nickname_color, background_color, text_color, gradient_only_for_custom_nicknames, margin_top, margin_right, margin_bottom, margin_left, padding_top, padding_right, padding_bottom, padding_left, border_top_left_radius, border_top_right_radius, border_bottom_left_radius, border_bottom_right_radius, max_messages, hide_message_pattern, hide_point_rewards, hide_links, link_replacement, ban_word_replacement, CASE WHEN $5 THEN ban_word_filter_id END, font_family, nickname_font_weight, text_font_weight, font_size, message_ttl, enter_animation, enter_duration, exit_animation, exit_duration, stacking_direction FROM chat_settings WHERE id = $4 AND deleted_at IS NULL RETURNING id, name, chat_type, chat_type_options as "chat_type_options: Json<ChatTypeOptions>", nickname_color, background_color, text_color, gradient_only_for_custom_nicknames, margin_top, margin_right, margin_bottom, margin_left, padding_top, padding_right, padding_bottom, padding_left, border_top_left_radius, border_top_right_radius, border_bottom_left_radius, border_bottom_right_radius, max_messages, hide_message_pattern, hide_point_rewards, hide_links, link_replacement, ban_word_replacement, ban_word_filter_id, font_family, nickname_font_weight, text_font_weight, font_size, message_ttl, enter_animation, enter_duration, exit_animation, exit_duration, stacking_direction, user_id, revision"#,
+            clone_id,
             name,
-            chat_type.to_str(),
             user_id,
+            id,
+            is_owner,
         )
-            .fetch_one(self.pool.as_ref())
+            .fetch_optional(&mut *tx)
             .await
-            .map_err(|e| match e {
-                sqlx::Error::Database(dbe) if dbe.constraint() == Some("chat_settings_id_key") => {
-                    ChatSettingsDao::ID_TAKEN_ERROR
-                }
-                _ => ChatSettingsDao::FAIL_QUERY_ERROR.clone().cause(e.into()),
-            })?;
+            .map_err(|e| ChatSettingsDao::FAIL_QUERY_ERROR.clone().cause(e.into()))?
+            .ok_or(ChatSettingsDao::NOT_FOUND_ERROR)?;
+
+        let raw_custom_nicknames = sqlx::query_as!(
+            RawCustomNickname,
+            r#"INSERT INTO chat_custom_nicknames (chat_settings_id, nickname, user_id, start_color, middle_colors, end_color, display_name, badge_url, font_weight) SELECT $1, nickname, user_id, start_color, middle_colors, end_color, display_name, badge_url, font_weight FROM chat_custom_nicknames WHERE chat_settings_id = $2 RETURNING nickname, user_id, start_color, middle_colors as "middle_colors: Json<Vec<i64>>", end_color, display_name, badge_url, font_weight"#,
+            clone_id,
+            id,
+        )
+            .fetch_all(&mut *tx)
+            .await
+            .map_err(|e| ChatSettingsDao::FAIL_QUERY_ERROR.clone().cause(e.into()))?;
+
+        let hidden_nicknames = sqlx::query!(
+            r#"INSERT INTO chat_hidden_nicknames (chat_settings_id, nickname) SELECT $1, nickname FROM chat_hidden_nicknames WHERE chat_settings_id = $2 RETURNING nickname"#,
+            clone_id,
+            id,
+        )
+            .fetch_all(&mut *tx)
+            .await
+            .map_err(|e| ChatSettingsDao::FAIL_QUERY_ERROR.clone().cause(e.into()))?;
+
+        let raw_viewer_group_rules = sqlx::query_as!(
+            RawViewerGroupRule,
+            r#"INSERT INTO chat_viewer_group_rules (chat_settings_id, viewer_group, hidden, exempt_from_ban_words, nickname_color, background_color, font_weight) SELECT $1, viewer_group, hidden, exempt_from_ban_words, nickname_color, background_color, font_weight FROM chat_viewer_group_rules WHERE chat_settings_id = $2 RETURNING viewer_group, hidden, exempt_from_ban_words, nickname_color, background_color, font_weight"#,
+            clone_id,
+            id,
+        )
+            .fetch_all(&mut *tx)
+            .await
+            .map_err(|e| ChatSettingsDao::FAIL_QUERY_ERROR.clone().cause(e.into()))?;
+
+        let mut raw_point_rewards: Vec<RawPointReward> = Vec::new();
+        if is_owner {
+            raw_point_rewards = sqlx::query_as!(
+                RawPointReward,
+                r#"INSERT INTO chat_point_rewards (chat_settings_id, reward_id, hidden, highlight_color) SELECT $1, reward_id, hidden, highlight_color FROM chat_point_rewards WHERE chat_settings_id = $2 RETURNING reward_id, hidden, highlight_color"#,
+                clone_id,
+                id,
+            )
+                .fetch_all(&mut *tx)
+                .await
+                .map_err(|e| ChatSettingsDao::FAIL_QUERY_ERROR.clone().cause(e.into()))?;
+        }
+
+        let mut chat_settings: ChatSettings = raw_chat_settings.try_into()?;
+
+        for raw_custom_nickname in raw_custom_nicknames {
+            chat_settings
+                .color
+                .custom_nicknames
+                .push(raw_custom_nickname.into());
+        }
+        for rec in hidden_nicknames {
+            chat_settings.hide.nicknames.push(rec.nickname);
+        }
+        for raw_point_reward in raw_point_rewards {
+            chat_settings
+                .hide
+                .point_rewards
+                .push(raw_point_reward.into());
+        }
+        for raw_viewer_group_rule in raw_viewer_group_rules {
+            chat_settings
+                .hide
+                .viewer_groups
+                .push(raw_viewer_group_rule.try_into()?);
+        }
 
-        Ok(raw_chat_settings.into())
+        self.create_version(&chat_settings, &mut tx).await?;
+
+        tx.commit().await.map_err(|e| {
+            ChatSettingsDao::FAIL_COMMIT_TRANSACTION_ERROR
+                .clone()
+                .cause(e.into())
+        })?;
+
+        Ok(chat_settings)
     }
 
     #[instrument(skip(self))]
     pub async fn get(&self, id: &Uuid) -> AppResult<ChatSettings> {
         let raw_chat_settings = sqlx::query_as!(
             RawChatSettings,
-            r#"SELECT id, name, chat_type, nickname_color, background_color, text_color, gradient_only_for_custom_nicknames, margin_top, margin_right, margin_bottom, margin_left, padding_top, padding_right, padding_bottom, padding_left, border_top_left_radius, border_top_right_radius, border_bottom_left_radius, border_bottom_right_radius, max_messages, hide_message_pattern, hide_point_rewards, hide_links, link_replacement, ban_word_replacement, ban_word_filter_id, font_family, nickname_font_weight, text_font_weight, font_size, user_id FROM chat_settings WHERE id = $1 LIMIT 1"#,
+            r#"SELECT id, name, chat_type, chat_type_options as "chat_type_options: Json<ChatTypeOptions>", nickname_color, background_color, text_color, gradient_only_for_custom_nicknames, margin_top, margin_right, margin_bottom, margin_left, padding_top, padding_right, padding_bottom, padding_left, border_top_left_radius, border_top_right_radius, border_bottom_left_radius, border_bottom_right_radius, max_messages, hide_message_pattern, hide_point_rewards, hide_links, link_replacement, ban_word_replacement, ban_word_filter_id, font_family, nickname_font_weight, text_font_weight, font_size, message_ttl, enter_animation, enter_duration, exit_animation, exit_duration, stacking_direction, user_id, revision FROM chat_settings WHERE id = $1 AND deleted_at IS NULL LIMIT 1"#,
             id,
         )
-            .fetch_one(self.pool.as_ref())
+            .fetch_optional(self.pool.as_ref())
             .await
-            .map_err(|e| ChatSettingsDao::FAIL_QUERY_ERROR.clone().cause(e.into()))?;
+            .map_err(|e| ChatSettingsDao::FAIL_QUERY_ERROR.clone().cause(e.into()))?
+            .ok_or(ChatSettingsDao::NOT_FOUND_ERROR)?;
 
         let custom_nicknames = self.get_custom_nicknames(id).await?;
         let hidden_nicknames = self.get_hidden_nicknames(id).await?;
+        let point_rewards = self.get_point_rewards(id).await?;
+        let viewer_group_rules = self.get_viewer_group_rules(id).await?;
 
-        let mut chat_settings: ChatSettings = raw_chat_settings.into();
+        let mut chat_settings: ChatSettings = raw_chat_settings.try_into()?;
 
         chat_settings.color.custom_nicknames = custom_nicknames;
         chat_settings.hide.nicknames = hidden_nicknames;
+        chat_settings.hide.point_rewards = point_rewards;
+        chat_settings.hide.viewer_groups = viewer_group_rules;
 
         Ok(chat_settings)
     }
 
     #[instrument(skip(self))]
-    pub async fn get_all_by_user_id(&self, user_id: &str) -> AppResult<Vec<ChatSettingsInfo>> {
+    pub async fn get_all_by_user_id(
+        &self,
+        user_id: &str,
+        page_request: &PageRequest,
+    ) -> AppResult<Page<ChatSettingsInfo>> {
+        let page_query = PageQuery::new(page_request)?;
+
+        let raw_chat_settings_infos = sqlx::query_as!(
+            RawChatSettingsInfo,
+            r#"SELECT id as "id!", name as "name!", chat_type as "chat_type!", user_id as "user_id!", created_at as "created_at!", updated_at as "updated_at!", deleted_at, sort_key as "sort_key!" FROM (SELECT id, name, chat_type, user_id, created_at, updated_at, deleted_at, CASE $2::varchar WHEN 'name' THEN lower(name) WHEN 'updatedAt' THEN to_char(updated_at, 'YYYY-MM-DD HH24:MI:SS.US') ELSE to_char(created_at, 'YYYY-MM-DD HH24:MI:SS.US') END AS sort_key FROM chat_settings WHERE user_id = $1 AND deleted_at IS NULL AND ($3::varchar IS NULL OR name ILIKE $3)) AS page WHERE $4::varchar IS NULL OR CASE WHEN $5 THEN (sort_key, id) < ($4, $6::uuid) ELSE (sort_key, id) > ($4, $6::uuid) END ORDER BY CASE WHEN $5 THEN sort_key END DESC, CASE WHEN $5 THEN id END DESC, sort_key, id LIMIT $7"#,
+            user_id,
+            page_query.sort.to_str(),
+            page_query.search_pattern,
+            page_query.after.as_ref().map(|cursor| cursor.sort_key.clone()),
+            page_query.is_desc,
+            page_query.after.as_ref().map(|cursor| cursor.id),
+            page_query.limit,
+        )
+        .fetch_all(self.pool.as_ref())
+        .await
+        .map_err(|e| ChatSettingsDao::FAIL_QUERY_ERROR.clone().cause(e.into()))?;
+
+        let mut rows: Vec<(ChatSettingsInfo, Cursor)> = Vec::new();
+
+        for raw_chat_settings_info in raw_chat_settings_infos {
+            let cursor = Cursor {
+                sort_key: raw_chat_settings_info.sort_key.clone(),
+                id: raw_chat_settings_info.id,
+            };
+            rows.push((raw_chat_settings_info.try_into()?, cursor));
+        }
+
+        Ok(page_query.page(rows))
+    }
+
+    /// Newest deleted first.
+    #[instrument(skip(self))]
+    pub async fn get_trash_by_user_id(&self, user_id: &str) -> AppResult<Vec<ChatSettingsInfo>> {
         let raw_chat_settings_infos = sqlx::query_as!(
             RawChatSettingsInfo,
-            r#"SELECT id, name, chat_type, user_id FROM chat_settings WHERE user_id = $1"#,
+            r#"SELECT id, name, chat_type, user_id, created_at, updated_at, deleted_at, to_char(deleted_at, 'YYYY-MM-DD HH24:MI:SS.US') as "sort_key!" FROM chat_settings WHERE user_id = $1 AND deleted_at IS NOT NULL ORDER BY deleted_at DESC, id"#,
             user_id,
         )
         .fetch_all(self.pool.as_ref())
@@ -102,7 +322,7 @@ impl ChatSettingsDao {
         let mut chat_settings_infos: Vec<ChatSettingsInfo> = Vec::new();
 
         for raw_chat_settings_info in raw_chat_settings_infos {
-            chat_settings_infos.push(raw_chat_settings_info.into());
+            chat_settings_infos.push(raw_chat_settings_info.try_into()?);
         }
 
         Ok(chat_settings_infos)
@@ -113,6 +333,7 @@ impl ChatSettingsDao {
         &self,
         id: &Uuid,
         update_chat_settings: &UpdateChatSettings,
+        expected_revision: Option<i32>,
     ) -> AppResult<ChatSettings> {
         let mut tx = self.pool.begin().await.map_err(|e| {
             ChatSettingsDao::FAIL_BEGIN_TRANSACTION_ERROR
@@ -120,177 +341,871 @@ impl ChatSettingsDao {
                 .cause(e.into())
         })?;
 
-        let mut custom_nicknames: Vec<CustomNickname> = Vec::new();
+        self.check_revision(id, expected_revision, &mut tx).await?;
 
-        for nickname in update_chat_settings.color.custom_nicknames.clone() {
-            let custom_nickname = nickname.into();
-            custom_nicknames.push(custom_nickname);
-        }
-        self.calculate_and_update_custom_nicknames(id, &custom_nicknames, &mut *tx)
+        let chat_settings = self
+            .update_in_transaction(id, update_chat_settings, &mut tx)
             .await?;
 
-        self.calculate_and_update_hidden_nicknames(
+        self.create_version(&chat_settings, &mut tx).await?;
+
+        tx.commit().await.map_err(|e| {
+            ChatSettingsDao::FAIL_COMMIT_TRANSACTION_ERROR
+                .clone()
+                .cause(e.into())
+        })?;
+
+        Ok(chat_settings)
+    }
+
+    #[instrument(skip(self))]
+    pub async fn get_versions(&self, id: &Uuid) -> AppResult<Vec<ChatSettingsVersionInfo>> {
+        let raw_versions = sqlx::query_as!(
+            RawChatSettingsVersionInfo,
+            r#"SELECT chat_settings_id, version, settings->>'name' as "name!", created_at FROM chat_settings_versions WHERE chat_settings_id = $1 ORDER BY version DESC"#,
             id,
-            &update_chat_settings.hide.nicknames,
-            &mut *tx,
         )
-        .await?;
+            .fetch_all(self.pool.as_ref())
+            .await
+            .map_err(|e| ChatSettingsDao::FAIL_QUERY_ERROR.clone().cause(e.into()))?;
 
-        let raw_chat_settings = sqlx::query_as!(
-            RawChatSettings,
-            r#"UPDATE chat_settings SET (name, chat_type, nickname_color, background_color, text_color, gradient_only_for_custom_nicknames, margin_top, margin_right, margin_bottom, margin_left, padding_top, padding_right, padding_bottom, padding_left, border_top_left_radius, border_top_right_radius, border_bottom_left_radius, border_bottom_right_radius, max_messages, hide_message_pattern, hide_point_rewards, hide_links, link_replacement, ban_word_replacement, ban_word_filter_id, font_family, nickname_font_weight, text_font_weight, font_size) = ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, $12, $13, $14, $15, $16, $17, $18, $19, $20, $21, $22, $23, $24, $25, $26, $27, $28, $29) WHERE id = $30 RETURNING id, name, chat_type, nickname_color, background_color, text_color, gradient_only_for_custom_nicknames, margin_top, margin_right, margin_bottom, margin_left, padding_top, padding_right, padding_bottom, padding_left, border_top_left_radius, border_top_right_radius, border_bottom_left_radius, border_bottom_right_radius, max_messages, hide_message_pattern, hide_point_rewards, hide_links, link_replacement, ban_word_replacement, ban_word_filter_id, font_family, nickname_font_weight, text_font_weight, font_size, user_id"#,
-            update_chat_settings.name,
-            update_chat_settings.chat_type.to_str(),
-            update_chat_settings.color.nickname_color,
-            update_chat_settings.color.background_color,
-            update_chat_settings.color.text_color,
-            update_chat_settings.color.gradient_only_for_custom_nicknames,
-            update_chat_settings.size.margin_top,
-            update_chat_settings.size.margin_right,
-            update_chat_settings.size.margin_bottom,
-            update_chat_settings.size.margin_left,
-            update_chat_settings.size.padding_top,
-            update_chat_settings.size.padding_right,
-            update_chat_settings.size.padding_bottom,
-            update_chat_settings.size.padding_left,
-            update_chat_settings.size.border_top_left_radius,
-            update_chat_settings.size.border_top_right_radius,
-            update_chat_settings.size.border_bottom_left_radius,
-            update_chat_settings.size.border_bottom_right_radius,
-            update_chat_settings.size.max_messages,
-            update_chat_settings.hide.hide_message_pattern,
-            update_chat_settings.hide.hide_point_rewards,
-            update_chat_settings.hide.hide_links,
-            update_chat_settings.hide.link_replacement,
-            update_chat_settings.hide.ban_word_replacement,
-            update_chat_settings.hide.ban_word_filter_id,
-            update_chat_settings.font.font_family,
-            update_chat_settings.font.nickname_font_weight,
-            update_chat_settings.font.text_font_weight,
-            update_chat_settings.font.font_size,
-            id
-        )
-            .fetch_one(&mut *tx)
+        let mut versions: Vec<ChatSettingsVersionInfo> = Vec::new();
+
+        for raw_version in raw_versions {
+            versions.push(raw_version.into());
+        }
+
+        Ok(versions)
+    }
+
+    #[instrument(skip(self))]
+    pub async fn get_version(&self, id: &Uuid, version: i32) -> AppResult<ChatSettingsVersion> {
+        let mut conn = self
+            .pool
+            .acquire()
             .await
             .map_err(|e| ChatSettingsDao::FAIL_QUERY_ERROR.clone().cause(e.into()))?;
 
-        tx.commit().await.map_err(|e| {
-            ChatSettingsDao::FAIL_COMMIT_TRANSACTION_ERROR
+        self.get_version_in_transaction(id, version, &mut conn)
+            .await
+    }
+
+    /// Saves the settings of the version again, as a new version.
+    #[instrument(skip(self))]
+    pub async fn restore_version(
+        &self,
+        id: &Uuid,
+        version: i32,
+        expected_revision: Option<i32>,
+    ) -> AppResult<ChatSettings> {
+        let mut tx = self.pool.begin().await.map_err(|e| {
+            ChatSettingsDao::FAIL_BEGIN_TRANSACTION_ERROR
                 .clone()
                 .cause(e.into())
         })?;
 
-        let mut chat_settings: ChatSettings = raw_chat_settings.into();
+        self.check_revision(id, expected_revision, &mut tx).await?;
 
-        chat_settings.color.custom_nicknames = custom_nicknames;
-        chat_settings.hide.nicknames = update_chat_settings.hide.nicknames.clone();
+        let snapshot = self
+            .get_version_in_transaction(id, version, &mut tx)
+            .await?;
+        let mut update_chat_settings: UpdateChatSettings = snapshot.settings.into();
+
+        // Filters deleted since then are detached, like the foreign key did.
+        if let Some(ban_word_filter_id) = update_chat_settings.hide.ban_word_filter_id {
+            if !self
+                .is_ban_word_filter_exists(&ban_word_filter_id, &mut tx)
+                .await?
+            {
+                update_chat_settings.hide.ban_word_filter_id = None;
+            }
+        }
+
+        let chat_settings = self
+            .update_in_transaction(id, &update_chat_settings, &mut tx)
+            .await?;
+
+        self.create_version(&chat_settings, &mut tx).await?;
+
+        tx.commit().await.map_err(|e| {
+            ChatSettingsDao::FAIL_COMMIT_TRANSACTION_ERROR
+                .clone()
+                .cause(e.into())
+        })?;
 
         Ok(chat_settings)
     }
 
-    #[instrument(skip(self))]
-    pub async fn delete(&self, id: &Uuid) -> AppResult {
-        let rec = sqlx::query!(r#"DELETE FROM chat_settings WHERE id = $1"#, id,)
-            .execute(self.pool.as_ref())
+    /// Applies `patch` to the settings read under the row lock and writes only
+    /// the columns and list entries which differ, so changes of other editors
+    /// are neither lost nor overwritten.
+    #[instrument(skip(self, patch))]
+    pub async fn patch(
+        &self,
+        id: &Uuid,
+        patch: impl FnOnce(&UpdateChatSettings) -> AppResult<UpdateChatSettings>,
+        expected_revision: Option<i32>,
+    ) -> AppResult<ChatSettings> {
+        let mut tx = self.pool.begin().await.map_err(|e| {
+            ChatSettingsDao::FAIL_BEGIN_TRANSACTION_ERROR
+                .clone()
+                .cause(e.into())
+        })?;
+
+        self.check_revision(id, expected_revision, &mut tx).await?;
+
+        let previous: UpdateChatSettings = self.get(id).await?.into();
+        let patched = patch(&previous)?;
+
+        let custom_nicknames: Option<Vec<CustomNickname>> = changed(
+            &previous.color.custom_nicknames,
+            &patched.color.custom_nicknames,
+        )
+        .map(|nicknames| nicknames.iter().cloned().map(Into::into).collect());
+        if let Some(custom_nicknames) = &custom_nicknames {
+            self.calculate_and_update_custom_nicknames(id, custom_nicknames, &mut tx)
+                .await?;
+        }
+
+        let hidden_nicknames = changed(&previous.hide.nicknames, &patched.hide.nicknames).cloned();
+        if let Some(hidden_nicknames) = &hidden_nicknames {
+            self.calculate_and_update_hidden_nicknames(id, hidden_nicknames, &mut tx)
+                .await?;
+        }
+
+        let point_rewards: Option<Vec<PointReward>> =
+            changed(&previous.hide.point_rewards, &patched.hide.point_rewards)
+                .map(|point_rewards| point_rewards.iter().cloned().map(Into::into).collect());
+        if let Some(point_rewards) = &point_rewards {
+            self.calculate_and_update_point_rewards(id, point_rewards, &mut tx)
+                .await?;
+        }
+
+        let viewer_group_rules: Option<Vec<ViewerGroupRule>> =
+            changed(&previous.hide.viewer_groups, &patched.hide.viewer_groups)
+                .map(|rules| rules.iter().cloned().map(Into::into).collect());
+        if let Some(viewer_group_rules) = &viewer_group_rules {
+            self.calculate_and_update_viewer_group_rules(id, viewer_group_rules, &mut tx)
+                .await?;
+        }
+
+        // Unchanged columns are passed as null and keep their value, the
+        // filter is nullable, so it's set by the flag instead.
+        let raw_chat_settings = sqlx::query_as!(
+            RawChatSettings,
+            r#"UPDATE chat_settings SET name = coalesce($1, name), chat_type = coalesce($2, chat_type), chat_type_options = coalesce($3, chat_type_options), nickname_color = coalesce($4, nickname_color), background_color = coalesce($5, background_color), text_color = coalesce($6, text_color), gradient_only_for_custom_nicknames = coalesce($7, gradient_only_for_custom_nicknames), margin_top = coalesce($8, margin_top), margin_right = coalesce($9, margin_right), margin_bottom = coalesce($10, margin_bottom), margin_left = coalesce($11, margin_left), padding_top = coalesce($12, padding_top), padding_right = coalesce($13, padding_right), padding_bottom = coalesce($14, padding_bottom), padding_left = coalesce($15, padding_left), border_top_left_radius = coalesce($16, border_top_left_radius), border_top_right_radius = coalesce($17, border_top_right_radius), border_bottom_left_radius = coalesce($18, border_bottom_left_radius), border_bottom_right_radius = coalesce($19, border_bottom_right_radius), max_messages = coalesce($20, max_messages), hide_message_pattern = coalesce($21, hide_message_pattern), hide_point_rewards = coalesce($22, hide_point_rewards), hide_links = coalesce($23, hide_links), link_replacement = coalesce($24, link_replacement), ban_word_replacement = coalesce($25, ban_word_replacement), ban_word_filter_id = CASE WHEN $26 THEN $27 ELSE ban_word_filter_id END, font_family = coalesce($28, font_family), nickname_font_weight = coalesce($29, nickname_font_weight), text_font_weight = coalesce($30, text_font_weight), font_size = coalesce($31, font_size), message_ttl = coalesce($32, message_ttl), enter_animation = coalesce($33, enter_animation), enter_duration = coalesce($34, enter_duration), exit_animation = coalesce($35, exit_animation), exit_duration = coalesce($36, exit_duration), stacking_direction = coalesce($37, stacking_direction), revision = revision + 1, updated_at = now() WHERE id = $38 RETURNING id, name, chat_type, chat_type_options as "chat_type_options: Json<ChatTypeOptions>", nickname_color, background_color, text_color, gradient_only_for_custom_nicknames, margin_top, margin_right, margin_bottom, margin_left, padding_top, padding_right, padding_bottom, padding_left, border_top_left_radius, border_top_right_radius, border_bottom_left_radius, border_bottom_right_radius, max_messages, hide_message_pattern, hide_point_rewards, hide_links, link_replacement, ban_word_replacement, ban_word_filter_id, font_family, nickname_font_weight, text_font_weight, font_size, message_ttl, enter_animation, enter_duration, exit_animation, exit_duration, stacking_direction, user_id, revision"#,
+            changed(&previous.name, &patched.name),
+            changed(&previous.chat_type, &patched.chat_type).map(|v| v.to_str()),
+            changed(&previous.chat_type_options, &patched.chat_type_options).map(Json) as _,
+            changed(&previous.color.nickname_color, &patched.color.nickname_color),
+            changed(&previous.color.background_color, &patched.color.background_color),
+            changed(&previous.color.text_color, &patched.color.text_color),
+            changed(&previous.color.gradient_only_for_custom_nicknames, &patched.color.gradient_only_for_custom_nicknames),
+            changed(&previous.size.margin_top, &patched.size.margin_top),
+            changed(&previous.size.margin_right, &patched.size.margin_right),
+            changed(&previous.size.margin_bottom, &patched.size.margin_bottom),
+            changed(&previous.size.margin_left, &patched.size.margin_left),
+            changed(&previous.size.padding_top, &patched.size.padding_top),
+            changed(&previous.size.padding_right, &patched.size.padding_right),
+            changed(&previous.size.padding_bottom, &patched.size.padding_bottom),
+            changed(&previous.size.padding_left, &patched.size.padding_left),
+            changed(&previous.size.border_top_left_radius, &patched.size.border_top_left_radius),
+            changed(&previous.size.border_top_right_radius, &patched.size.border_top_right_radius),
+            changed(&previous.size.border_bottom_left_radius, &patched.size.border_bottom_left_radius),
+            changed(&previous.size.border_bottom_right_radius, &patched.size.border_bottom_right_radius),
+            changed(&previous.size.max_messages, &patched.size.max_messages),
+            changed(&previous.hide.hide_message_pattern, &patched.hide.hide_message_pattern),
+            changed(&previous.hide.hide_point_rewards, &patched.hide.hide_point_rewards),
+            changed(&previous.hide.hide_links, &patched.hide.hide_links),
+            changed(&previous.hide.link_replacement, &patched.hide.link_replacement),
+            changed(&previous.hide.ban_word_replacement, &patched.hide.ban_word_replacement),
+            previous.hide.ban_word_filter_id != patched.hide.ban_word_filter_id,
+            patched.hide.ban_word_filter_id,
+            changed(&previous.font.font_family, &patched.font.font_family),
+            changed(&previous.font.nickname_font_weight, &patched.font.nickname_font_weight),
+            changed(&previous.font.text_font_weight, &patched.font.text_font_weight),
+            changed(&previous.font.font_size, &patched.font.font_size),
+            changed(&previous.animation.message_ttl, &patched.animation.message_ttl),
+            changed(&previous.animation.enter_animation, &patched.animation.enter_animation).map(|v| v.to_str()),
+            changed(&previous.animation.enter_duration, &patched.animation.enter_duration),
+            changed(&previous.animation.exit_animation, &patched.animation.exit_animation).map(|v| v.to_str()),
+            changed(&previous.animation.exit_duration, &patched.animation.exit_duration),
+            changed(&previous.animation.stacking_direction, &patched.animation.stacking_direction).map(|v| v.to_str()),
+            id
+        )
+            .fetch_one(&mut *tx)
             .await
             .map_err(|e| ChatSettingsDao::FAIL_QUERY_ERROR.clone().cause(e.into()))?;
 
-        if rec.rows_affected() == 0 {
-            Err(ChatSettingsDao::NOT_FOUND_ERROR)
-        } else {
-            Ok(())
+        let mut chat_settings: ChatSettings = raw_chat_settings.try_into()?;
+
+        chat_settings.color.custom_nicknames = match custom_nicknames {
+            Some(custom_nicknames) => custom_nicknames,
+            None => self.get_custom_nicknames(id).await?,
+        };
+        chat_settings.hide.nicknames = match hidden_nicknames {
+            Some(hidden_nicknames) => hidden_nicknames,
+            None => self.get_hidden_nicknames(id).await?,
+        };
+        chat_settings.hide.point_rewards = match point_rewards {
+            Some(point_rewards) => point_rewards,
+            None => self.get_point_rewards(id).await?,
+        };
+        chat_settings.hide.viewer_groups = match viewer_group_rules {
+            Some(viewer_group_rules) => viewer_group_rules,
+            None => self.get_viewer_group_rules(id).await?,
+        };
+
+        self.create_version(&chat_settings, &mut tx).await?;
+
+        tx.commit().await.map_err(|e| {
+            ChatSettingsDao::FAIL_COMMIT_TRANSACTION_ERROR
+                .clone()
+                .cause(e.into())
+        })?;
+
+        Ok(chat_settings)
+    }
+
+    #[instrument(skip(self, update_chat_settings, conn))]
+    async fn update_in_transaction(
+        &self,
+        id: &Uuid,
+        update_chat_settings: &UpdateChatSettings,
+        conn: &mut PgConnection,
+    ) -> AppResult<ChatSettings> {
+        let mut custom_nicknames: Vec<CustomNickname> = Vec::new();
+
+        for nickname in update_chat_settings.color.custom_nicknames.clone() {
+            let custom_nickname = nickname.into();
+            custom_nicknames.push(custom_nickname);
+        }
+        self.calculate_and_update_custom_nicknames(id, &custom_nicknames, conn)
+            .await?;
+
+        self.calculate_and_update_hidden_nicknames(id, &update_chat_settings.hide.nicknames, conn)
+            .await?;
+
+        let mut point_rewards: Vec<PointReward> = Vec::new();
+
+        for point_reward in update_chat_settings.hide.point_rewards.clone() {
+            point_rewards.push(point_reward.into());
+        }
+        self.calculate_and_update_point_rewards(id, &point_rewards, conn)
+            .await?;
+
+        let mut viewer_group_rules: Vec<ViewerGroupRule> = Vec::new();
+
+        for viewer_group_rule in update_chat_settings.hide.viewer_groups.clone() {
+            viewer_group_rules.push(viewer_group_rule.into());
+        }
+        self.calculate_and_update_viewer_group_rules(id, &viewer_group_rules, conn)
+            .await?;
+
+        let raw_chat_settings = sqlx::query_as!(
+            RawChatSettings,
+            r#"UPDATE chat_settings SET (name, chat_type, chat_type_options, nickname_color, background_color, text_color, gradient_only_for_custom_nicknames, margin_top, margin_right, margin_bottom, margin_left, padding_top, padding_right, padding_bottom, padding_left, border_top_left_radius, border_top_right_radius, border_bottom_left_radius, border_bottom_right_radius, max_messages, hide_message_pattern, hide_point_rewards, hide_links, link_replacement, ban_word_replacement, ban_word_filter_id, font_family, nickname_font_weight, text_font_weight, font_size, message_ttl, enter_animation, enter_duration, exit_animation, exit_duration, stacking_direction, revision, updated_at) = ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, $12, $13, $14, $15, $16, $17, $18, $19, $20, $21, $22, $23, $24, $25, $26, $27, $28, $29, $30, $31, $32, $33, $34, $35, $36, revision + 1, now()) WHERE id = $37 RETURNING id, name, chat_type, chat_type_options as "chat_type_options: Json<ChatTypeOptions>", nickname_color, background_color, text_color, gradient_only_for_custom_nicknames, margin_top, margin_right, margin_bottom, margin_left, padding_top, padding_right, padding_bottom, padding_left, border_top_left_radius, border_top_right_radius, border_bottom_left_radius, border_bottom_right_radius, max_messages, hide_message_pattern, hide_point_rewards, hide_links, link_replacement, ban_word_replacement, ban_word_filter_id, font_family, nickname_font_weight, text_font_weight, font_size, message_ttl, enter_animation, enter_duration, exit_animation, exit_duration, stacking_direction, user_id, revision"#,
+            update_chat_settings.name,
+            update_chat_settings.chat_type.to_str(),
+            Json(&update_chat_settings.chat_type_options) as _,
+            update_chat_settings.color.nickname_color,
+            update_chat_settings.color.background_color,
+            update_chat_settings.color.text_color,
+            update_chat_settings.color.gradient_only_for_custom_nicknames,
+            update_chat_settings.size.margin_top,
+            update_chat_settings.size.margin_right,
+            update_chat_settings.size.margin_bottom,
+            update_chat_settings.size.margin_left,
+            update_chat_settings.size.padding_top,
+            update_chat_settings.size.padding_right,
+            update_chat_settings.size.padding_bottom,
+            update_chat_settings.size.padding_left,
+            update_chat_settings.size.border_top_left_radius,
+            update_chat_settings.size.border_top_right_radius,
+            update_chat_settings.size.border_bottom_left_radius,
+            update_chat_settings.size.border_bottom_right_radius,
+            update_chat_settings.size.max_messages,
+            update_chat_settings.hide.hide_message_pattern,
+            update_chat_settings.hide.hide_point_rewards,
+            update_chat_settings.hide.hide_links,
+            update_chat_settings.hide.link_replacement,
+            update_chat_settings.hide.ban_word_replacement,
+            update_chat_settings.hide.ban_word_filter_id,
+            update_chat_settings.font.font_family,
+            update_chat_settings.font.nickname_font_weight,
+            update_chat_settings.font.text_font_weight,
+            update_chat_settings.font.font_size,
+            update_chat_settings.animation.message_ttl,
+            update_chat_settings.animation.enter_animation.to_str(),
+            update_chat_settings.animation.enter_duration,
+            update_chat_settings.animation.exit_animation.to_str(),
+            update_chat_settings.animation.exit_duration,
+            update_chat_settings.animation.stacking_direction.to_str(),
+            id
+        )
+            .fetch_one(conn)
+            .await
+            .map_err(|e| ChatSettingsDao::FAIL_QUERY_ERROR.clone().cause(e.into()))?;
+
+        let mut chat_settings: ChatSettings = raw_chat_settings.try_into()?;
+
+        chat_settings.color.custom_nicknames = custom_nicknames;
+        chat_settings.hide.nicknames = update_chat_settings.hide.nicknames.clone();
+        chat_settings.hide.point_rewards = point_rewards;
+        chat_settings.hide.viewer_groups = viewer_group_rules;
+
+        Ok(chat_settings)
+    }
+
+    #[instrument(skip(self))]
+    pub async fn delete(&self, id: &Uuid, expected_revision: Option<i32>) -> AppResult {
+        let mut tx = self.pool.begin().await.map_err(|e| {
+            ChatSettingsDao::FAIL_BEGIN_TRANSACTION_ERROR
+                .clone()
+                .cause(e.into())
+        })?;
+
+        self.check_revision(id, expected_revision, &mut tx).await?;
+
+        sqlx::query!(
+            r#"UPDATE chat_settings SET deleted_at = now() WHERE id = $1"#,
+            id,
+        )
+        .execute(&mut *tx)
+        .await
+        .map_err(|e| ChatSettingsDao::FAIL_QUERY_ERROR.clone().cause(e.into()))?;
+
+        tx.commit().await.map_err(|e| {
+            ChatSettingsDao::FAIL_COMMIT_TRANSACTION_ERROR
+                .clone()
+                .cause(e.into())
+        })
+    }
+
+    /// Takes the chat settings out of the trash.
+    #[instrument(skip(self))]
+    pub async fn restore(&self, id: &Uuid) -> AppResult<ChatSettings> {
+        let rec = sqlx::query!(
+            r#"UPDATE chat_settings SET deleted_at = NULL, revision = revision + 1, updated_at = now() WHERE id = $1 AND deleted_at IS NOT NULL"#,
+            id,
+        )
+        .execute(self.pool.as_ref())
+        .await
+        .map_err(|e| ChatSettingsDao::FAIL_QUERY_ERROR.clone().cause(e.into()))?;
+
+        if rec.rows_affected() == 0 {
+            return Err(ChatSettingsDao::NOT_IN_TRASH_ERROR);
+        }
+
+        self.get(id).await
+    }
+
+    /// Deletes chat settings which are in the trash for longer than `retention_days`.
+    #[instrument(skip(self))]
+    pub async fn purge(&self, retention_days: i32) -> AppResult<u64> {
+        let rec = sqlx::query!(
+            r#"DELETE FROM chat_settings WHERE deleted_at < now() - make_interval(days => $1)"#,
+            retention_days,
+        )
+        .execute(self.pool.as_ref())
+        .await
+        .map_err(|e| ChatSettingsDao::FAIL_QUERY_ERROR.clone().cause(e.into()))?;
+
+        Ok(rec.rows_affected())
+    }
+
+    /// Locks the row until the end of the transaction, so the revision can't
+    /// change between the check and the write. Without an expected revision
+    /// any revision is overwritten. Chat settings in the trash are not found.
+    #[instrument(skip(self, conn))]
+    async fn check_revision(
+        &self,
+        id: &Uuid,
+        expected_revision: Option<i32>,
+        conn: &mut PgConnection,
+    ) -> AppResult {
+        let rec = sqlx::query!(
+            r#"SELECT revision FROM chat_settings WHERE id = $1 AND deleted_at IS NULL FOR UPDATE"#,
+            id,
+        )
+        .fetch_optional(conn)
+        .await
+        .map_err(|e| ChatSettingsDao::FAIL_QUERY_ERROR.clone().cause(e.into()))?;
+
+        match rec {
+            None => Err(ChatSettingsDao::NOT_FOUND_ERROR),
+            Some(rec) if expected_revision.is_some_and(|revision| revision != rec.revision) => {
+                Err(ChatSettingsDao::REVISION_MISMATCH_ERROR
+                    .clone()
+                    .other("revision".to_string(), rec.revision.into()))
+            }
+            Some(_) => Ok(()),
+        }
+    }
+
+    #[instrument(skip(self, conn))]
+    async fn insert(
+        &self,
+        user_id: &str,
+        name: &str,
+        chat_type: &ChatType,
+        conn: &mut PgConnection,
+    ) -> AppResult<ChatSettings> {
+        let raw_chat_settings = sqlx::query_as!(
+            RawChatSettings,
+            r#"INSERT INTO chat_settings (id, name, chat_type, user_id) VALUES ($1, $2, $3, $4) RETURNING id, name, chat_type, chat_type_options as "chat_type_options: Json<ChatTypeOptions>", nickname_color, background_color, text_color, gradient_only_for_custom_nicknames, margin_top, margin_right, margin_bottom, margin_left, padding_top, padding_right, padding_bottom, padding_left, border_top_left_radius, border_top_right_radius, border_bottom_left_radius, border_bottom_right_radius, max_messages, hide_message_pattern, hide_point_rewards, hide_links, link_replacement, ban_word_replacement, ban_word_filter_id, font_family, nickname_font_weight, text_font_weight, font_size, message_ttl, enter_animation, enter_duration, exit_animation, exit_duration, stacking_direction, user_id, revision"#,
+            Uuid::new_v4(),
+            name,
+            chat_type.to_str(),
+            user_id,
+        )
+            .fetch_one(conn)
+            .await
+            .map_err(|e| match e {
+                sqlx::Error::Database(dbe) if dbe.constraint() == Some("chat_settings_id_key") => {
+                    ChatSettingsDao::ID_TAKEN_ERROR
+                }
+                _ => ChatSettingsDao::FAIL_QUERY_ERROR.clone().cause(e.into()),
+            })?;
+
+        raw_chat_settings.try_into()
+    }
+
+    #[instrument(skip(self, conn))]
+    async fn get_version_in_transaction(
+        &self,
+        id: &Uuid,
+        version: i32,
+        conn: &mut PgConnection,
+    ) -> AppResult<ChatSettingsVersion> {
+        let raw_version = sqlx::query_as!(
+            RawChatSettingsVersion,
+            r#"SELECT chat_settings_id, version, settings as "settings: Json<ChatSettings>", created_at FROM chat_settings_versions WHERE chat_settings_id = $1 AND version = $2 LIMIT 1"#,
+            id,
+            version,
+        )
+            .fetch_optional(conn)
+            .await
+            .map_err(|e| ChatSettingsDao::FAIL_QUERY_ERROR.clone().cause(e.into()))?;
+
+        match raw_version {
+            Some(raw_version) => Ok(raw_version.into()),
+            None => Err(ChatSettingsDao::VERSION_NOT_FOUND_ERROR),
+        }
+    }
+
+    /// Versions are numbered after the row is locked by the update, so
+    /// concurrent updates can't take the same number.
+    #[instrument(skip(self, chat_settings, conn))]
+    async fn create_version(
+        &self,
+        chat_settings: &ChatSettings,
+        conn: &mut PgConnection,
+    ) -> AppResult {
+        sqlx::query!(
+            r#"INSERT INTO chat_settings_versions (chat_settings_id, version, settings) SELECT $1, coalesce(max(version), 0) + 1, $2 FROM chat_settings_versions WHERE chat_settings_id = $1"#,
+            chat_settings.id,
+            Json(chat_settings) as _,
+        )
+            .execute(conn)
+            .await
+            .map_err(|e| ChatSettingsDao::FAIL_QUERY_ERROR.clone().cause(e.into()))?;
+
+        Ok(())
+    }
+
+    #[instrument(skip(self, update_chat_settings, conn))]
+    async fn create_with_settings_in_transaction(
+        &self,
+        user_id: &str,
+        update_chat_settings: &UpdateChatSettings,
+        conn: &mut PgConnection,
+    ) -> AppResult<ChatSettings> {
+        let created = self
+            .insert(
+                user_id,
+                &update_chat_settings.name,
+                &update_chat_settings.chat_type,
+                conn,
+            )
+            .await?;
+
+        let chat_settings = self
+            .update_in_transaction(&created.id, update_chat_settings, conn)
+            .await?;
+
+        self.create_version(&chat_settings, conn).await?;
+
+        Ok(chat_settings)
+    }
+
+    #[instrument(skip(self, conn))]
+    async fn is_ban_word_filter_exists(
+        &self,
+        ban_word_filter_id: &Uuid,
+        conn: &mut PgConnection,
+    ) -> AppResult<bool> {
+        let rec = sqlx::query!(
+            r#"SELECT count(id) FROM ban_word_filters WHERE id = $1 LIMIT 1"#,
+            ban_word_filter_id,
+        )
+        .fetch_one(conn)
+        .await
+        .map_err(|e| ChatSettingsDao::FAIL_QUERY_ERROR.clone().cause(e.into()))?;
+
+        Ok(rec.count.unwrap_or(0) > 0)
+    }
+
+    #[instrument(skip(self))]
+    async fn get_custom_nicknames(&self, id: &Uuid) -> AppResult<Vec<CustomNickname>> {
+        let raw_custom_nicknames = sqlx::query_as!(
+            RawCustomNickname,
+            r#"SELECT nickname, user_id, start_color, middle_colors as "middle_colors: Json<Vec<i64>>", end_color, display_name, badge_url, font_weight FROM chat_custom_nicknames WHERE chat_settings_id = $1"#,
+            id,
+        )
+            .fetch_all(self.pool.as_ref())
+            .await
+            .map_err(|e| ChatSettingsDao::FAIL_QUERY_ERROR.clone().cause(e.into()))?;
+
+        let mut custom_nicknames: Vec<CustomNickname> = Vec::new();
+
+        for raw_custom_nickname in raw_custom_nicknames {
+            custom_nicknames.push(raw_custom_nickname.into());
+        }
+
+        Ok(custom_nicknames)
+    }
+
+    #[instrument(skip(self))]
+    async fn get_hidden_nicknames(&self, id: &Uuid) -> AppResult<Vec<String>> {
+        let recs = sqlx::query!(
+            r#"SELECT nickname FROM chat_hidden_nicknames WHERE chat_settings_id = $1"#,
+            id,
+        )
+        .fetch_all(self.pool.as_ref())
+        .await
+        .map_err(|e| ChatSettingsDao::FAIL_QUERY_ERROR.clone().cause(e.into()))?;
+
+        let mut nicknames: Vec<String> = Vec::new();
+
+        for rec in recs {
+            nicknames.push(rec.nickname);
+        }
+
+        Ok(nicknames)
+    }
+
+    #[instrument(skip(self))]
+    async fn get_point_rewards(&self, id: &Uuid) -> AppResult<Vec<PointReward>> {
+        let raw_point_rewards = sqlx::query_as!(
+            RawPointReward,
+            r#"SELECT reward_id, hidden, highlight_color FROM chat_point_rewards WHERE chat_settings_id = $1"#,
+            id,
+        )
+            .fetch_all(self.pool.as_ref())
+            .await
+            .map_err(|e| ChatSettingsDao::FAIL_QUERY_ERROR.clone().cause(e.into()))?;
+
+        let mut point_rewards: Vec<PointReward> = Vec::new();
+
+        for raw_point_reward in raw_point_rewards {
+            point_rewards.push(raw_point_reward.into());
+        }
+
+        Ok(point_rewards)
+    }
+
+    #[instrument(skip(self))]
+    async fn get_viewer_group_rules(&self, id: &Uuid) -> AppResult<Vec<ViewerGroupRule>> {
+        let raw_viewer_group_rules = sqlx::query_as!(
+            RawViewerGroupRule,
+            r#"SELECT viewer_group, hidden, exempt_from_ban_words, nickname_color, background_color, font_weight FROM chat_viewer_group_rules WHERE chat_settings_id = $1"#,
+            id,
+        )
+            .fetch_all(self.pool.as_ref())
+            .await
+            .map_err(|e| ChatSettingsDao::FAIL_QUERY_ERROR.clone().cause(e.into()))?;
+
+        let mut viewer_group_rules: Vec<ViewerGroupRule> = Vec::new();
+
+        for raw_viewer_group_rule in raw_viewer_group_rules {
+            viewer_group_rules.push(raw_viewer_group_rule.try_into()?);
+        }
+
+        Ok(viewer_group_rules)
+    }
+
+    #[instrument(skip(self, conn))]
+    async fn calculate_and_update_custom_nicknames(
+        &self,
+        id: &Uuid,
+        custom_nicknames: &Vec<CustomNickname>,
+        conn: &mut PgConnection,
+    ) -> AppResult {
+        let nicknames: Vec<String> = custom_nicknames
+            .iter()
+            .map(|c| c.nickname.clone())
+            .collect();
+        let previous_custom_nicknames = self.get_custom_nicknames(id).await?;
+        let previous_nicknames: Vec<String> = previous_custom_nicknames
+            .iter()
+            .map(|c| c.nickname.clone())
+            .collect();
+
+        // region: create custom nicknames
+        let mut to_create: Vec<CustomNickname> = Vec::new();
+        for custom_nickname in custom_nicknames.clone() {
+            if !previous_nicknames.contains(&custom_nickname.nickname) {
+                to_create.push(custom_nickname.clone());
+            }
+        }
+        if !to_create.is_empty() {
+            self.create_custom_nicknames(id, &to_create, conn).await?;
+        }
+        // endregion
+
+        // region: Update custom nicknames
+        let mut to_update: Vec<CustomNickname> = Vec::new();
+        for custom_nickname in custom_nicknames.clone() {
+            let is_changed = previous_custom_nicknames
+                .iter()
+                .any(|p| p.nickname == custom_nickname.nickname && *p != custom_nickname);
+            if is_changed {
+                to_update.push(custom_nickname.clone());
+            }
+        }
+        if !to_update.is_empty() {
+            self.update_custom_nicknames(id, &to_update, conn).await?;
+        }
+        // endregion
+
+        // region: Delete custom nicknames
+        let mut to_delete: Vec<CustomNickname> = Vec::new();
+        for previous in previous_custom_nicknames.clone() {
+            if !nicknames.contains(&previous.nickname) {
+                to_delete.push(previous.clone());
+            }
+        }
+        if !to_delete.is_empty() {
+            self.delete_custom_nicknames(id, &to_delete, conn).await?;
+        }
+        // endregion
+
+        Ok(())
+    }
+
+    #[instrument(skip(self, conn))]
+    async fn create_custom_nicknames(
+        &self,
+        id: &Uuid,
+        custom_nicknames: &Vec<CustomNickname>,
+        conn: &mut PgConnection,
+    ) -> AppResult {
+        let nicknames: Vec<String> = custom_nicknames
+            .iter()
+            .map(|v| v.nickname.clone())
+            .collect();
+        let columns = CustomNicknameColumns::from(custom_nicknames);
+        sqlx::query!(
+            r#"INSERT INTO chat_custom_nicknames (chat_settings_id, nickname, user_id, start_color, middle_colors, end_color, display_name, badge_url, font_weight) SELECT $1, * FROM unnest($2::varchar[], $3::varchar[], $4::bigint[], $5::jsonb[], $6::bigint[], $7::varchar[], $8::varchar[], $9::integer[])"#,
+            id,
+            &nicknames,
+            &columns.user_ids as &[Option<String>],
+            &columns.start_colors,
+            &columns.middle_colors,
+            &columns.end_colors,
+            &columns.display_names as &[Option<String>],
+            &columns.badge_urls as &[Option<String>],
+            &columns.font_weights as &[Option<i32>],
+        )
+            .execute(conn)
+            .await
+            .map_err(|e| ChatSettingsDao::FAIL_QUERY_ERROR.clone().cause(e.into()))?;
+
+        Ok(())
+    }
+
+    #[instrument(skip(self, conn))]
+    async fn update_custom_nicknames(
+        &self,
+        id: &Uuid,
+        custom_nicknames: &Vec<CustomNickname>,
+        conn: &mut PgConnection,
+    ) -> AppResult {
+        let nicknames: Vec<String> = custom_nicknames
+            .iter()
+            .map(|v| v.nickname.clone())
+            .collect();
+        let columns = CustomNicknameColumns::from(custom_nicknames);
+        sqlx::query!(
+            r#"UPDATE chat_custom_nicknames SET user_id = bulk_query.user_id, start_color = bulk_query.start_color, middle_colors = bulk_query.middle_colors, end_color = bulk_query.end_color, display_name = bulk_query.display_name, badge_url = bulk_query.badge_url, font_weight = bulk_query.font_weight FROM (SELECT * FROM unnest($2::varchar[], $3::varchar[], $4::bigint[], $5::jsonb[], $6::bigint[], $7::varchar[], $8::varchar[], $9::integer[]) as t(nickname, user_id, start_color, middle_colors, end_color, display_name, badge_url, font_weight)) as bulk_query WHERE chat_custom_nicknames.chat_settings_id = $1 AND chat_custom_nicknames.nickname = bulk_query.nickname"#,
+            id,
+            &nicknames,
+            &columns.user_ids as &[Option<String>],
+            &columns.start_colors,
+            &columns.middle_colors,
+            &columns.end_colors,
+            &columns.display_names as &[Option<String>],
+            &columns.badge_urls as &[Option<String>],
+            &columns.font_weights as &[Option<i32>],
+        )
+            .execute(conn)
+            .await
+            .map_err(|e| ChatSettingsDao::FAIL_QUERY_ERROR.clone().cause(e.into()))?;
+
+        Ok(())
+    }
+
+    #[instrument(skip(self, conn))]
+    async fn delete_custom_nicknames(
+        &self,
+        id: &Uuid,
+        custom_nicknames: &Vec<CustomNickname>,
+        conn: &mut PgConnection,
+    ) -> AppResult {
+        let nicknames: Vec<String> = custom_nicknames
+            .iter()
+            .map(|v| v.nickname.clone())
+            .collect();
+        sqlx::query!(
+            r#"DELETE FROM chat_custom_nicknames WHERE chat_settings_id = $1 AND nickname = any($2::varchar[])"#,
+            id,
+            &nicknames,
+        )
+            .execute(conn)
+            .await
+            .map_err(|e| ChatSettingsDao::FAIL_QUERY_ERROR.clone().cause(e.into()))?;
+
+        Ok(())
+    }
+
+    #[instrument(skip(self, conn))]
+    async fn calculate_and_update_hidden_nicknames(
+        &self,
+        id: &Uuid,
+        hidden_nicknames: &Vec<String>,
+        conn: &mut PgConnection,
+    ) -> AppResult {
+        let previous_hidden_nicknames: Vec<String> = self.get_hidden_nicknames(id).await?;
+
+        // region: create hidden nicknames
+        let mut to_create: Vec<String> = Vec::new();
+        for nickname in hidden_nicknames.clone() {
+            if !previous_hidden_nicknames.contains(&nickname) {
+                to_create.push(nickname.clone());
+            }
+        }
+        if !to_create.is_empty() {
+            self.create_hidden_nicknames(id, &to_create, conn).await?;
+        }
+        // endregion
+
+        // region: Delete hidden nicknames
+        let mut to_delete: Vec<String> = Vec::new();
+        for previous in previous_hidden_nicknames.clone() {
+            if !hidden_nicknames.contains(&previous) {
+                to_delete.push(previous.clone());
+            }
         }
+        if !to_delete.is_empty() {
+            self.delete_hidden_nicknames(id, &to_delete, conn).await?;
+        }
+        // endregion
+
+        Ok(())
     }
 
-    #[instrument(skip(self))]
-    async fn get_custom_nicknames(&self, id: &Uuid) -> AppResult<Vec<CustomNickname>> {
-        let raw_custom_nicknames = sqlx::query_as!(
-            RawCustomNickname,
-            r#"SELECT nickname, start_color, end_color FROM chat_custom_nicknames WHERE chat_settings_id = $1"#,
+    #[instrument(skip(self, conn))]
+    async fn create_hidden_nicknames(
+        &self,
+        id: &Uuid,
+        hidden_nicknames: &Vec<String>,
+        conn: &mut PgConnection,
+    ) -> AppResult {
+        sqlx::query!(
+            r#"INSERT INTO chat_hidden_nicknames (chat_settings_id, nickname) SELECT $1, * FROM unnest($2::varchar[])"#,
             id,
+            &hidden_nicknames,
         )
-            .fetch_all(self.pool.as_ref())
+            .execute(conn)
             .await
             .map_err(|e| ChatSettingsDao::FAIL_QUERY_ERROR.clone().cause(e.into()))?;
 
-        let mut custom_nicknames: Vec<CustomNickname> = Vec::new();
-
-        for raw_custom_nickname in raw_custom_nicknames {
-            custom_nicknames.push(raw_custom_nickname.into());
-        }
-
-        Ok(custom_nicknames)
+        Ok(())
     }
 
-    #[instrument(skip(self))]
-    async fn get_hidden_nicknames(&self, id: &Uuid) -> AppResult<Vec<String>> {
-        let recs = sqlx::query!(
-            r#"SELECT nickname FROM chat_hidden_nicknames WHERE chat_settings_id = $1"#,
+    #[instrument(skip(self, conn))]
+    async fn delete_hidden_nicknames(
+        &self,
+        id: &Uuid,
+        hidden_nicknames: &Vec<String>,
+        conn: &mut PgConnection,
+    ) -> AppResult {
+        sqlx::query!(
+            r#"DELETE FROM chat_hidden_nicknames WHERE chat_settings_id = $1 AND nickname = any($2::varchar[])"#,
             id,
+            &hidden_nicknames,
         )
-        .fetch_all(self.pool.as_ref())
-        .await
-        .map_err(|e| ChatSettingsDao::FAIL_QUERY_ERROR.clone().cause(e.into()))?;
-
-        let mut nicknames: Vec<String> = Vec::new();
-
-        for rec in recs {
-            nicknames.push(rec.nickname);
-        }
+            .execute(conn)
+            .await
+            .map_err(|e| ChatSettingsDao::FAIL_QUERY_ERROR.clone().cause(e.into()))?;
 
-        Ok(nicknames)
+        Ok(())
     }
 
     #[instrument(skip(self, conn))]
-    async fn calculate_and_update_custom_nicknames(
+    async fn calculate_and_update_point_rewards(
         &self,
         id: &Uuid,
-        custom_nicknames: &Vec<CustomNickname>,
+        point_rewards: &Vec<PointReward>,
         conn: &mut PgConnection,
     ) -> AppResult {
-        let nicknames: Vec<String> = custom_nicknames
+        let reward_ids: Vec<String> = point_rewards
             .iter()
-            .map(|c| c.nickname.clone())
+            .map(|p| p.reward_id.clone())
             .collect();
-        let previous_custom_nicknames = self.get_custom_nicknames(id).await?;
-        let previous_nicknames: Vec<String> = previous_custom_nicknames
+        let previous_point_rewards = self.get_point_rewards(id).await?;
+        let previous_reward_ids: Vec<String> = previous_point_rewards
             .iter()
-            .map(|c| c.nickname.clone())
+            .map(|p| p.reward_id.clone())
             .collect();
 
-        // region: create custom nicknames
-        let mut to_create: Vec<CustomNickname> = Vec::new();
-        for custom_nickname in custom_nicknames.clone() {
-            if !previous_nicknames.contains(&custom_nickname.nickname) {
-                to_create.push(custom_nickname.clone());
+        // region: create point rewards
+        let mut to_create: Vec<PointReward> = Vec::new();
+        for point_reward in point_rewards.clone() {
+            if !previous_reward_ids.contains(&point_reward.reward_id) {
+                to_create.push(point_reward.clone());
             }
         }
-        if to_create.len() > 0 {
-            self.create_custom_nicknames(id, &to_create, conn).await?;
+        if !to_create.is_empty() {
+            self.create_point_rewards(id, &to_create, conn).await?;
         }
         // endregion
 
-        // region: Update custom nicknames
-        let mut to_update: Vec<CustomNickname> = Vec::new();
-        for custom_nickname in custom_nicknames.clone() {
-            if previous_nicknames.contains(&custom_nickname.nickname) {
-                to_update.push(custom_nickname.clone());
+        // region: Update point rewards
+        let mut to_update: Vec<PointReward> = Vec::new();
+        for point_reward in point_rewards.clone() {
+            let is_changed = previous_point_rewards
+                .iter()
+                .any(|p| p.reward_id == point_reward.reward_id && *p != point_reward);
+            if is_changed {
+                to_update.push(point_reward.clone());
             }
         }
-        if to_update.len() > 0 {
-            self.update_custom_nicknames(id, &to_update, conn).await?;
+        if !to_update.is_empty() {
+            self.update_point_rewards(id, &to_update, conn).await?;
         }
         // endregion
 
-        // region: Delete custom nicknames
-        let mut to_delete: Vec<CustomNickname> = Vec::new();
-        for previous in previous_custom_nicknames.clone() {
-            if !nicknames.contains(&previous.nickname) {
+        // region: Delete point rewards
+        let mut to_delete: Vec<String> = Vec::new();
+        for previous in previous_reward_ids.clone() {
+            if !reward_ids.contains(&previous) {
                 to_delete.push(previous.clone());
             }
         }
-        if to_delete.len() > 0 {
-            self.delete_custom_nicknames(id, &to_delete, conn).await?;
+        if !to_delete.is_empty() {
+            self.delete_point_rewards(id, &to_delete, conn).await?;
         }
         // endregion
 
@@ -298,24 +1213,25 @@ impl ChatSettingsDao {
     }
 
     #[instrument(skip(self, conn))]
-    async fn create_custom_nicknames(
+    async fn create_point_rewards(
         &self,
         id: &Uuid,
-        custom_nicknames: &Vec<CustomNickname>,
+        point_rewards: &Vec<PointReward>,
         conn: &mut PgConnection,
     ) -> AppResult {
-        let nicknames: Vec<String> = custom_nicknames
+        let reward_ids: Vec<String> = point_rewards
             .iter()
-            .map(|v| v.nickname.clone())
+            .map(|v| v.reward_id.clone())
             .collect();
-        let start_colors: Vec<i64> = custom_nicknames.iter().map(|v| v.start_color).collect();
-        let end_colors: Vec<i64> = custom_nicknames.iter().map(|v| v.end_color).collect();
+        let hidden: Vec<bool> = point_rewards.iter().map(|v| v.hidden).collect();
+        let highlight_colors: Vec<Option<i64>> =
+            point_rewards.iter().map(|v| v.highlight_color).collect();
         sqlx::query!(
-            r#"INSERT INTO chat_custom_nicknames (chat_settings_id, nickname, start_color, end_color) SELECT $1, * FROM unnest($2::varchar[], $3::bigint[], $4::bigint[])"#,
+            r#"INSERT INTO chat_point_rewards (chat_settings_id, reward_id, hidden, highlight_color) SELECT $1, * FROM unnest($2::varchar[], $3::boolean[], $4::bigint[])"#,
             id,
-            &nicknames,
-            &start_colors,
-            &end_colors
+            &reward_ids,
+            &hidden,
+            &highlight_colors as &[Option<i64>]
         )
             .execute(conn)
             .await
@@ -325,24 +1241,25 @@ impl ChatSettingsDao {
     }
 
     #[instrument(skip(self, conn))]
-    async fn update_custom_nicknames(
+    async fn update_point_rewards(
         &self,
         id: &Uuid,
-        custom_nicknames: &Vec<CustomNickname>,
+        point_rewards: &Vec<PointReward>,
         conn: &mut PgConnection,
     ) -> AppResult {
-        let nicknames: Vec<String> = custom_nicknames
+        let reward_ids: Vec<String> = point_rewards
             .iter()
-            .map(|v| v.nickname.clone())
+            .map(|v| v.reward_id.clone())
             .collect();
-        let start_colors: Vec<i64> = custom_nicknames.iter().map(|v| v.start_color).collect();
-        let end_colors: Vec<i64> = custom_nicknames.iter().map(|v| v.end_color).collect();
+        let hidden: Vec<bool> = point_rewards.iter().map(|v| v.hidden).collect();
+        let highlight_colors: Vec<Option<i64>> =
+            point_rewards.iter().map(|v| v.highlight_color).collect();
         sqlx::query!(
-            r#"UPDATE chat_custom_nicknames SET start_color = bulk_query.start_color, end_color = bulk_query.end_color FROM (SELECT * FROM unnest($2::varchar[], $3::bigint[], $4::bigint[]) as t(nickname, start_color, end_color)) as bulk_query WHERE chat_custom_nicknames.chat_settings_id = $1 AND chat_custom_nicknames.nickname = bulk_query.nickname"#,
+            r#"UPDATE chat_point_rewards SET hidden = bulk_query.hidden, highlight_color = bulk_query.highlight_color FROM (SELECT * FROM unnest($2::varchar[], $3::boolean[], $4::bigint[]) as t(reward_id, hidden, highlight_color)) as bulk_query WHERE chat_point_rewards.chat_settings_id = $1 AND chat_point_rewards.reward_id = bulk_query.reward_id"#,
             id,
-            &nicknames,
-            &start_colors,
-            &end_colors
+            &reward_ids,
+            &hidden,
+            &highlight_colors as &[Option<i64>]
         )
             .execute(conn)
             .await
@@ -352,20 +1269,16 @@ impl ChatSettingsDao {
     }
 
     #[instrument(skip(self, conn))]
-    async fn delete_custom_nicknames(
+    async fn delete_point_rewards(
         &self,
         id: &Uuid,
-        custom_nicknames: &Vec<CustomNickname>,
+        reward_ids: &Vec<String>,
         conn: &mut PgConnection,
     ) -> AppResult {
-        let nicknames: Vec<String> = custom_nicknames
-            .iter()
-            .map(|v| v.nickname.clone())
-            .collect();
         sqlx::query!(
-            r#"DELETE FROM chat_custom_nicknames WHERE chat_settings_id = $1 AND nickname = any($2::varchar[])"#,
+            r#"DELETE FROM chat_point_rewards WHERE chat_settings_id = $1 AND reward_id = any($2::varchar[])"#,
             id,
-            &nicknames,
+            &reward_ids,
         )
             .execute(conn)
             .await
@@ -375,35 +1288,55 @@ impl ChatSettingsDao {
     }
 
     #[instrument(skip(self, conn))]
-    async fn calculate_and_update_hidden_nicknames(
+    async fn calculate_and_update_viewer_group_rules(
         &self,
         id: &Uuid,
-        hidden_nicknames: &Vec<String>,
+        viewer_group_rules: &Vec<ViewerGroupRule>,
         conn: &mut PgConnection,
     ) -> AppResult {
-        let previous_hidden_nicknames: Vec<String> = self.get_hidden_nicknames(id).await?;
+        let groups: Vec<ViewerGroup> = viewer_group_rules.iter().map(|r| r.group).collect();
+        let previous_viewer_group_rules = self.get_viewer_group_rules(id).await?;
+        let previous_groups: Vec<ViewerGroup> = previous_viewer_group_rules
+            .iter()
+            .map(|r| r.group)
+            .collect();
 
-        // region: create hidden nicknames
-        let mut to_create: Vec<String> = Vec::new();
-        for nickname in hidden_nicknames.clone() {
-            if !previous_hidden_nicknames.contains(&nickname) {
-                to_create.push(nickname.clone());
+        // region: create viewer group rules
+        let mut to_create: Vec<ViewerGroupRule> = Vec::new();
+        for viewer_group_rule in viewer_group_rules.clone() {
+            if !previous_groups.contains(&viewer_group_rule.group) {
+                to_create.push(viewer_group_rule.clone());
             }
         }
-        if to_create.len() > 0 {
-            self.create_hidden_nicknames(id, &to_create, conn).await?;
+        if !to_create.is_empty() {
+            self.create_viewer_group_rules(id, &to_create, conn).await?;
         }
         // endregion
 
-        // region: Delete hidden nicknames
+        // region: Update viewer group rules
+        let mut to_update: Vec<ViewerGroupRule> = Vec::new();
+        for viewer_group_rule in viewer_group_rules.clone() {
+            let is_changed = previous_viewer_group_rules
+                .iter()
+                .any(|r| r.group == viewer_group_rule.group && *r != viewer_group_rule);
+            if is_changed {
+                to_update.push(viewer_group_rule.clone());
+            }
+        }
+        if !to_update.is_empty() {
+            self.update_viewer_group_rules(id, &to_update, conn).await?;
+        }
+        // endregion
+
+        // region: Delete viewer group rules
         let mut to_delete: Vec<String> = Vec::new();
-        for previous in previous_hidden_nicknames.clone() {
-            if !hidden_nicknames.contains(&previous) {
-                to_delete.push(previous.clone());
+        for previous in previous_groups.clone() {
+            if !groups.contains(&previous) {
+                to_delete.push(previous.to_str().to_string());
             }
         }
-        if to_delete.len() > 0 {
-            self.delete_hidden_nicknames(id, &to_delete, conn).await?;
+        if !to_delete.is_empty() {
+            self.delete_viewer_group_rules(id, &to_delete, conn).await?;
         }
         // endregion
 
@@ -411,16 +1344,22 @@ impl ChatSettingsDao {
     }
 
     #[instrument(skip(self, conn))]
-    async fn create_hidden_nicknames(
+    async fn create_viewer_group_rules(
         &self,
         id: &Uuid,
-        hidden_nicknames: &Vec<String>,
+        viewer_group_rules: &[ViewerGroupRule],
         conn: &mut PgConnection,
     ) -> AppResult {
+        let columns = ViewerGroupRuleColumns::from(viewer_group_rules);
         sqlx::query!(
-            r#"INSERT INTO chat_hidden_nicknames (chat_settings_id, nickname) SELECT $1, * FROM unnest($2::varchar[])"#,
+            r#"INSERT INTO chat_viewer_group_rules (chat_settings_id, viewer_group, hidden, exempt_from_ban_words, nickname_color, background_color, font_weight) SELECT $1, * FROM unnest($2::varchar[], $3::boolean[], $4::boolean[], $5::bigint[], $6::bigint[], $7::integer[])"#,
             id,
-            &hidden_nicknames,
+            &columns.groups,
+            &columns.hidden,
+            &columns.exempt_from_ban_words,
+            &columns.nickname_colors as &[Option<i64>],
+            &columns.background_colors as &[Option<i64>],
+            &columns.font_weights as &[Option<i32>]
         )
             .execute(conn)
             .await
@@ -430,16 +1369,41 @@ impl ChatSettingsDao {
     }
 
     #[instrument(skip(self, conn))]
-    async fn delete_hidden_nicknames(
+    async fn update_viewer_group_rules(
         &self,
         id: &Uuid,
-        hidden_nicknames: &Vec<String>,
+        viewer_group_rules: &[ViewerGroupRule],
         conn: &mut PgConnection,
     ) -> AppResult {
+        let columns = ViewerGroupRuleColumns::from(viewer_group_rules);
         sqlx::query!(
-            r#"DELETE FROM chat_hidden_nicknames WHERE chat_settings_id = $1 AND nickname = any($2::varchar[])"#,
+            r#"UPDATE chat_viewer_group_rules SET hidden = bulk_query.hidden, exempt_from_ban_words = bulk_query.exempt_from_ban_words, nickname_color = bulk_query.nickname_color, background_color = bulk_query.background_color, font_weight = bulk_query.font_weight FROM (SELECT * FROM unnest($2::varchar[], $3::boolean[], $4::boolean[], $5::bigint[], $6::bigint[], $7::integer[]) as t(viewer_group, hidden, exempt_from_ban_words, nickname_color, background_color, font_weight)) as bulk_query WHERE chat_viewer_group_rules.chat_settings_id = $1 AND chat_viewer_group_rules.viewer_group = bulk_query.viewer_group"#,
             id,
-            &hidden_nicknames,
+            &columns.groups,
+            &columns.hidden,
+            &columns.exempt_from_ban_words,
+            &columns.nickname_colors as &[Option<i64>],
+            &columns.background_colors as &[Option<i64>],
+            &columns.font_weights as &[Option<i32>]
+        )
+            .execute(conn)
+            .await
+            .map_err(|e| ChatSettingsDao::FAIL_QUERY_ERROR.clone().cause(e.into()))?;
+
+        Ok(())
+    }
+
+    #[instrument(skip(self, conn))]
+    async fn delete_viewer_group_rules(
+        &self,
+        id: &Uuid,
+        groups: &Vec<String>,
+        conn: &mut PgConnection,
+    ) -> AppResult {
+        sqlx::query!(
+            r#"DELETE FROM chat_viewer_group_rules WHERE chat_settings_id = $1 AND viewer_group = any($2::varchar[])"#,
+            id,
+            &groups,
         )
             .execute(conn)
             .await
@@ -453,6 +1417,7 @@ struct RawChatSettings {
     id: Uuid,
     name: String,
     chat_type: String,
+    chat_type_options: Json<ChatTypeOptions>,
     nickname_color: i64,
     background_color: i64,
     text_color: i64,
@@ -480,54 +1445,75 @@ struct RawChatSettings {
     nickname_font_weight: i32,
     text_font_weight: i32,
     font_size: f64,
+    message_ttl: i32,
+    enter_animation: String,
+    enter_duration: i32,
+    exit_animation: String,
+    exit_duration: i32,
+    stacking_direction: String,
     user_id: String,
+    revision: i32,
 }
 
-impl Into<ChatSettings> for RawChatSettings {
-    fn into(self) -> ChatSettings {
-        ChatSettings {
-            id: self.id,
-            name: self.name,
-            chat_type: ChatType::from_str(&self.chat_type),
+impl TryFrom<RawChatSettings> for ChatSettings {
+    type Error = AppError;
+
+    fn try_from(raw: RawChatSettings) -> AppResult<Self> {
+        Ok(ChatSettings {
+            id: raw.id,
+            name: raw.name,
+            chat_type: parse_chat_type(&raw.chat_type)?,
+            chat_type_options: raw.chat_type_options.0,
             color: ChatColorSettings {
-                nickname_color: self.nickname_color,
-                background_color: self.background_color,
-                text_color: self.text_color,
-                gradient_only_for_custom_nicknames: self.gradient_only_for_custom_nicknames,
+                nickname_color: raw.nickname_color,
+                background_color: raw.background_color,
+                text_color: raw.text_color,
+                gradient_only_for_custom_nicknames: raw.gradient_only_for_custom_nicknames,
                 custom_nicknames: Vec::new(),
             },
             size: ChatSizeSettings {
-                margin_top: self.margin_top,
-                margin_right: self.margin_right,
-                margin_bottom: self.margin_bottom,
-                margin_left: self.margin_left,
-                padding_top: self.padding_top,
-                padding_right: self.padding_right,
-                padding_bottom: self.padding_bottom,
-                padding_left: self.padding_left,
-                border_top_left_radius: self.border_top_left_radius,
-                border_top_right_radius: self.border_top_right_radius,
-                border_bottom_left_radius: self.border_bottom_left_radius,
-                border_bottom_right_radius: self.border_bottom_right_radius,
-                max_messages: self.max_messages,
+                margin_top: raw.margin_top,
+                margin_right: raw.margin_right,
+                margin_bottom: raw.margin_bottom,
+                margin_left: raw.margin_left,
+                padding_top: raw.padding_top,
+                padding_right: raw.padding_right,
+                padding_bottom: raw.padding_bottom,
+                padding_left: raw.padding_left,
+                border_top_left_radius: raw.border_top_left_radius,
+                border_top_right_radius: raw.border_top_right_radius,
+                border_bottom_left_radius: raw.border_bottom_left_radius,
+                border_bottom_right_radius: raw.border_bottom_right_radius,
+                max_messages: raw.max_messages,
             },
             hide: ChatHideSettings {
-                hide_message_pattern: self.hide_message_pattern,
-                hide_point_rewards: self.hide_point_rewards,
-                hide_links: self.hide_links,
-                link_replacement: self.link_replacement,
-                ban_word_replacement: self.ban_word_replacement,
-                ban_word_filter_id: self.ban_word_filter_id,
+                hide_message_pattern: raw.hide_message_pattern,
+                hide_point_rewards: raw.hide_point_rewards,
+                hide_links: raw.hide_links,
+                link_replacement: raw.link_replacement,
+                ban_word_replacement: raw.ban_word_replacement,
+                ban_word_filter_id: raw.ban_word_filter_id,
                 nicknames: Vec::new(),
+                point_rewards: Vec::new(),
+                viewer_groups: Vec::new(),
             },
             font: ChatFontSettings {
-                font_family: self.font_family,
-                nickname_font_weight: self.nickname_font_weight,
-                text_font_weight: self.text_font_weight,
-                font_size: self.font_size,
+                font_family: raw.font_family,
+                nickname_font_weight: raw.nickname_font_weight,
+                text_font_weight: raw.text_font_weight,
+                font_size: raw.font_size,
             },
-            user_id: self.user_id,
-        }
+            animation: ChatAnimationSettings {
+                message_ttl: raw.message_ttl,
+                enter_animation: parse_animation(&raw.enter_animation)?,
+                enter_duration: raw.enter_duration,
+                exit_animation: parse_animation(&raw.exit_animation)?,
+                exit_duration: raw.exit_duration,
+                stacking_direction: parse_stacking_direction(&raw.stacking_direction)?,
+            },
+            user_id: raw.user_id,
+            revision: raw.revision,
+        })
     }
 }
 
@@ -536,31 +1522,237 @@ struct RawChatSettingsInfo {
     name: String,
     chat_type: String,
     user_id: String,
+    created_at: NaiveDateTime,
+    updated_at: NaiveDateTime,
+    deleted_at: Option<NaiveDateTime>,
+    sort_key: String,
+}
+
+impl TryFrom<RawChatSettingsInfo> for ChatSettingsInfo {
+    type Error = AppError;
+
+    fn try_from(raw: RawChatSettingsInfo) -> AppResult<Self> {
+        Ok(ChatSettingsInfo {
+            id: raw.id,
+            name: raw.name,
+            chat_type: parse_chat_type(&raw.chat_type)?,
+            user_id: raw.user_id,
+            created_at: raw.created_at,
+            updated_at: raw.updated_at,
+            deleted_at: raw.deleted_at,
+        })
+    }
+}
+
+struct RawChatSettingsVersionInfo {
+    chat_settings_id: Uuid,
+    version: i32,
+    name: String,
+    created_at: NaiveDateTime,
 }
 
-impl Into<ChatSettingsInfo> for RawChatSettingsInfo {
-    fn into(self) -> ChatSettingsInfo {
-        ChatSettingsInfo {
-            id: self.id,
-            name: self.name,
-            chat_type: ChatType::from_str(&self.chat_type),
-            user_id: self.user_id,
+impl From<RawChatSettingsVersionInfo> for ChatSettingsVersionInfo {
+    fn from(raw: RawChatSettingsVersionInfo) -> Self {
+        ChatSettingsVersionInfo {
+            chat_settings_id: raw.chat_settings_id,
+            version: raw.version,
+            name: raw.name,
+            created_at: raw.created_at,
         }
     }
 }
 
+struct RawChatSettingsVersion {
+    chat_settings_id: Uuid,
+    version: i32,
+    settings: Json<ChatSettings>,
+    created_at: NaiveDateTime,
+}
+
+impl From<RawChatSettingsVersion> for ChatSettingsVersion {
+    fn from(raw: RawChatSettingsVersion) -> Self {
+        ChatSettingsVersion {
+            chat_settings_id: raw.chat_settings_id,
+            version: raw.version,
+            settings: raw.settings.0,
+            created_at: raw.created_at,
+        }
+    }
+}
+
+/// New value of a patched field, `None` when it stays the same.
+fn changed<'a, T: PartialEq>(previous: &T, patched: &'a T) -> Option<&'a T> {
+    if previous == patched {
+        None
+    } else {
+        Some(patched)
+    }
+}
+
+/// Unknown values are written by a newer version or by hand, reading them as
+/// another type would overwrite them on the next save.
+fn parse_chat_type(chat_type: &str) -> AppResult<ChatType> {
+    chat_type.parse().map_err(|e: UnknownChatType| {
+        ChatSettingsDao::UNKNOWN_CHAT_TYPE_ERROR
+            .clone()
+            .cause(e.into())
+    })
+}
+
+fn parse_animation(animation: &str) -> AppResult<ChatAnimation> {
+    animation.parse().map_err(|e: UnknownChatAnimation| {
+        ChatSettingsDao::UNKNOWN_ANIMATION_ERROR
+            .clone()
+            .cause(e.into())
+    })
+}
+
+fn parse_viewer_group(viewer_group: &str) -> AppResult<ViewerGroup> {
+    viewer_group.parse().map_err(|e: UnknownViewerGroup| {
+        ChatSettingsDao::UNKNOWN_VIEWER_GROUP_ERROR
+            .clone()
+            .cause(e.into())
+    })
+}
+
+fn parse_stacking_direction(stacking_direction: &str) -> AppResult<StackingDirection> {
+    stacking_direction
+        .parse()
+        .map_err(|e: UnknownStackingDirection| {
+            ChatSettingsDao::UNKNOWN_STACKING_DIRECTION_ERROR
+                .clone()
+                .cause(e.into())
+        })
+}
+
 struct RawCustomNickname {
     nickname: String,
+    user_id: Option<String>,
     start_color: i64,
+    middle_colors: Json<Vec<i64>>,
     end_color: i64,
+    display_name: Option<String>,
+    badge_url: Option<String>,
+    font_weight: Option<i32>,
 }
 
-impl Into<CustomNickname> for RawCustomNickname {
-    fn into(self) -> CustomNickname {
+impl From<RawCustomNickname> for CustomNickname {
+    fn from(raw: RawCustomNickname) -> Self {
         CustomNickname {
-            nickname: self.nickname,
-            start_color: self.start_color,
-            end_color: self.end_color,
+            nickname: raw.nickname,
+            user_id: raw.user_id,
+            start_color: raw.start_color,
+            middle_colors: raw.middle_colors.0,
+            end_color: raw.end_color,
+            display_name: raw.display_name,
+            badge_url: raw.badge_url,
+            font_weight: raw.font_weight,
+        }
+    }
+}
+
+/// Custom nicknames split into one array per column, for `unnest`.
+struct CustomNicknameColumns {
+    user_ids: Vec<Option<String>>,
+    start_colors: Vec<i64>,
+    middle_colors: Vec<JsonValue>,
+    end_colors: Vec<i64>,
+    display_names: Vec<Option<String>>,
+    badge_urls: Vec<Option<String>>,
+    font_weights: Vec<Option<i32>>,
+}
+
+impl From<&Vec<CustomNickname>> for CustomNicknameColumns {
+    fn from(custom_nicknames: &Vec<CustomNickname>) -> Self {
+        CustomNicknameColumns {
+            user_ids: custom_nicknames.iter().map(|v| v.user_id.clone()).collect(),
+            start_colors: custom_nicknames.iter().map(|v| v.start_color).collect(),
+            middle_colors: custom_nicknames
+                .iter()
+                .map(|v| JsonValue::from(v.middle_colors.clone()))
+                .collect(),
+            end_colors: custom_nicknames.iter().map(|v| v.end_color).collect(),
+            display_names: custom_nicknames
+                .iter()
+                .map(|v| v.display_name.clone())
+                .collect(),
+            badge_urls: custom_nicknames.iter().map(|v| v.badge_url.clone()).collect(),
+            font_weights: custom_nicknames.iter().map(|v| v.font_weight).collect(),
+        }
+    }
+}
+
+struct RawPointReward {
+    reward_id: String,
+    hidden: bool,
+    highlight_color: Option<i64>,
+}
+
+impl From<RawPointReward> for PointReward {
+    fn from(raw: RawPointReward) -> Self {
+        PointReward {
+            reward_id: raw.reward_id,
+            hidden: raw.hidden,
+            highlight_color: raw.highlight_color,
+        }
+    }
+}
+
+struct RawViewerGroupRule {
+    viewer_group: String,
+    hidden: bool,
+    exempt_from_ban_words: bool,
+    nickname_color: Option<i64>,
+    background_color: Option<i64>,
+    font_weight: Option<i32>,
+}
+
+impl TryFrom<RawViewerGroupRule> for ViewerGroupRule {
+    type Error = AppError;
+
+    fn try_from(raw: RawViewerGroupRule) -> AppResult<Self> {
+        Ok(ViewerGroupRule {
+            group: parse_viewer_group(&raw.viewer_group)?,
+            hidden: raw.hidden,
+            exempt_from_ban_words: raw.exempt_from_ban_words,
+            nickname_color: raw.nickname_color,
+            background_color: raw.background_color,
+            font_weight: raw.font_weight,
+        })
+    }
+}
+
+/// Viewer group rules split into one array per column, for `unnest`.
+struct ViewerGroupRuleColumns {
+    groups: Vec<String>,
+    hidden: Vec<bool>,
+    exempt_from_ban_words: Vec<bool>,
+    nickname_colors: Vec<Option<i64>>,
+    background_colors: Vec<Option<i64>>,
+    font_weights: Vec<Option<i32>>,
+}
+
+impl From<&[ViewerGroupRule]> for ViewerGroupRuleColumns {
+    fn from(viewer_group_rules: &[ViewerGroupRule]) -> Self {
+        ViewerGroupRuleColumns {
+            groups: viewer_group_rules
+                .iter()
+                .map(|v| v.group.to_str().to_string())
+                .collect(),
+            hidden: viewer_group_rules.iter().map(|v| v.hidden).collect(),
+            exempt_from_ban_words: viewer_group_rules
+                .iter()
+                .map(|v| v.exempt_from_ban_words)
+                .collect(),
+            nickname_colors: viewer_group_rules
+                .iter()
+                .map(|v| v.nickname_color)
+                .collect(),
+            background_colors: viewer_group_rules
+                .iter()
+                .map(|v| v.background_color)
+                .collect(),
+            font_weights: viewer_group_rules.iter().map(|v| v.font_weight).collect(),
         }
     }
 }
@@ -592,4 +1784,311 @@ chat_settings_dao_errors! {
     (FAIL_COMMIT_TRANSACTION_ERROR, StatusCode::INTERNAL_SERVER_ERROR, "fail commit chat settings transaction");
     (ID_TAKEN_ERROR, StatusCode::CONFLICT, "id taken");
     (NOT_FOUND_ERROR, StatusCode::NOT_FOUND, "chat settings not found");
+    (NOT_IN_TRASH_ERROR, StatusCode::NOT_FOUND, "chat settings not in trash");
+    (VERSION_NOT_FOUND_ERROR, StatusCode::NOT_FOUND, "chat settings version not found");
+    (REVISION_MISMATCH_ERROR, StatusCode::PRECONDITION_FAILED, "chat settings was changed");
+    (UNKNOWN_CHAT_TYPE_ERROR, StatusCode::INTERNAL_SERVER_ERROR, "unknown stored chat type");
+    (UNKNOWN_ANIMATION_ERROR, StatusCode::INTERNAL_SERVER_ERROR, "unknown stored chat animation");
+    (UNKNOWN_STACKING_DIRECTION_ERROR, StatusCode::INTERNAL_SERVER_ERROR, "unknown stored stacking direction");
+    (UNKNOWN_VIEWER_GROUP_ERROR, StatusCode::INTERNAL_SERVER_ERROR, "unknown stored viewer group");
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use sqlx::PgPool;
+
+    use types::domain::{ChatType, UpdateBanWordFilter, UpdateChatSettings, UpdatePointReward};
+
+    use crate::{BanWordFilterDao, ChatSettingsDao, UserDao};
+
+    #[sqlx::test(migrations = "../migrations")]
+    async fn clone_keeps_owned_references_only_for_owner(pool: PgPool) {
+        let pool = Arc::new(pool);
+        let user_dao = UserDao::new(pool.clone());
+        let ban_word_filter_dao = BanWordFilterDao::new(pool.clone());
+        let chat_settings_dao = ChatSettingsDao::new(pool);
+        user_dao.get_or_create("1", "owner").await.unwrap();
+        user_dao.get_or_create("2", "other").await.unwrap();
+
+        let ban_word_filter = ban_word_filter_dao.create("1", "filter").await.unwrap();
+        let chat_settings = chat_settings_dao
+            .create("1", "chat", &ChatType::Default)
+            .await
+            .unwrap();
+        let mut update_chat_settings = UpdateChatSettings::from(chat_settings.clone());
+        update_chat_settings.hide.ban_word_filter_id = Some(ban_word_filter.id);
+        update_chat_settings.hide.point_rewards = vec![UpdatePointReward {
+            reward_id: "reward".to_string(),
+            hidden: true,
+            highlight_color: None,
+        }];
+        chat_settings_dao
+            .update(&chat_settings.id, &update_chat_settings, None)
+            .await
+            .unwrap();
+
+        let own_clone = chat_settings_dao
+            .create_clone(&chat_settings.id, "1", "own", true)
+            .await
+            .unwrap();
+        assert_eq!(own_clone.user_id, "1");
+        assert_eq!(own_clone.hide.ban_word_filter_id, Some(ban_word_filter.id));
+        assert_eq!(own_clone.hide.point_rewards.len(), 1);
+
+        let other_clone = chat_settings_dao
+            .create_clone(&chat_settings.id, "2", "other", false)
+            .await
+            .unwrap();
+        assert_eq!(other_clone.user_id, "2");
+        assert_eq!(other_clone.hide.ban_word_filter_id, None);
+        assert!(other_clone.hide.point_rewards.is_empty());
+        // What is returned is what was stored.
+        assert_eq!(
+            chat_settings_dao.get(&other_clone.id).await.unwrap(),
+            other_clone
+        );
+    }
+
+    #[sqlx::test(migrations = "../migrations")]
+    async fn trash_restore_and_purge(pool: PgPool) {
+        let pool = Arc::new(pool);
+        UserDao::new(pool.clone())
+            .get_or_create("1", "owner")
+            .await
+            .unwrap();
+        let chat_settings_dao = ChatSettingsDao::new(pool.clone());
+        let chat_settings = chat_settings_dao
+            .create("1", "chat", &ChatType::Default)
+            .await
+            .unwrap();
+
+        chat_settings_dao
+            .delete(&chat_settings.id, None)
+            .await
+            .unwrap();
+        assert_eq!(
+            chat_settings_dao
+                .get(&chat_settings.id)
+                .await
+                .unwrap_err()
+                .status_code,
+            ChatSettingsDao::NOT_FOUND_ERROR.status_code
+        );
+        // Deleting twice finds nothing to delete.
+        assert!(chat_settings_dao
+            .delete(&chat_settings.id, None)
+            .await
+            .is_err());
+        let trash = chat_settings_dao.get_trash_by_user_id("1").await.unwrap();
+        assert_eq!(trash.len(), 1);
+        assert_eq!(trash[0].id, chat_settings.id);
+
+        let restored = chat_settings_dao.restore(&chat_settings.id).await.unwrap();
+        assert_eq!(restored.revision, chat_settings.revision + 1);
+        assert!(chat_settings_dao.restore(&chat_settings.id).await.is_err());
+
+        chat_settings_dao
+            .delete(&chat_settings.id, None)
+            .await
+            .unwrap();
+        assert_eq!(chat_settings_dao.purge(30).await.unwrap(), 0);
+        sqlx::query("UPDATE chat_settings SET deleted_at = now() - interval '31 days'")
+            .execute(pool.as_ref())
+            .await
+            .unwrap();
+        assert_eq!(chat_settings_dao.purge(30).await.unwrap(), 1);
+        assert!(chat_settings_dao
+            .get_trash_by_user_id("1")
+            .await
+            .unwrap()
+            .is_empty());
+    }
+
+    #[sqlx::test(migrations = "../migrations")]
+    async fn restore_version_checks_revision(pool: PgPool) {
+        let pool = Arc::new(pool);
+        UserDao::new(pool.clone())
+            .get_or_create("1", "owner")
+            .await
+            .unwrap();
+        let chat_settings_dao = ChatSettingsDao::new(pool);
+        let chat_settings = chat_settings_dao
+            .create("1", "first", &ChatType::Default)
+            .await
+            .unwrap();
+        let mut update_chat_settings = UpdateChatSettings::from(chat_settings.clone());
+        update_chat_settings.name = "second".to_string();
+        let updated = chat_settings_dao
+            .update(&chat_settings.id, &update_chat_settings, None)
+            .await
+            .unwrap();
+
+        assert_eq!(
+            chat_settings_dao
+                .restore_version(&chat_settings.id, 1, Some(chat_settings.revision))
+                .await
+                .unwrap_err()
+                .status_code,
+            ChatSettingsDao::REVISION_MISMATCH_ERROR.status_code
+        );
+
+        let restored = chat_settings_dao
+            .restore_version(&chat_settings.id, 1, Some(updated.revision))
+            .await
+            .unwrap();
+        assert_eq!(restored.name, "first");
+        assert_eq!(restored.revision, updated.revision + 1);
+        let versions = chat_settings_dao
+            .get_versions(&chat_settings.id)
+            .await
+            .unwrap();
+        assert_eq!(versions.len(), 3);
+        assert_eq!(versions[0].name, "first");
+    }
+
+    #[sqlx::test(migrations = "../migrations")]
+    async fn unknown_stored_chat_type(pool: PgPool) {
+        let pool = Arc::new(pool);
+        UserDao::new(pool.clone())
+            .get_or_create("1", "owner")
+            .await
+            .unwrap();
+        let chat_settings_dao = ChatSettingsDao::new(pool.clone());
+        let chat_settings = chat_settings_dao
+            .create("1", "chat", &ChatType::Bubble)
+            .await
+            .unwrap();
+        assert_eq!(
+            chat_settings_dao
+                .get(&chat_settings.id)
+                .await
+                .unwrap()
+                .chat_type,
+            ChatType::Bubble
+        );
+
+        // Written by a newer version, not read as some other type.
+        sqlx::query("UPDATE chat_settings SET chat_type = 'hologram' WHERE id = $1")
+            .bind(chat_settings.id)
+            .execute(pool.as_ref())
+            .await
+            .unwrap();
+        let error = chat_settings_dao.get(&chat_settings.id).await.unwrap_err();
+        assert_eq!(
+            error.message,
+            ChatSettingsDao::UNKNOWN_CHAT_TYPE_ERROR.message
+        );
+    }
+
+    #[sqlx::test(migrations = "../migrations")]
+    async fn unknown_stored_viewer_group(pool: PgPool) {
+        let pool = Arc::new(pool);
+        UserDao::new(pool.clone())
+            .get_or_create("1", "owner")
+            .await
+            .unwrap();
+        let chat_settings_dao = ChatSettingsDao::new(pool.clone());
+        let chat_settings = chat_settings_dao
+            .create("1", "chat", &ChatType::Default)
+            .await
+            .unwrap();
+
+        sqlx::query(
+            "INSERT INTO chat_viewer_group_rules (chat_settings_id, viewer_group) VALUES ($1, 'founder')",
+        )
+        .bind(chat_settings.id)
+        .execute(pool.as_ref())
+        .await
+        .unwrap();
+        let error = chat_settings_dao.get(&chat_settings.id).await.unwrap_err();
+        assert_eq!(
+            error.message,
+            ChatSettingsDao::UNKNOWN_VIEWER_GROUP_ERROR.message
+        );
+    }
+
+    #[sqlx::test(migrations = "../migrations")]
+    async fn failed_create_leaves_no_ban_word_filter(pool: PgPool) {
+        let pool = Arc::new(pool);
+        UserDao::new(pool.clone())
+            .get_or_create("1", "owner")
+            .await
+            .unwrap();
+        let chat_settings_dao = ChatSettingsDao::new(pool.clone());
+        let chat_settings = chat_settings_dao
+            .create("1", "chat", &ChatType::Default)
+            .await
+            .unwrap();
+        let update_ban_word_filter = UpdateBanWordFilter {
+            name: "filter".to_string(),
+            ban_words: vec!["bad".to_string()],
+        };
+
+        let mut update_chat_settings = UpdateChatSettings::from(chat_settings);
+        update_chat_settings.name = "imported".to_string();
+        let imported = chat_settings_dao
+            .create_with_ban_word_filter("1", &update_chat_settings, &update_ban_word_filter)
+            .await
+            .unwrap();
+        let ban_word_filter_id = imported.hide.ban_word_filter_id.unwrap();
+        let ban_word_filter = BanWordFilterDao::new(pool.clone())
+            .get(&ban_word_filter_id)
+            .await
+            .unwrap();
+        assert_eq!(ban_word_filter.ban_words, vec!["bad".to_string()]);
+
+        // The same nickname twice breaks the settings after the filter is in.
+        update_chat_settings.hide.nicknames = vec!["spammer".to_string(), "spammer".to_string()];
+        chat_settings_dao
+            .create_with_ban_word_filter("1", &update_chat_settings, &update_ban_word_filter)
+            .await
+            .unwrap_err();
+        let count: i64 = sqlx::query_scalar("SELECT count(id) FROM ban_word_filters")
+            .fetch_one(pool.as_ref())
+            .await
+            .unwrap();
+        assert_eq!(count, 1);
+    }
+
+    #[sqlx::test(migrations = "../migrations")]
+    async fn patch_applies_to_locked_settings(pool: PgPool) {
+        let pool = Arc::new(pool);
+        UserDao::new(pool.clone())
+            .get_or_create("1", "owner")
+            .await
+            .unwrap();
+        let chat_settings_dao = ChatSettingsDao::new(pool);
+        let chat_settings = chat_settings_dao
+            .create("1", "chat", &ChatType::Default)
+            .await
+            .unwrap();
+        let original_color = chat_settings.color.nickname_color;
+
+        // Another editor changes the color after the patch was sent.
+        let mut update_chat_settings = UpdateChatSettings::from(chat_settings.clone());
+        update_chat_settings.color.nickname_color = original_color + 1;
+        chat_settings_dao
+            .update(&chat_settings.id, &update_chat_settings, None)
+            .await
+            .unwrap();
+
+        let patched = chat_settings_dao
+            .patch(
+                &chat_settings.id,
+                |previous| {
+                    assert_eq!(previous.color.nickname_color, original_color + 1);
+                    let mut patched = previous.clone();
+                    patched.color.nickname_color = original_color;
+                    patched.name = "patched".to_string();
+                    Ok(patched)
+                },
+                None,
+            )
+            .await
+            .unwrap();
+        assert_eq!(patched.name, "patched");
+        assert_eq!(patched.color.nickname_color, original_color);
+    }
 }
@@ -0,0 +1,260 @@
+use std::sync::Arc;
+
+use axum::http::StatusCode;
+use chrono::NaiveDateTime;
+use sqlx::types::JsonValue;
+use sqlx::{Pool, Postgres};
+use tracing::instrument;
+use uuid::Uuid;
+
+use types::domain::{ChatSettingsTemplate, Page, PageRequest};
+use types::error::{AppError, AppResult};
+
+use crate::cursor::{Cursor, PageQuery};
+
+pub struct ChatSettingsTemplateDao {
+    pool: Arc<Pool<Postgres>>,
+}
+
+impl ChatSettingsTemplateDao {
+    pub fn new(pool: Arc<Pool<Postgres>>) -> Self {
+        ChatSettingsTemplateDao { pool }
+    }
+
+    #[instrument(skip(self, settings))]
+    pub async fn create(
+        &self,
+        user_id: &str,
+        name: &str,
+        description: &str,
+        schema_version: u32,
+        settings: &JsonValue,
+    ) -> AppResult<ChatSettingsTemplate> {
+        let raw_template = sqlx::query_as!(
+            RawChatSettingsTemplate,
+            r#"INSERT INTO chat_settings_templates (id, name, description, schema_version, settings, user_id) VALUES ($1, $2, $3, $4, $5, $6) RETURNING id, name, description, user_id, schema_version, settings, created_at"#,
+            Uuid::new_v4(),
+            name,
+            description,
+            schema_version as i32,
+            settings,
+            user_id,
+        )
+            .fetch_one(self.pool.as_ref())
+            .await
+            .map_err(|e| match e {
+                sqlx::Error::Database(dbe) if dbe.constraint() == Some("chat_settings_templates_pkey") => {
+                    ChatSettingsTemplateDao::ID_TAKEN_ERROR
+                }
+                _ => ChatSettingsTemplateDao::FAIL_QUERY_ERROR.clone().cause(e.into()),
+            })?;
+
+        Ok(raw_template.into())
+    }
+
+    #[instrument(skip(self))]
+    pub async fn get(&self, id: &Uuid) -> AppResult<ChatSettingsTemplate> {
+        let raw_template = sqlx::query_as!(
+            RawChatSettingsTemplate,
+            r#"SELECT id, name, description, user_id, schema_version, settings, created_at FROM chat_settings_templates WHERE id = $1 LIMIT 1"#,
+            id,
+        )
+            .fetch_optional(self.pool.as_ref())
+            .await
+            .map_err(|e| ChatSettingsTemplateDao::FAIL_QUERY_ERROR.clone().cause(e.into()))?;
+
+        match raw_template {
+            Some(raw_template) => Ok(raw_template.into()),
+            None => Err(ChatSettingsTemplateDao::NOT_FOUND_ERROR),
+        }
+    }
+
+    /// Built-in templates are the oldest ones. Templates never change, so
+    /// sorting by `updatedAt` sorts by creation.
+    #[instrument(skip(self))]
+    pub async fn get_all(
+        &self,
+        page_request: &PageRequest,
+    ) -> AppResult<Page<ChatSettingsTemplate>> {
+        let page_query = PageQuery::new(page_request)?;
+
+        let recs = sqlx::query!(
+            r#"SELECT id as "id!", name as "name!", description as "description!", user_id, schema_version as "schema_version!", settings as "settings!", created_at as "created_at!", sort_key as "sort_key!" FROM (SELECT id, name, description, user_id, schema_version, settings, created_at, CASE $1::varchar WHEN 'name' THEN lower(name) ELSE to_char(created_at, 'YYYY-MM-DD HH24:MI:SS.US') END AS sort_key FROM chat_settings_templates WHERE $2::varchar IS NULL OR name ILIKE $2) AS page WHERE $3::varchar IS NULL OR CASE WHEN $4 THEN (sort_key, id) < ($3, $5::uuid) ELSE (sort_key, id) > ($3, $5::uuid) END ORDER BY CASE WHEN $4 THEN sort_key END DESC, CASE WHEN $4 THEN id END DESC, sort_key, id LIMIT $6"#,
+            page_query.sort.to_str(),
+            page_query.search_pattern,
+            page_query.after.as_ref().map(|cursor| cursor.sort_key.clone()),
+            page_query.is_desc,
+            page_query.after.as_ref().map(|cursor| cursor.id),
+            page_query.limit,
+        )
+            .fetch_all(self.pool.as_ref())
+            .await
+            .map_err(|e| ChatSettingsTemplateDao::FAIL_QUERY_ERROR.clone().cause(e.into()))?;
+
+        let rows: Vec<(ChatSettingsTemplate, Cursor)> = recs
+            .into_iter()
+            .map(|rec| {
+                let cursor = Cursor {
+                    sort_key: rec.sort_key,
+                    id: rec.id,
+                };
+                let raw_template = RawChatSettingsTemplate {
+                    id: rec.id,
+                    name: rec.name,
+                    description: rec.description,
+                    user_id: rec.user_id,
+                    schema_version: rec.schema_version,
+                    settings: rec.settings,
+                    created_at: rec.created_at,
+                };
+                (raw_template.into(), cursor)
+            })
+            .collect();
+
+        Ok(page_query.page(rows))
+    }
+
+    #[instrument(skip(self))]
+    pub async fn delete(&self, id: &Uuid) -> AppResult {
+        let rec = sqlx::query!(r#"DELETE FROM chat_settings_templates WHERE id = $1"#, id)
+            .execute(self.pool.as_ref())
+            .await
+            .map_err(|e| {
+                ChatSettingsTemplateDao::FAIL_QUERY_ERROR
+                    .clone()
+                    .cause(e.into())
+            })?;
+
+        if rec.rows_affected() == 0 {
+            Err(ChatSettingsTemplateDao::NOT_FOUND_ERROR)
+        } else {
+            Ok(())
+        }
+    }
+}
+
+struct RawChatSettingsTemplate {
+    id: Uuid,
+    name: String,
+    description: String,
+    user_id: Option<String>,
+    schema_version: i32,
+    settings: JsonValue,
+    created_at: NaiveDateTime,
+}
+
+impl From<RawChatSettingsTemplate> for ChatSettingsTemplate {
+    fn from(raw: RawChatSettingsTemplate) -> Self {
+        ChatSettingsTemplate {
+            id: raw.id,
+            name: raw.name,
+            description: raw.description,
+            user_id: raw.user_id,
+            schema_version: raw.schema_version as u32,
+            settings: raw.settings,
+            created_at: raw.created_at,
+        }
+    }
+}
+
+macro_rules! chat_settings_template_dao_errors {
+    (
+        $(
+            $(#[$docs:meta])*
+            ($name:ident, $status:expr, $phrase:expr);
+        )+
+    ) => {
+        impl ChatSettingsTemplateDao {
+        $(
+            $(#[$docs])*
+            pub const $name: AppError = AppError {
+                status_code: $status,
+                message: Some($phrase),
+                cause: None,
+                other: None
+            };
+        )+
+        }
+    }
+}
+
+chat_settings_template_dao_errors! {
+    (FAIL_QUERY_ERROR, StatusCode::INTERNAL_SERVER_ERROR, "fail chat settings template query");
+    (ID_TAKEN_ERROR, StatusCode::CONFLICT, "id taken");
+    (NOT_FOUND_ERROR, StatusCode::NOT_FOUND, "chat settings template not found");
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use sqlx::types::JsonValue;
+    use sqlx::PgPool;
+
+    use types::domain::{PageRequest, SortBy, SortOrder};
+
+    use crate::{ChatSettingsTemplateDao, UserDao};
+
+    #[sqlx::test(migrations = "../migrations")]
+    async fn pages_built_in_templates_first(pool: PgPool) {
+        let pool = Arc::new(pool);
+        UserDao::new(pool.clone())
+            .get_or_create("1", "owner")
+            .await
+            .unwrap();
+        let chat_settings_template_dao = ChatSettingsTemplateDao::new(pool.clone());
+
+        let published = chat_settings_template_dao
+            .create("1", "Aurora", "", 2, &JsonValue::Null)
+            .await
+            .unwrap();
+
+        let mut page_request = PageRequest {
+            limit: 2,
+            cursor: None,
+            sort: SortBy::CreatedAt,
+            order: SortOrder::Asc,
+            search: None,
+        };
+        let first_page = chat_settings_template_dao
+            .get_all(&page_request)
+            .await
+            .unwrap();
+        assert_eq!(first_page.items.len(), 2);
+        assert!(first_page
+            .items
+            .iter()
+            .all(|template| template.user_id.is_none()));
+
+        page_request.cursor = first_page.next_cursor;
+        let second_page = chat_settings_template_dao
+            .get_all(&page_request)
+            .await
+            .unwrap();
+        let ids: Vec<_> = second_page
+            .items
+            .iter()
+            .map(|template| template.id)
+            .collect();
+        assert_eq!(ids.len(), 2);
+        assert_eq!(ids[1], published.id);
+        assert_eq!(second_page.next_cursor, None);
+
+        let by_name = chat_settings_template_dao
+            .get_all(&PageRequest {
+                limit: 20,
+                cursor: None,
+                sort: SortBy::Name,
+                order: SortOrder::Asc,
+                search: Some("u".to_string()),
+            })
+            .await
+            .unwrap();
+        let names: Vec<_> = by_name
+            .items
+            .iter()
+            .map(|template| template.name.as_str())
+            .collect();
+        assert_eq!(names, vec!["Aurora", "Bubbles"]);
+    }
+}
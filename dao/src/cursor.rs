@@ -0,0 +1,229 @@
+use axum::http::StatusCode;
+use uuid::Uuid;
+
+use types::domain::{Page, PageRequest, SortBy, SortOrder};
+use types::error::{AppError, AppResult};
+
+/// Position after the last item of a page: the sort key of the item and its
+/// id, which orders items with the same key. The encoded cursor also names
+/// the sort it was made for, so it can't be used with another one.
+pub(crate) struct Cursor {
+    pub sort_key: String,
+    pub id: Uuid,
+}
+
+impl Cursor {
+    pub(crate) fn encode(&self, sort: SortBy, is_desc: bool) -> String {
+        hex::encode(format!(
+            "{}:{}:{}:{}",
+            sort.to_str(),
+            order(is_desc),
+            self.id,
+            self.sort_key
+        ))
+    }
+
+    pub(crate) fn decode(cursor: &str, sort: SortBy, is_desc: bool) -> AppResult<Cursor> {
+        let decoded = hex::decode(cursor)
+            .ok()
+            .and_then(|bytes| String::from_utf8(bytes).ok())
+            .ok_or(Cursor::INVALID_CURSOR_ERROR)?;
+        // The sort key goes last, it may contain colons itself.
+        let mut parts = decoded.splitn(4, ':');
+        let (Some(cursor_sort), Some(cursor_order), Some(id), Some(sort_key)) =
+            (parts.next(), parts.next(), parts.next(), parts.next())
+        else {
+            return Err(Cursor::INVALID_CURSOR_ERROR);
+        };
+
+        if cursor_sort != sort.to_str() || cursor_order != order(is_desc) {
+            return Err(Cursor::INVALID_CURSOR_ERROR);
+        }
+
+        Ok(Cursor {
+            sort_key: sort_key.to_string(),
+            id: Uuid::parse_str(id).map_err(|_| Cursor::INVALID_CURSOR_ERROR)?,
+        })
+    }
+}
+
+fn order(is_desc: bool) -> &'static str {
+    if is_desc {
+        "desc"
+    } else {
+        "asc"
+    }
+}
+
+/// Bind values of a page request, shared by the list queries.
+pub(crate) struct PageQuery {
+    pub sort: SortBy,
+    pub is_desc: bool,
+    pub search_pattern: Option<String>,
+    pub after: Option<Cursor>,
+    /// One more than requested, to know if there is a next page.
+    pub limit: i64,
+}
+
+impl PageQuery {
+    pub(crate) fn new(page_request: &PageRequest) -> AppResult<PageQuery> {
+        let is_desc = page_request.order == SortOrder::Desc;
+        let after = match &page_request.cursor {
+            Some(cursor) => Some(Cursor::decode(cursor, page_request.sort, is_desc)?),
+            None => None,
+        };
+
+        Ok(PageQuery {
+            sort: page_request.sort,
+            is_desc,
+            search_pattern: page_request.search.as_deref().map(search_pattern),
+            after,
+            limit: page_request.limit + 1,
+        })
+    }
+
+    /// Page of the fetched rows, with the cursor of the last item if there
+    /// were more rows than requested.
+    pub(crate) fn page<T>(&self, mut rows: Vec<(T, Cursor)>) -> Page<T> {
+        let next_cursor = if rows.len() as i64 >= self.limit {
+            rows.truncate((self.limit - 1) as usize);
+            rows.last()
+                .map(|(_, cursor)| cursor.encode(self.sort, self.is_desc))
+        } else {
+            None
+        };
+
+        Page {
+            items: rows.into_iter().map(|(item, _)| item).collect(),
+            next_cursor,
+        }
+    }
+}
+
+/// `ILIKE` pattern matching the search anywhere in the name.
+fn search_pattern(search: &str) -> String {
+    let escaped = search
+        .replace('\\', "\\\\")
+        .replace('%', "\\%")
+        .replace('_', "\\_");
+
+    format!("%{}%", escaped)
+}
+
+macro_rules! cursor_errors {
+    (
+        $(
+            $(#[$docs:meta])*
+            ($name:ident, $status:expr, $phrase:expr);
+        )+
+    ) => {
+        impl Cursor {
+        $(
+            $(#[$docs])*
+            pub const $name: AppError = AppError {
+                status_code: $status,
+                message: Some($phrase),
+                cause: None,
+                other: None
+            };
+        )+
+        }
+    }
+}
+
+cursor_errors! {
+    (INVALID_CURSOR_ERROR, StatusCode::BAD_REQUEST, "invalid cursor");
+}
+
+#[cfg(test)]
+mod tests {
+    use uuid::Uuid;
+
+    use types::domain::{PageRequest, SortBy, SortOrder};
+
+    use crate::cursor::{search_pattern, Cursor, PageQuery};
+
+    fn page_request(cursor: Option<String>, sort: SortBy, order: SortOrder) -> PageRequest {
+        PageRequest {
+            limit: 2,
+            cursor,
+            sort,
+            order,
+            search: None,
+        }
+    }
+
+    #[test]
+    fn encode_decode() {
+        let cursor = Cursor {
+            sort_key: "2023-10-12 12:00:00.000000".to_string(),
+            id: Uuid::new_v4(),
+        };
+
+        let decoded = Cursor::decode(
+            &cursor.encode(SortBy::UpdatedAt, true),
+            SortBy::UpdatedAt,
+            true,
+        )
+        .unwrap();
+        assert_eq!(decoded.sort_key, cursor.sort_key);
+        assert_eq!(decoded.id, cursor.id);
+    }
+
+    #[test]
+    fn decode_rejects_other_sort_and_garbage() {
+        let cursor = Cursor {
+            sort_key: "name".to_string(),
+            id: Uuid::new_v4(),
+        }
+        .encode(SortBy::Name, false);
+
+        assert!(Cursor::decode(&cursor, SortBy::CreatedAt, false).is_err());
+        assert!(Cursor::decode(&cursor, SortBy::Name, true).is_err());
+        for garbage in [
+            "",
+            "zz",
+            &hex::encode("name:asc:not-a-uuid:name"),
+            &hex::encode("name:asc"),
+        ] {
+            let error = Cursor::decode(garbage, SortBy::Name, false).err().unwrap();
+            assert_eq!(error.message, Cursor::INVALID_CURSOR_ERROR.message);
+        }
+
+        let mismatched = page_request(Some(cursor), SortBy::Name, SortOrder::Desc);
+        assert!(PageQuery::new(&mismatched).is_err());
+    }
+
+    #[test]
+    fn page() {
+        let page_query = PageQuery::new(&page_request(None, SortBy::Name, SortOrder::Asc)).unwrap();
+        let rows = |count: usize| -> Vec<(usize, Cursor)> {
+            (0..count)
+                .map(|i| {
+                    (
+                        i,
+                        Cursor {
+                            sort_key: i.to_string(),
+                            id: Uuid::nil(),
+                        },
+                    )
+                })
+                .collect()
+        };
+
+        let last = page_query.page(rows(2));
+        assert_eq!(last.items, vec![0, 1]);
+        assert_eq!(last.next_cursor, None);
+
+        let more = page_query.page(rows(3));
+        assert_eq!(more.items, vec![0, 1]);
+        let next_cursor = Cursor::decode(&more.next_cursor.unwrap(), SortBy::Name, false).unwrap();
+        assert_eq!(next_cursor.sort_key, "1");
+    }
+
+    #[test]
+    fn search_pattern_escapes_wildcards() {
+        assert_eq!(search_pattern("chat"), "%chat%");
+        assert_eq!(search_pattern("100%_off\\"), "%100\\%\\_off\\\\%");
+    }
+}
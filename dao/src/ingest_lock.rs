@@ -0,0 +1,204 @@
+use std::collections::HashSet;
+use std::sync::Arc;
+
+use axum::http::StatusCode;
+use sqlx::{Connection, PgConnection, Pool, Postgres};
+use tokio::sync::Mutex;
+use tracing::instrument;
+
+use types::error::{AppError, AppResult};
+
+/// Postgres session advisory locks, so only one instance sharing the database
+/// does a job at a time. Every lock of the instance is held by one dedicated
+/// connection, outside of the pool.
+pub struct IngestLockDao {
+    pool: Arc<Pool<Postgres>>,
+    session: Arc<Mutex<LockSession>>,
+}
+
+#[derive(Default)]
+struct LockSession {
+    connection: Option<PgConnection>,
+    /// Bumped on every new connection, locks of older ones are gone.
+    generation: u64,
+    keys: HashSet<String>,
+}
+
+impl LockSession {
+    /// Drops the connection and with it every lock taken on it.
+    fn reset(&mut self) {
+        self.connection = None;
+        self.keys.clear();
+    }
+}
+
+impl IngestLockDao {
+    pub fn new(pool: Arc<Pool<Postgres>>) -> Self {
+        IngestLockDao {
+            pool,
+            session: Arc::new(Mutex::new(LockSession::default())),
+        }
+    }
+
+    /// `None` when another instance, or another job of this one, holds the
+    /// lock. The lock lives as long as the lock connection, so it's released
+    /// when the holder dies.
+    #[instrument(skip(self))]
+    pub async fn try_lock(&self, key: &str) -> AppResult<Option<IngestLock>> {
+        let mut session = self.session.lock().await;
+
+        // Advisory locks are reentrant within a session.
+        if session.keys.contains(key) {
+            return Ok(None);
+        }
+
+        if session.connection.is_none() {
+            let connection = PgConnection::connect_with(&self.pool.connect_options())
+                .await
+                .map_err(|e| IngestLockDao::FAIL_LOCK_ERROR.clone().cause(e.into()))?;
+            session.connection = Some(connection);
+            session.generation += 1;
+        }
+        let connection = session.connection.as_mut().ok_or(AppError::UNEXPECTED)?;
+
+        // 64 bits of the md5 keep different keys from sharing a lock.
+        let is_locked = match sqlx::query_scalar!(
+            r#"SELECT pg_try_advisory_lock(('x' || left(md5($1), 16))::bit(64)::bigint) AS "is_locked!""#,
+            key
+        )
+        .fetch_one(connection)
+        .await
+        {
+            Ok(is_locked) => is_locked,
+            Err(e) => {
+                session.reset();
+                return Err(IngestLockDao::FAIL_LOCK_ERROR.clone().cause(e.into()));
+            }
+        };
+
+        if !is_locked {
+            return Ok(None);
+        }
+
+        session.keys.insert(key.to_string());
+        Ok(Some(IngestLock {
+            key: key.to_string(),
+            generation: session.generation,
+            session: self.session.clone(),
+        }))
+    }
+}
+
+pub struct IngestLock {
+    key: String,
+    generation: u64,
+    session: Arc<Mutex<LockSession>>,
+}
+
+impl IngestLock {
+    /// The lock is gone with the lock connection, e.g. after a database restart.
+    #[instrument(skip(self), fields(key = %self.key))]
+    pub async fn is_held(&mut self) -> bool {
+        let mut session = self.session.lock().await;
+        if session.generation != self.generation {
+            return false;
+        }
+
+        let Some(connection) = session.connection.as_mut() else {
+            return false;
+        };
+        if connection.ping().await.is_err() {
+            session.reset();
+            return false;
+        }
+
+        true
+    }
+
+    #[instrument(skip(self), fields(key = %self.key))]
+    pub async fn release(self) {
+        let mut session = self.session.lock().await;
+        if session.generation != self.generation {
+            return;
+        }
+        let Some(connection) = session.connection.as_mut() else {
+            return;
+        };
+
+        let result = sqlx::query_scalar!(
+            r#"SELECT pg_advisory_unlock(('x' || left(md5($1), 16))::bit(64)::bigint) AS "is_unlocked!""#,
+            self.key
+        )
+        .fetch_one(connection)
+        .await;
+
+        match result {
+            Ok(_) => {
+                session.keys.remove(&self.key);
+            }
+            Err(e) => {
+                // Without the connection no lock of it is held anymore.
+                tracing::warn!({ error = %e }, "fail release ingest lock");
+                session.reset();
+            }
+        }
+    }
+}
+
+macro_rules! ingest_lock_dao_errors {
+    (
+        $(
+            $(#[$docs:meta])*
+            ($name:ident, $status:expr, $phrase:expr);
+        )+
+    ) => {
+        impl IngestLockDao {
+        $(
+            $(#[$docs])*
+            pub const $name: AppError = AppError {
+                status_code: $status,
+                message: Some($phrase),
+                cause: None,
+                other: None
+            };
+        )+
+        }
+    }
+}
+
+ingest_lock_dao_errors! {
+    (FAIL_LOCK_ERROR, StatusCode::INTERNAL_SERVER_ERROR, "fail take ingest lock");
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use sqlx::PgPool;
+
+    use crate::IngestLockDao;
+
+    #[sqlx::test(migrations = "../migrations")]
+    async fn one_holder_per_key(pool: PgPool) {
+        let pool = Arc::new(pool);
+        // Two instances sharing the database.
+        let first = IngestLockDao::new(pool.clone());
+        let second = IngestLockDao::new(pool.clone());
+
+        let mut lock = first.try_lock("a").await.unwrap().unwrap();
+        assert!(lock.is_held().await);
+        assert!(first.try_lock("a").await.unwrap().is_none());
+        assert!(second.try_lock("a").await.unwrap().is_none());
+        let other_lock = second.try_lock("b").await.unwrap().unwrap();
+
+        lock.release().await;
+        let mut lock = second.try_lock("a").await.unwrap().unwrap();
+        assert!(lock.is_held().await);
+        assert!(first.try_lock("a").await.unwrap().is_none());
+
+        // Both locks share one connection, and none is taken from the pool.
+        other_lock.release().await;
+        assert!(lock.is_held().await);
+        assert_eq!(pool.num_idle(), pool.size() as usize);
+    }
+}
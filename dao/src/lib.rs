@@ -1,13 +1,20 @@
 pub use ban_word_filter::*;
 pub use chat_settings::*;
+pub use chat_settings_template::*;
 pub use database::*;
+pub use ingest_lock::*;
+pub use notification::*;
 pub use token::*;
 pub use twitch_data::*;
 pub use user::*;
 
 mod ban_word_filter;
 mod chat_settings;
+mod chat_settings_template;
+mod cursor;
 mod database;
+mod ingest_lock;
+mod notification;
 mod token;
 mod twitch_data;
 mod user;
@@ -0,0 +1,80 @@
+use std::sync::Arc;
+
+use axum::http::StatusCode;
+use sqlx::postgres::PgListener;
+use sqlx::{Pool, Postgres};
+use tracing::instrument;
+
+use types::error::{AppError, AppResult};
+
+/// Postgres refuses payloads of this size and above.
+const MAX_PAYLOAD_SIZE: usize = 8000;
+
+/// Postgres `LISTEN/NOTIFY`, to reach every instance sharing the database.
+pub struct NotificationDao {
+    pool: Arc<Pool<Postgres>>,
+}
+
+impl NotificationDao {
+    pub fn new(pool: Arc<Pool<Postgres>>) -> Self {
+        NotificationDao { pool }
+    }
+
+    #[instrument(skip(self))]
+    pub async fn notify(&self, channel: &str, payload: &str) -> AppResult {
+        if payload.len() >= MAX_PAYLOAD_SIZE {
+            return Err(NotificationDao::PAYLOAD_TOO_LARGE_ERROR
+                .clone()
+                .other("size".to_string(), payload.len().into()));
+        }
+
+        sqlx::query!(r#"SELECT pg_notify($1, $2)"#, channel, payload)
+            .execute(self.pool.as_ref())
+            .await
+            .map_err(|e| NotificationDao::FAIL_NOTIFY_ERROR.clone().cause(e.into()))?;
+
+        Ok(())
+    }
+
+    /// The listener reconnects by itself, notifications sent meanwhile are lost.
+    #[instrument(skip(self))]
+    pub async fn listen(&self, channel: &str) -> AppResult<PgListener> {
+        let mut listener = PgListener::connect_with(self.pool.as_ref())
+            .await
+            .map_err(|e| NotificationDao::FAIL_LISTEN_ERROR.clone().cause(e.into()))?;
+
+        listener
+            .listen(channel)
+            .await
+            .map_err(|e| NotificationDao::FAIL_LISTEN_ERROR.clone().cause(e.into()))?;
+
+        Ok(listener)
+    }
+}
+
+macro_rules! notification_dao_errors {
+    (
+        $(
+            $(#[$docs:meta])*
+            ($name:ident, $status:expr, $phrase:expr);
+        )+
+    ) => {
+        impl NotificationDao {
+        $(
+            $(#[$docs])*
+            pub const $name: AppError = AppError {
+                status_code: $status,
+                message: Some($phrase),
+                cause: None,
+                other: None
+            };
+        )+
+        }
+    }
+}
+
+notification_dao_errors! {
+    (FAIL_NOTIFY_ERROR, StatusCode::INTERNAL_SERVER_ERROR, "fail send notification");
+    (FAIL_LISTEN_ERROR, StatusCode::INTERNAL_SERVER_ERROR, "fail listen notifications");
+    (PAYLOAD_TOO_LARGE_ERROR, StatusCode::INTERNAL_SERVER_ERROR, "notification payload too large");
+}
@@ -23,7 +23,7 @@ impl TokenDao {
     #[instrument(skip(self))]
     pub async fn create(&self, user_id: &str, user_agent: &str, ip: &str) -> AppResult<Token> {
         let now = Utc::now().naive_utc();
-        let encrypted_ip = self.crypt.encrypt_str(&ip);
+        let encrypted_ip = self.crypt.encrypt_str(ip);
 
         let raw_token = sqlx::query_as!(
             RawToken,
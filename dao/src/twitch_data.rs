@@ -28,6 +28,23 @@ impl TwitchDataDao {
         }
     }
 
+    #[instrument(skip(self))]
+    pub async fn get(&self, user_id: &str) -> AppResult<twitch::Data> {
+        let raw_twitch_data = sqlx::query_as!(
+            RawTwitchData,
+            r#"SELECT user_id, refresh_token FROM twitch_data WHERE user_id = $1 LIMIT 1"#,
+            user_id,
+        )
+        .fetch_one(self.pool.as_ref())
+        .await
+        .map_err(|e| match e {
+            sqlx::Error::RowNotFound => TwitchDataDao::NOT_FOUND_ERROR,
+            _ => TwitchDataDao::FAIL_QUERY_ERROR.clone().cause(e.into()),
+        })?;
+
+        Ok(raw_twitch_data.into())
+    }
+
     #[instrument(skip_all)]
     async fn create(&self, user_id: &str, refresh_token: &str) -> AppResult<twitch::Data> {
         let raw_twitch_data = sqlx::query_as!(
@@ -69,11 +86,11 @@ struct RawTwitchData {
     refresh_token: String,
 }
 
-impl Into<twitch::Data> for RawTwitchData {
-    fn into(self) -> twitch::Data {
+impl From<RawTwitchData> for twitch::Data {
+    fn from(raw: RawTwitchData) -> Self {
         twitch::Data {
-            user_id: self.user_id,
-            refresh_token: self.refresh_token,
+            user_id: raw.user_id,
+            refresh_token: raw.refresh_token,
         }
     }
 }
@@ -102,4 +119,5 @@ macro_rules! twitch_data_dao_errors {
 twitch_data_dao_errors! {
     (FAIL_QUERY_ERROR, StatusCode::INTERNAL_SERVER_ERROR, "fail twitch data query");
     (USER_ID_TAKEN_ERROR, StatusCode::CONFLICT, "user id taken");
+    (NOT_FOUND_ERROR, StatusCode::NOT_FOUND, "twitch data not found");
 }
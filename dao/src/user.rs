@@ -69,12 +69,12 @@ struct RawUser {
     created_at: NaiveDateTime,
 }
 
-impl Into<User> for RawUser {
-    fn into(self) -> User {
+impl From<RawUser> for User {
+    fn from(raw: RawUser) -> Self {
         User {
-            id: self.id,
-            username: self.username,
-            created_at: self.created_at,
+            id: raw.id,
+            username: raw.username,
+            created_at: raw.created_at,
         }
     }
 }
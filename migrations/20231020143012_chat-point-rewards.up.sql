@@ -0,0 +1,10 @@
+-- Add up migration script here
+CREATE TABLE
+    IF NOT EXISTS chat_point_rewards (
+        chat_settings_id uuid,
+        reward_id varchar NOT NULL,
+        hidden boolean NOT NULL DEFAULT false,
+        highlight_color bigint NULL DEFAULT NULL,
+        PRIMARY KEY (chat_settings_id, reward_id),
+        FOREIGN KEY (chat_settings_id) REFERENCES chat_settings(id) ON DELETE CASCADE ON UPDATE CASCADE
+    );
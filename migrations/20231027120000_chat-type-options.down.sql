@@ -0,0 +1,3 @@
+-- Add down migration script here
+ALTER TABLE IF EXISTS chat_settings
+    DROP COLUMN IF EXISTS chat_type_options;
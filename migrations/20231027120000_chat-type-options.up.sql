@@ -0,0 +1,3 @@
+-- Add up migration script here
+ALTER TABLE IF EXISTS chat_settings
+    ADD COLUMN IF NOT EXISTS chat_type_options jsonb NOT NULL DEFAULT '{}';
@@ -0,0 +1,8 @@
+-- Add down migration script here
+ALTER TABLE IF EXISTS chat_settings
+    DROP COLUMN IF EXISTS message_ttl,
+    DROP COLUMN IF EXISTS enter_animation,
+    DROP COLUMN IF EXISTS enter_duration,
+    DROP COLUMN IF EXISTS exit_animation,
+    DROP COLUMN IF EXISTS exit_duration,
+    DROP COLUMN IF EXISTS stacking_direction;
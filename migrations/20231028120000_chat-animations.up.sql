@@ -0,0 +1,8 @@
+-- Add up migration script here
+ALTER TABLE IF EXISTS chat_settings
+    ADD COLUMN IF NOT EXISTS message_ttl integer NOT NULL DEFAULT 0,
+    ADD COLUMN IF NOT EXISTS enter_animation varchar NOT NULL DEFAULT 'none',
+    ADD COLUMN IF NOT EXISTS enter_duration integer NOT NULL DEFAULT 300,
+    ADD COLUMN IF NOT EXISTS exit_animation varchar NOT NULL DEFAULT 'none',
+    ADD COLUMN IF NOT EXISTS exit_duration integer NOT NULL DEFAULT 300,
+    ADD COLUMN IF NOT EXISTS stacking_direction varchar NOT NULL DEFAULT 'auto';
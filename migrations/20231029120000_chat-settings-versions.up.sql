@@ -0,0 +1,10 @@
+-- Add up migration script here
+CREATE TABLE
+    IF NOT EXISTS chat_settings_versions (
+        chat_settings_id uuid,
+        version integer NOT NULL,
+        settings jsonb NOT NULL,
+        created_at timestamp NOT NULL DEFAULT now(),
+        PRIMARY KEY (chat_settings_id, version),
+        FOREIGN KEY (chat_settings_id) REFERENCES chat_settings(id) ON DELETE CASCADE ON UPDATE CASCADE
+    );
@@ -0,0 +1,37 @@
+-- Add up migration script here
+CREATE TABLE
+    IF NOT EXISTS chat_settings_templates (
+        id uuid PRIMARY KEY,
+        name varchar NOT NULL,
+        description varchar NOT NULL DEFAULT '',
+        schema_version integer NOT NULL,
+        settings jsonb NOT NULL,
+        user_id varchar NULL DEFAULT NULL,
+        created_at timestamp NOT NULL DEFAULT now(),
+        FOREIGN KEY (user_id) REFERENCES users(id) ON DELETE CASCADE ON UPDATE CASCADE
+    );
+
+-- Built-in templates have no user
+INSERT INTO chat_settings_templates (id, name, description, schema_version, settings) VALUES
+    (
+        '6f3d1b7e-2a41-4c3e-9d5b-0a1c2e3f4a51',
+        'Classic',
+        'Dark translucent blocks with the newest message at the bottom.',
+        2,
+        '{"name": "Classic", "chatType": "block", "chatTypeOptions": {}, "color": {"nicknameColor": 3923116031, "backgroundColor": 67372185, "textColor": 3873892095, "gradientOnlyForCustomNicknames": false, "customNicknames": []}, "size": {"marginTop": 2, "marginRight": 2, "marginBottom": 2, "marginLeft": 2, "paddingTop": 2, "paddingRight": 2, "paddingBottom": 2, "paddingLeft": 2, "borderTopLeftRadius": 2, "borderTopRightRadius": 2, "borderBottomLeftRadius": 2, "borderBottomRightRadius": 2, "maxMessages": 50}, "hide": {"hideMessagePattern": "!", "hidePointRewards": false, "pointRewards": [], "hideLinks": true, "linkReplacement": "<ссылка>", "banWordReplacement": "***", "nicknames": [], "banWordFilterId": null}, "font": {"fontFamily": "", "nicknameFontWeight": 500, "textFontWeight": 400, "fontSize": 3.5}, "animation": {"messageTtl": 0, "enterAnimation": "none", "enterDuration": 300, "exitAnimation": "none", "exitDuration": 300, "stackingDirection": "auto"}}'
+    ),
+    (
+        '6f3d1b7e-2a41-4c3e-9d5b-0a1c2e3f4a52',
+        'Bubbles',
+        'Speech bubbles on alternating sides that slide in and fade out after a minute.',
+        2,
+        '{"name": "Bubbles", "chatType": "bubble", "chatTypeOptions": {"bubble": {"maxWidth": 70.0, "alternateSides": true, "showTail": true}}, "color": {"nicknameColor": 4294967295, "backgroundColor": 1718013183, "textColor": 4294967295, "gradientOnlyForCustomNicknames": false, "customNicknames": []}, "size": {"marginTop": 1.5, "marginRight": 1.5, "marginBottom": 1.5, "marginLeft": 1.5, "paddingTop": 2, "paddingRight": 2, "paddingBottom": 2, "paddingLeft": 2, "borderTopLeftRadius": 3, "borderTopRightRadius": 3, "borderBottomLeftRadius": 0, "borderBottomRightRadius": 3, "maxMessages": 20}, "hide": {"hideMessagePattern": "!", "hidePointRewards": false, "pointRewards": [], "hideLinks": true, "linkReplacement": "<ссылка>", "banWordReplacement": "***", "nicknames": [], "banWordFilterId": null}, "font": {"fontFamily": "Nunito", "nicknameFontWeight": 800, "textFontWeight": 500, "fontSize": 3.5}, "animation": {"messageTtl": 60, "enterAnimation": "slide-up", "enterDuration": 250, "exitAnimation": "fade", "exitDuration": 400, "stackingDirection": "auto"}}'
+    ),
+    (
+        '6f3d1b7e-2a41-4c3e-9d5b-0a1c2e3f4a53',
+        'Ticker',
+        'Single line scrolling along the bottom of the screen.',
+        2,
+        '{"name": "Ticker", "chatType": "ticker", "chatTypeOptions": {"ticker": {"speed": 12.0, "gap": 6.0}}, "color": {"nicknameColor": 4291319807, "backgroundColor": 204, "textColor": 4294967295, "gradientOnlyForCustomNicknames": false, "customNicknames": []}, "size": {"marginTop": 0, "marginRight": 0, "marginBottom": 0, "marginLeft": 0, "paddingTop": 1, "paddingRight": 1, "paddingBottom": 1, "paddingLeft": 1, "borderTopLeftRadius": 0, "borderTopRightRadius": 0, "borderBottomLeftRadius": 0, "borderBottomRightRadius": 0, "maxMessages": 30}, "hide": {"hideMessagePattern": "!", "hidePointRewards": false, "pointRewards": [], "hideLinks": true, "linkReplacement": "<ссылка>", "banWordReplacement": "***", "nicknames": [], "banWordFilterId": null}, "font": {"fontFamily": "Roboto", "nicknameFontWeight": 700, "textFontWeight": 400, "fontSize": 4.0}, "animation": {"messageTtl": 0, "enterAnimation": "none", "enterDuration": 300, "exitAnimation": "none", "exitDuration": 300, "stackingDirection": "auto"}}'
+    )
+ON CONFLICT (id) DO NOTHING;
@@ -0,0 +1,8 @@
+-- Add down migration script here
+ALTER TABLE IF EXISTS chat_settings
+    DROP COLUMN IF EXISTS created_at,
+    DROP COLUMN IF EXISTS updated_at;
+
+ALTER TABLE IF EXISTS ban_word_filters
+    DROP COLUMN IF EXISTS created_at,
+    DROP COLUMN IF EXISTS updated_at;
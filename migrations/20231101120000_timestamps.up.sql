@@ -0,0 +1,8 @@
+-- Add up migration script here
+ALTER TABLE IF EXISTS chat_settings
+    ADD COLUMN IF NOT EXISTS created_at timestamp NOT NULL DEFAULT now(),
+    ADD COLUMN IF NOT EXISTS updated_at timestamp NOT NULL DEFAULT now();
+
+ALTER TABLE IF EXISTS ban_word_filters
+    ADD COLUMN IF NOT EXISTS created_at timestamp NOT NULL DEFAULT now(),
+    ADD COLUMN IF NOT EXISTS updated_at timestamp NOT NULL DEFAULT now();
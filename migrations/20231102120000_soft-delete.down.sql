@@ -0,0 +1,6 @@
+-- Add down migration script here
+ALTER TABLE IF EXISTS chat_settings
+    DROP COLUMN IF EXISTS deleted_at;
+
+ALTER TABLE IF EXISTS ban_word_filters
+    DROP COLUMN IF EXISTS deleted_at;
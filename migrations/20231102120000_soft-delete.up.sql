@@ -0,0 +1,6 @@
+-- Add up migration script here
+ALTER TABLE IF EXISTS chat_settings
+    ADD COLUMN IF NOT EXISTS deleted_at timestamp NULL DEFAULT NULL;
+
+ALTER TABLE IF EXISTS ban_word_filters
+    ADD COLUMN IF NOT EXISTS deleted_at timestamp NULL DEFAULT NULL;
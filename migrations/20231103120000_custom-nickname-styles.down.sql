@@ -0,0 +1,7 @@
+-- Add down migration script here
+ALTER TABLE IF EXISTS chat_custom_nicknames
+    DROP COLUMN IF EXISTS user_id,
+    DROP COLUMN IF EXISTS middle_colors,
+    DROP COLUMN IF EXISTS display_name,
+    DROP COLUMN IF EXISTS badge_url,
+    DROP COLUMN IF EXISTS font_weight;
@@ -0,0 +1,7 @@
+-- Add up migration script here
+ALTER TABLE IF EXISTS chat_custom_nicknames
+    ADD COLUMN IF NOT EXISTS user_id varchar NULL DEFAULT NULL,
+    ADD COLUMN IF NOT EXISTS middle_colors jsonb NOT NULL DEFAULT '[]',
+    ADD COLUMN IF NOT EXISTS display_name varchar NULL DEFAULT NULL,
+    ADD COLUMN IF NOT EXISTS badge_url varchar NULL DEFAULT NULL,
+    ADD COLUMN IF NOT EXISTS font_weight integer NULL DEFAULT NULL;
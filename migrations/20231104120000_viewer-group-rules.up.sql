@@ -0,0 +1,13 @@
+-- Add up migration script here
+CREATE TABLE
+    IF NOT EXISTS chat_viewer_group_rules (
+        chat_settings_id uuid,
+        viewer_group varchar NOT NULL,
+        hidden boolean NOT NULL DEFAULT false,
+        exempt_from_ban_words boolean NOT NULL DEFAULT false,
+        nickname_color bigint NULL DEFAULT NULL,
+        background_color bigint NULL DEFAULT NULL,
+        font_weight integer NULL DEFAULT NULL,
+        PRIMARY KEY (chat_settings_id, viewer_group),
+        FOREIGN KEY (chat_settings_id) REFERENCES chat_settings(id) ON DELETE CASCADE ON UPDATE CASCADE
+    );
@@ -31,7 +31,13 @@ impl AuthService {
             user_dao,
             twitch_data_dao,
             token_dao,
-            scope: vec![],
+            scope: vec![
+                Scope::BITS_READ,
+                Scope::CHANNEL_READ_REDEMPTIONS,
+                Scope::CHANNEL_READ_SUBSCRIPTIONS,
+                Scope::MODERATOR_READ_FOLLOWERS,
+                Scope::USER_READ_CHAT,
+            ],
         }
     }
 
@@ -5,17 +5,24 @@ use tracing::instrument;
 use uuid::Uuid;
 
 use dao::BanWordFilterDao;
-use types::domain::{BanWordFilter, BanWordFilterInfo, UpdateBanWordFilter};
+use types::domain::{
+    BanWordFilter, BanWordFilterInfo, BanWordFilterUsage, Page, PageRequest, SettingsChange,
+    UpdateBanWordFilter,
+};
 use types::error::{AppError, AppResult};
 
+use crate::SettingsBus;
+
 pub struct BanWordService {
     ban_word_filter_dao: Arc<BanWordFilterDao>,
+    settings_bus: Arc<SettingsBus>,
 }
 
 impl BanWordService {
-    pub fn new(ban_word_filter_dao: Arc<BanWordFilterDao>) -> Self {
+    pub fn new(ban_word_filter_dao: Arc<BanWordFilterDao>, settings_bus: Arc<SettingsBus>) -> Self {
         BanWordService {
             ban_word_filter_dao,
+            settings_bus,
         }
     }
 
@@ -30,8 +37,14 @@ impl BanWordService {
     }
 
     #[instrument(skip(self))]
-    pub async fn get_all_filters(&self, user_id: &str) -> AppResult<Vec<BanWordFilterInfo>> {
-        self.ban_word_filter_dao.get_all_by_user_id(user_id).await
+    pub async fn get_all_filters(
+        &self,
+        user_id: &str,
+        page_request: &PageRequest,
+    ) -> AppResult<Page<BanWordFilterInfo>> {
+        self.ban_word_filter_dao
+            .get_all_by_user_id(user_id, page_request)
+            .await
     }
 
     #[instrument(skip(self))]
@@ -40,21 +53,79 @@ impl BanWordService {
         user_id: &str,
         ban_word_filter_id: &Uuid,
         update_ban_word_filter: &UpdateBanWordFilter,
+        expected_revision: Option<i32>,
     ) -> AppResult<BanWordFilter> {
         self.check_user_owning_of_filter_by_id(user_id, ban_word_filter_id)
             .await?;
 
+        let ban_word_filter = self
+            .ban_word_filter_dao
+            .update(
+                ban_word_filter_id,
+                update_ban_word_filter,
+                expected_revision,
+            )
+            .await?;
+
+        self.settings_bus
+            .publish(SettingsChange::BanWordFilter {
+                id: *ban_word_filter_id,
+            })
+            .await;
+
+        Ok(ban_word_filter)
+    }
+
+    /// Returns the chat settings which still use the filter, they stop
+    /// hiding its ban words and lose it once the filter is purged.
+    #[instrument(skip(self))]
+    pub async fn delete_filter(
+        &self,
+        user_id: &str,
+        ban_word_filter_id: &Uuid,
+        expected_revision: Option<i32>,
+    ) -> AppResult<Vec<BanWordFilterUsage>> {
+        self.check_user_owning_of_filter_by_id(user_id, ban_word_filter_id)
+            .await?;
+
+        self.ban_word_filter_dao
+            .delete(ban_word_filter_id, expected_revision)
+            .await?;
+
+        self.settings_bus
+            .publish(SettingsChange::BanWordFilter {
+                id: *ban_word_filter_id,
+            })
+            .await;
+
         self.ban_word_filter_dao
-            .update(ban_word_filter_id, update_ban_word_filter)
+            .get_usages(ban_word_filter_id)
             .await
     }
 
     #[instrument(skip(self))]
-    pub async fn delete_filter(&self, user_id: &str, ban_word_filter_id: &Uuid) -> AppResult {
+    pub async fn get_filters_trash(&self, user_id: &str) -> AppResult<Vec<BanWordFilterInfo>> {
+        self.ban_word_filter_dao.get_trash_by_user_id(user_id).await
+    }
+
+    #[instrument(skip(self))]
+    pub async fn restore_filter(
+        &self,
+        user_id: &str,
+        ban_word_filter_id: &Uuid,
+    ) -> AppResult<BanWordFilter> {
         self.check_user_owning_of_filter_by_id(user_id, ban_word_filter_id)
             .await?;
 
-        self.ban_word_filter_dao.delete(ban_word_filter_id).await
+        let ban_word_filter = self.ban_word_filter_dao.restore(ban_word_filter_id).await?;
+
+        self.settings_bus
+            .publish(SettingsChange::BanWordFilter {
+                id: *ban_word_filter_id,
+            })
+            .await;
+
+        Ok(ban_word_filter)
     }
 
     #[instrument(skip(self))]
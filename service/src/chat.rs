@@ -1,31 +1,100 @@
 use std::sync::Arc;
 
 use axum::http::StatusCode;
+use serde_json::Value;
 use tracing::instrument;
 use uuid::Uuid;
 
-use dao::ChatSettingsDao;
-use types::domain::{ChatSettings, ChatSettingsInfo, ChatType, UpdateChatSettings};
+use dao::{BanWordFilterDao, ChatSettingsDao, ChatSettingsTemplateDao};
+use twitch_api::TwitchApi;
+use types::domain::{
+    ChatSettings, ChatSettingsDiff, ChatSettingsExport, ChatSettingsInfo, ChatSettingsTemplate,
+    ChatSettingsTemplateInfo, ChatSettingsVersion, ChatSettingsVersionInfo, ChatType, Page,
+    PageRequest, SettingsChange, UpdateChatSettings, UpdateCustomNickname,
+};
 use types::error::{AppError, AppResult};
 
+use crate::settings_diff::diff_versions;
+use crate::settings_export::{export, parse_export};
+use crate::settings_patch::apply_patch;
+use crate::settings_template::{template_info, template_settings, upgrade_template};
+use crate::{ChatStyle, ChatStylesheet, SettingsBus};
+
 pub struct ChatService {
     chat_settings_dao: Arc<ChatSettingsDao>,
+    ban_word_filter_dao: Arc<BanWordFilterDao>,
+    chat_settings_template_dao: Arc<ChatSettingsTemplateDao>,
+    twitch_api: Arc<TwitchApi>,
+    settings_bus: Arc<SettingsBus>,
 }
 
 impl ChatService {
-    pub fn new(chat_settings_dao: Arc<ChatSettingsDao>) -> Self {
-        ChatService { chat_settings_dao }
+    pub fn new(
+        chat_settings_dao: Arc<ChatSettingsDao>,
+        ban_word_filter_dao: Arc<BanWordFilterDao>,
+        chat_settings_template_dao: Arc<ChatSettingsTemplateDao>,
+        twitch_api: Arc<TwitchApi>,
+        settings_bus: Arc<SettingsBus>,
+    ) -> Self {
+        ChatService {
+            chat_settings_dao,
+            ban_word_filter_dao,
+            chat_settings_template_dao,
+            twitch_api,
+            settings_bus,
+        }
     }
 
+    /// Creates the settings from the template, if any, otherwise from the
+    /// defaults of the chat type. The chat type overrides the template's one.
     #[instrument(skip(self))]
     pub async fn create_chat_settings(
         &self,
         user_id: &str,
         name: &str,
-        chat_type: &ChatType,
+        chat_type: Option<&ChatType>,
+        template_id: Option<&Uuid>,
+    ) -> AppResult<ChatSettings> {
+        let Some(template_id) = template_id else {
+            let chat_type = chat_type.ok_or(ChatService::CHAT_TYPE_REQUIRED_ERROR)?;
+
+            return self
+                .chat_settings_dao
+                .create(user_id, name, chat_type)
+                .await;
+        };
+
+        let template = self.chat_settings_template_dao.get(template_id).await?;
+        let mut settings = template_settings(&template)?;
+
+        settings.name = name.to_string();
+        if let Some(chat_type) = chat_type {
+            settings.chat_type = chat_type.clone();
+        }
+        self.resolve_custom_nicknames(&mut settings.color.custom_nicknames)
+            .await;
+
+        self.chat_settings_dao
+            .create_with_settings(user_id, &settings)
+            .await
+    }
+
+    /// Settings are readable by anyone with the id, like the overlay, so
+    /// settings of other users can be cloned as well.
+    #[instrument(skip(self))]
+    pub async fn clone_chat_settings(
+        &self,
+        user_id: &str,
+        chat_settings_id: &Uuid,
+        name: &str,
     ) -> AppResult<ChatSettings> {
+        let is_owner = self
+            .chat_settings_dao
+            .is_belongs_to_user(chat_settings_id, user_id)
+            .await?;
+
         self.chat_settings_dao
-            .create(user_id, name, chat_type)
+            .create_clone(chat_settings_id, user_id, name, is_owner)
             .await
     }
 
@@ -35,8 +104,169 @@ impl ChatService {
     }
 
     #[instrument(skip(self))]
-    pub async fn get_all_chat_settings(&self, user_id: &str) -> AppResult<Vec<ChatSettingsInfo>> {
-        self.chat_settings_dao.get_all_by_user_id(user_id).await
+    pub async fn export_chat_settings(
+        &self,
+        chat_settings_id: &Uuid,
+        include_ban_word_filter: bool,
+    ) -> AppResult<ChatSettingsExport> {
+        let chat_settings = self.chat_settings_dao.get(chat_settings_id).await?;
+
+        let ban_word_filter = match chat_settings.hide.ban_word_filter_id {
+            // A filter in the trash is exported like no filter at all.
+            Some(ban_word_filter_id) if include_ban_word_filter => {
+                match self.ban_word_filter_dao.get(&ban_word_filter_id).await {
+                    Ok(ban_word_filter) => Some(ban_word_filter),
+                    Err(e) if e.status_code == StatusCode::NOT_FOUND => None,
+                    Err(e) => return Err(e),
+                }
+            }
+            _ => None,
+        };
+
+        Ok(export(chat_settings, ban_word_filter))
+    }
+
+    /// Creates new settings from an export. An embedded filter is created as
+    /// a new filter of the user, a referenced one is kept only if it's theirs.
+    #[instrument(skip(self, document))]
+    pub async fn import_chat_settings(
+        &self,
+        user_id: &str,
+        document: Value,
+    ) -> AppResult<ChatSettings> {
+        let export = parse_export(document)?;
+        let mut settings = export.settings;
+
+        self.resolve_custom_nicknames(&mut settings.color.custom_nicknames)
+            .await;
+
+        if let Some(update_ban_word_filter) = export.ban_word_filter {
+            return self
+                .chat_settings_dao
+                .create_with_ban_word_filter(user_id, &settings, &update_ban_word_filter)
+                .await;
+        }
+
+        if let Some(ban_word_filter_id) = settings.hide.ban_word_filter_id {
+            let is_owner = self
+                .ban_word_filter_dao
+                .is_belongs_to_user(&ban_word_filter_id, user_id)
+                .await?;
+            if !is_owner {
+                settings.hide.ban_word_filter_id = None;
+            }
+        }
+
+        self.chat_settings_dao
+            .create_with_settings(user_id, &settings)
+            .await
+    }
+
+    /// Templates which can't be read anymore are left out of the gallery.
+    #[instrument(skip(self))]
+    pub async fn get_chat_settings_templates(
+        &self,
+        page_request: &PageRequest,
+    ) -> AppResult<Page<ChatSettingsTemplateInfo>> {
+        let templates = self
+            .chat_settings_template_dao
+            .get_all(page_request)
+            .await?;
+
+        let mut infos: Vec<ChatSettingsTemplateInfo> = Vec::new();
+
+        for template in templates.items {
+            match template_settings(&template) {
+                Ok(settings) => infos.push(template_info(template, &settings)),
+                Err(e) => {
+                    tracing::warn!({ error = ?e, template_id = %template.id }, "invalid chat settings template")
+                }
+            }
+        }
+
+        Ok(Page {
+            items: infos,
+            next_cursor: templates.next_cursor,
+        })
+    }
+
+    #[instrument(skip(self))]
+    pub async fn get_chat_settings_template(
+        &self,
+        template_id: &Uuid,
+    ) -> AppResult<ChatSettingsTemplate> {
+        let template = self.chat_settings_template_dao.get(template_id).await?;
+
+        upgrade_template(template)
+    }
+
+    /// Publishes the settings to the gallery. Point rewards and the ban word
+    /// filter belong to the user, and nicknames name people of their chat, so
+    /// they aren't part of the template.
+    #[instrument(skip(self))]
+    pub async fn publish_chat_settings_template(
+        &self,
+        user_id: &str,
+        chat_settings_id: &Uuid,
+        name: &str,
+        description: &str,
+    ) -> AppResult<ChatSettingsTemplate> {
+        self.check_user_owning_of_chat_settings_by_id(user_id, chat_settings_id)
+            .await?;
+
+        let mut settings: UpdateChatSettings =
+            self.chat_settings_dao.get(chat_settings_id).await?.into();
+
+        settings.name = name.to_string();
+        settings.hide.point_rewards = Vec::new();
+        settings.hide.ban_word_filter_id = None;
+        settings.hide.nicknames = Vec::new();
+        settings.color.custom_nicknames = Vec::new();
+
+        self.chat_settings_template_dao
+            .create(
+                user_id,
+                name,
+                description,
+                ChatSettingsExport::SCHEMA_VERSION,
+                &serde_json::to_value(settings).unwrap_or(Value::Null),
+            )
+            .await
+    }
+
+    /// Only the user who published the template can delete it, built-in
+    /// templates can't be deleted.
+    #[instrument(skip(self))]
+    pub async fn delete_chat_settings_template(
+        &self,
+        user_id: &str,
+        template_id: &Uuid,
+    ) -> AppResult {
+        let template = self.chat_settings_template_dao.get(template_id).await?;
+
+        if template.user_id.as_deref() != Some(user_id) {
+            return Err(ChatService::IS_NOT_TEMPLATE_OWNER_ERROR);
+        }
+
+        self.chat_settings_template_dao.delete(template_id).await
+    }
+
+    #[instrument(skip(self))]
+    pub async fn get_stylesheet(&self, chat_settings_id: &Uuid) -> AppResult<ChatStylesheet> {
+        let chat_settings = self.chat_settings_dao.get(chat_settings_id).await?;
+
+        Ok(ChatStyle::from(&chat_settings).to_stylesheet())
+    }
+
+    #[instrument(skip(self))]
+    pub async fn get_all_chat_settings(
+        &self,
+        user_id: &str,
+        page_request: &PageRequest,
+    ) -> AppResult<Page<ChatSettingsInfo>> {
+        self.chat_settings_dao
+            .get_all_by_user_id(user_id, page_request)
+            .await
     }
 
     #[instrument(skip(self))]
@@ -45,21 +275,245 @@ impl ChatService {
         user_id: &str,
         chat_settings_id: &Uuid,
         update_chat_settings: &UpdateChatSettings,
+        expected_revision: Option<i32>,
+    ) -> AppResult<ChatSettings> {
+        self.check_user_owning_of_chat_settings_by_id(user_id, chat_settings_id)
+            .await?;
+
+        let mut update_chat_settings = update_chat_settings.clone();
+        self.resolve_custom_nicknames(&mut update_chat_settings.color.custom_nicknames)
+            .await;
+
+        let chat_settings = self
+            .chat_settings_dao
+            .update(chat_settings_id, &update_chat_settings, expected_revision)
+            .await?;
+
+        self.settings_bus
+            .publish(SettingsChange::ChatSettings {
+                id: *chat_settings_id,
+            })
+            .await;
+
+        Ok(chat_settings)
+    }
+
+    /// Merges the patch into the current settings, only the changed fields
+    /// are written.
+    #[instrument(skip(self, patch))]
+    pub async fn patch_chat_settings(
+        &self,
+        user_id: &str,
+        chat_settings_id: &Uuid,
+        patch: &Value,
+        expected_revision: Option<i32>,
     ) -> AppResult<ChatSettings> {
         self.check_user_owning_of_chat_settings_by_id(user_id, chat_settings_id)
             .await?;
 
+        let mut patch = patch.clone();
+        self.resolve_patched_custom_nicknames(&mut patch).await;
+
+        let chat_settings = self
+            .chat_settings_dao
+            .patch(
+                chat_settings_id,
+                |previous| apply_patch(previous, &patch),
+                expected_revision,
+            )
+            .await?;
+
+        self.settings_bus
+            .publish(SettingsChange::ChatSettings {
+                id: *chat_settings_id,
+            })
+            .await;
+
+        Ok(chat_settings)
+    }
+
+    #[instrument(skip(self))]
+    pub async fn get_chat_settings_versions(
+        &self,
+        user_id: &str,
+        chat_settings_id: &Uuid,
+    ) -> AppResult<Vec<ChatSettingsVersionInfo>> {
+        self.check_user_owning_of_chat_settings_by_id(user_id, chat_settings_id)
+            .await?;
+
+        self.chat_settings_dao.get_versions(chat_settings_id).await
+    }
+
+    #[instrument(skip(self))]
+    pub async fn get_chat_settings_version(
+        &self,
+        user_id: &str,
+        chat_settings_id: &Uuid,
+        version: i32,
+    ) -> AppResult<ChatSettingsVersion> {
+        self.check_user_owning_of_chat_settings_by_id(user_id, chat_settings_id)
+            .await?;
+
         self.chat_settings_dao
-            .update(chat_settings_id, update_chat_settings)
+            .get_version(chat_settings_id, version)
             .await
     }
 
     #[instrument(skip(self))]
-    pub async fn delete_chat_settings(&self, user_id: &str, chat_settings_id: &Uuid) -> AppResult {
+    pub async fn diff_chat_settings_versions(
+        &self,
+        user_id: &str,
+        chat_settings_id: &Uuid,
+        from_version: i32,
+        to_version: i32,
+    ) -> AppResult<ChatSettingsDiff> {
+        self.check_user_owning_of_chat_settings_by_id(user_id, chat_settings_id)
+            .await?;
+
+        let from = self
+            .chat_settings_dao
+            .get_version(chat_settings_id, from_version)
+            .await?;
+        let to = self
+            .chat_settings_dao
+            .get_version(chat_settings_id, to_version)
+            .await?;
+
+        Ok(diff_versions(&from, &to))
+    }
+
+    #[instrument(skip(self))]
+    pub async fn restore_chat_settings_version(
+        &self,
+        user_id: &str,
+        chat_settings_id: &Uuid,
+        version: i32,
+        expected_revision: Option<i32>,
+    ) -> AppResult<ChatSettings> {
+        self.check_user_owning_of_chat_settings_by_id(user_id, chat_settings_id)
+            .await?;
+
+        let chat_settings = self
+            .chat_settings_dao
+            .restore_version(chat_settings_id, version, expected_revision)
+            .await?;
+
+        self.settings_bus
+            .publish(SettingsChange::ChatSettings {
+                id: *chat_settings_id,
+            })
+            .await;
+
+        Ok(chat_settings)
+    }
+
+    #[instrument(skip(self))]
+    pub async fn delete_chat_settings(
+        &self,
+        user_id: &str,
+        chat_settings_id: &Uuid,
+        expected_revision: Option<i32>,
+    ) -> AppResult {
         self.check_user_owning_of_chat_settings_by_id(user_id, chat_settings_id)
             .await?;
 
-        self.chat_settings_dao.delete(chat_settings_id).await
+        self.chat_settings_dao
+            .delete(chat_settings_id, expected_revision)
+            .await?;
+
+        // Open overlays of deleted settings are closed.
+        self.settings_bus
+            .publish(SettingsChange::ChatSettings {
+                id: *chat_settings_id,
+            })
+            .await;
+
+        Ok(())
+    }
+
+    #[instrument(skip(self))]
+    pub async fn get_chat_settings_trash(&self, user_id: &str) -> AppResult<Vec<ChatSettingsInfo>> {
+        self.chat_settings_dao.get_trash_by_user_id(user_id).await
+    }
+
+    #[instrument(skip(self))]
+    pub async fn restore_chat_settings(
+        &self,
+        user_id: &str,
+        chat_settings_id: &Uuid,
+    ) -> AppResult<ChatSettings> {
+        self.check_user_owning_of_chat_settings_by_id(user_id, chat_settings_id)
+            .await?;
+
+        let chat_settings = self.chat_settings_dao.restore(chat_settings_id).await?;
+
+        self.settings_bus
+            .publish(SettingsChange::ChatSettings {
+                id: *chat_settings_id,
+            })
+            .await;
+
+        Ok(chat_settings)
+    }
+
+    /// Binds custom nicknames to the Twitch user of their nickname. Those
+    /// which can't be resolved keep matching by login until the next save.
+    /// Resolves the nicknames a patch replaces before the row is locked, a
+    /// patch which doesn't replace them keeps the stored ones. Malformed ones
+    /// are left for the patch validation.
+    #[instrument(skip(self, patch))]
+    async fn resolve_patched_custom_nicknames(&self, patch: &mut Value) {
+        let Some(value) = patch.pointer_mut("/color/customNicknames") else {
+            return;
+        };
+        let Ok(mut custom_nicknames) =
+            serde_json::from_value::<Vec<UpdateCustomNickname>>(value.clone())
+        else {
+            return;
+        };
+
+        self.resolve_custom_nicknames(&mut custom_nicknames).await;
+
+        if let Ok(resolved) = serde_json::to_value(custom_nicknames) {
+            *value = resolved;
+        }
+    }
+
+    #[instrument(skip(self, custom_nicknames))]
+    async fn resolve_custom_nicknames(&self, custom_nicknames: &mut [UpdateCustomNickname]) {
+        let logins: Vec<String> = custom_nicknames
+            .iter()
+            .filter(|custom_nickname| custom_nickname.user_id.is_none())
+            .map(|custom_nickname| custom_nickname.nickname.to_lowercase())
+            .collect();
+
+        if logins.is_empty() {
+            return;
+        }
+
+        let users_info = match self.twitch_api.get_users_info(&[], &logins).await {
+            Ok(users_info) => users_info,
+            Err(e) => {
+                tracing::warn!({ error = ?e }, "fail resolve custom nicknames");
+                return;
+            }
+        };
+
+        for custom_nickname in custom_nicknames.iter_mut() {
+            if custom_nickname.user_id.is_some() {
+                continue;
+            }
+
+            let login = custom_nickname.nickname.to_lowercase();
+
+            match users_info.iter().find(|user_info| user_info.login == login) {
+                Some(user_info) => custom_nickname.user_id = Some(user_info.id.clone()),
+                None => tracing::warn!(
+                    { nickname = %custom_nickname.nickname },
+                    "fail resolve custom nickname"
+                ),
+            }
+        }
     }
 
     #[instrument(skip(self))]
@@ -104,4 +558,10 @@ macro_rules! chat_service_errors {
 
 chat_service_errors! {
     (IS_NOT_OWNER_ERROR, StatusCode::UNAUTHORIZED, "chat settings is not your");
+    (UNKNOWN_EXPORT_FORMAT_ERROR, StatusCode::BAD_REQUEST, "unknown chat settings export format");
+    (UNSUPPORTED_EXPORT_VERSION_ERROR, StatusCode::BAD_REQUEST, "unsupported chat settings export version");
+    (INVALID_EXPORT_ERROR, StatusCode::BAD_REQUEST, "invalid chat settings export");
+    (INVALID_PATCH_ERROR, StatusCode::BAD_REQUEST, "invalid chat settings patch");
+    (CHAT_TYPE_REQUIRED_ERROR, StatusCode::BAD_REQUEST, "chat type or template is required");
+    (IS_NOT_TEMPLATE_OWNER_ERROR, StatusCode::UNAUTHORIZED, "chat settings template is not your");
 }
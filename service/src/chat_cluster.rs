@@ -0,0 +1,365 @@
+use std::sync::Arc;
+use std::time::Duration;
+
+use serde::{Deserialize, Serialize};
+use tokio::sync::{broadcast, mpsc};
+use tokio::time::{interval, sleep, MissedTickBehavior};
+use tracing::instrument;
+
+use dao::{IngestLock, IngestLockDao, NotificationDao};
+use twitch_api::ChatSource;
+use types::error::AppResult;
+use types::twitch;
+
+use crate::backoff::Backoff;
+
+const EVENTS_CAPACITY: usize = 256;
+const RELAYED_CAPACITY: usize = 1024;
+const CHANNEL: &str = "chat_events";
+/// How often followers try to take over a channel and owners check they still own it.
+const OWNER_CHECK_INTERVAL: Duration = Duration::from_secs(5);
+const LISTEN_RETRY_MIN: Duration = Duration::from_secs(1);
+const LISTEN_RETRY_MAX: Duration = Duration::from_secs(60);
+
+#[derive(Serialize, Deserialize)]
+struct RelayedChatEvent {
+    channel: String,
+    event: twitch::ChatEvent,
+}
+
+/// Reads each channel once across every instance sharing the database. The
+/// instance holding the channel lock reads the chat and relays its events to
+/// the others, one of which takes over when the owner dies.
+pub struct ClusterChatSource {
+    chat_source: Arc<dyn ChatSource>,
+    ingest_lock_dao: Arc<IngestLockDao>,
+    notification_dao: Arc<NotificationDao>,
+    relayed: broadcast::Sender<Arc<RelayedChatEvent>>,
+}
+
+impl ClusterChatSource {
+    pub async fn new(
+        chat_source: Arc<dyn ChatSource>,
+        ingest_lock_dao: Arc<IngestLockDao>,
+        notification_dao: Arc<NotificationDao>,
+    ) -> AppResult<Self> {
+        let (relayed, _) = broadcast::channel(RELAYED_CAPACITY);
+        let mut listener = notification_dao.listen(CHANNEL).await?;
+
+        let local_relayed = relayed.clone();
+        tokio::spawn(async move {
+            let mut backoff = Backoff::new(LISTEN_RETRY_MIN, LISTEN_RETRY_MAX);
+            loop {
+                let notification = match listener.recv().await {
+                    Ok(notification) => {
+                        backoff.reset();
+                        notification
+                    }
+                    // The listener failed to reconnect, the database may be down.
+                    Err(e) => {
+                        tracing::warn!({ error = %e }, "fail receive chat event");
+                        backoff.wait().await;
+                        continue;
+                    }
+                };
+
+                match serde_json::from_str::<RelayedChatEvent>(notification.payload()) {
+                    Ok(relayed_event) => {
+                        let _ = local_relayed.send(Arc::new(relayed_event));
+                    }
+                    Err(e) => tracing::warn!({ error = %e }, "invalid chat event"),
+                }
+            }
+        });
+
+        Ok(ClusterChatSource {
+            chat_source,
+            ingest_lock_dao,
+            notification_dao,
+            relayed,
+        })
+    }
+}
+
+impl ChatSource for ClusterChatSource {
+    fn join(&self, channel: &str) -> mpsc::Receiver<twitch::ChatEvent> {
+        let (sender, receiver) = mpsc::channel(EVENTS_CAPACITY);
+        let member = ClusterMember {
+            channel: channel.to_ascii_lowercase(),
+            chat_source: self.chat_source.clone(),
+            ingest_lock_dao: self.ingest_lock_dao.clone(),
+            notification_dao: self.notification_dao.clone(),
+            relayed: self.relayed.clone(),
+            events: sender,
+        };
+        tokio::spawn(member.run());
+        receiver
+    }
+}
+
+/// One channel joined by this instance, either owning or following it.
+struct ClusterMember {
+    channel: String,
+    chat_source: Arc<dyn ChatSource>,
+    ingest_lock_dao: Arc<IngestLockDao>,
+    notification_dao: Arc<NotificationDao>,
+    relayed: broadcast::Sender<Arc<RelayedChatEvent>>,
+    events: mpsc::Sender<twitch::ChatEvent>,
+}
+
+impl ClusterMember {
+    #[instrument(skip(self), fields(channel = %self.channel))]
+    async fn run(self) {
+        let lock_key = format!("chat_ingest:{}", self.channel);
+        // Kept between checks on the owner, so following misses nothing.
+        let mut relayed = None;
+
+        while !self.events.is_closed() {
+            match self.ingest_lock_dao.try_lock(&lock_key).await {
+                Ok(Some(lock)) => {
+                    relayed = None;
+                    if !self.own(lock).await {
+                        return;
+                    }
+                }
+                Ok(None) => {
+                    let relayed = relayed.get_or_insert_with(|| self.relayed.subscribe());
+                    if !self.follow(relayed).await {
+                        return;
+                    }
+                }
+                Err(e) => {
+                    tracing::warn!({ error = ?e }, "fail check channel owner");
+                    sleep(OWNER_CHECK_INTERVAL).await;
+                }
+            }
+        }
+    }
+
+    /// Reads the chat while the lock is held. `false` once it shouldn't be
+    /// read anymore.
+    async fn own(&self, mut lock: IngestLock) -> bool {
+        tracing::info!("owning chat ingest");
+
+        let mut chat_events = self.chat_source.join(&self.channel);
+        let mut check = interval(OWNER_CHECK_INTERVAL);
+        check.set_missed_tick_behavior(MissedTickBehavior::Delay);
+
+        let is_running = loop {
+            tokio::select! {
+                chat_event = chat_events.recv() => {
+                    let Some(chat_event) = chat_event else {
+                        break false;
+                    };
+                    // Overlays of this instance don't wait for the database.
+                    let is_delivered = self.events.send(chat_event.clone()).await.is_ok();
+                    self.relay(chat_event).await;
+                    if !is_delivered {
+                        break false;
+                    }
+                }
+                _ = check.tick() => {
+                    if !lock.is_held().await {
+                        tracing::warn!("lost chat ingest lock");
+                        break true;
+                    }
+                }
+                _ = self.events.closed() => break false,
+            }
+        };
+
+        lock.release().await;
+        is_running
+    }
+
+    /// Forwards the events relayed by the owner until it's time to check on
+    /// the owner again. `false` once the channel isn't read anymore.
+    async fn follow(&self, relayed: &mut broadcast::Receiver<Arc<RelayedChatEvent>>) -> bool {
+        let check = sleep(OWNER_CHECK_INTERVAL);
+        tokio::pin!(check);
+
+        loop {
+            tokio::select! {
+                relayed_event = relayed.recv() => match relayed_event {
+                    Ok(relayed_event) if relayed_event.channel == self.channel => {
+                        if self.events.send(relayed_event.event.clone()).await.is_err() {
+                            return false;
+                        }
+                    }
+                    Ok(_) => {}
+                    Err(broadcast::error::RecvError::Lagged(skipped)) => {
+                        tracing::warn!({ skipped }, "chat events skipped");
+                    }
+                    Err(broadcast::error::RecvError::Closed) => return false,
+                },
+                _ = &mut check => return true,
+                _ = self.events.closed() => return false,
+            }
+        }
+    }
+
+    async fn relay(&self, chat_event: twitch::ChatEvent) {
+        let relayed_event = RelayedChatEvent {
+            channel: self.channel.clone(),
+            event: chat_event,
+        };
+        let payload = serde_json::to_string(&relayed_event).unwrap();
+
+        // Followers miss this event, but this instance still shows it.
+        if let Err(e) = self.notification_dao.notify(CHANNEL, &payload).await {
+            tracing::warn!({ error = ?e }, "fail relay chat event");
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::{Arc, Mutex};
+    use std::time::Duration;
+
+    use chrono::Utc;
+    use sqlx::postgres::{PgConnectOptions, PgPoolOptions};
+    use sqlx::PgPool;
+    use tokio::sync::mpsc;
+    use tokio::time::{sleep, timeout};
+
+    use dao::{IngestLockDao, NotificationDao};
+    use twitch_api::ChatSource;
+    use types::twitch;
+
+    use crate::chat_cluster::OWNER_CHECK_INTERVAL;
+    use crate::ClusterChatSource;
+
+    /// Chat of one instance, whose events are sent by the test itself.
+    #[derive(Default)]
+    struct TestChatSource {
+        senders: Mutex<Vec<mpsc::Sender<twitch::ChatEvent>>>,
+    }
+
+    impl TestChatSource {
+        /// Waits until the instance reads the chat.
+        async fn joined(&self) -> mpsc::Sender<twitch::ChatEvent> {
+            timeout(OWNER_CHECK_INTERVAL * 3, async {
+                loop {
+                    if let Some(sender) = self.senders.lock().unwrap().last() {
+                        return sender.clone();
+                    }
+                    sleep(Duration::from_millis(10)).await;
+                }
+            })
+            .await
+            .expect("chat never read")
+        }
+
+        fn join_count(&self) -> usize {
+            self.senders.lock().unwrap().len()
+        }
+    }
+
+    impl ChatSource for TestChatSource {
+        fn join(&self, _channel: &str) -> mpsc::Receiver<twitch::ChatEvent> {
+            let (sender, receiver) = mpsc::channel(16);
+            self.senders.lock().unwrap().push(sender);
+            receiver
+        }
+    }
+
+    async fn instance(pool: &Arc<PgPool>) -> (Arc<TestChatSource>, ClusterChatSource) {
+        let chat_source = Arc::new(TestChatSource::default());
+        let cluster_chat_source = ClusterChatSource::new(
+            chat_source.clone(),
+            Arc::new(IngestLockDao::new(pool.clone())),
+            Arc::new(NotificationDao::new(pool.clone())),
+        )
+        .await
+        .unwrap();
+        (chat_source, cluster_chat_source)
+    }
+
+    fn message(id: &str) -> twitch::ChatEvent {
+        twitch::ChatEvent::Message(Box::new(twitch::ChatMessage {
+            id: id.to_string(),
+            channel: "streamer".to_string(),
+            user_id: "1".to_string(),
+            login: "viewer".to_string(),
+            display_name: "Viewer".to_string(),
+            color: None,
+            badges: Vec::new(),
+            text: format!("message {}", id),
+            emotes: String::new(),
+            bits: None,
+            reward_id: None,
+            first_message: false,
+            sent_at: Utc::now(),
+        }))
+    }
+
+    fn id(chat_event: twitch::ChatEvent) -> String {
+        match chat_event {
+            twitch::ChatEvent::Message(message) => message.id,
+            chat_event => panic!("unexpected event: {:?}", chat_event),
+        }
+    }
+
+    /// Sends messages until the follower got one, since it starts following
+    /// at some point after joining.
+    async fn until_followed(
+        owner_chat: &mpsc::Sender<twitch::ChatEvent>,
+        owner_events: &mut mpsc::Receiver<twitch::ChatEvent>,
+        follower_events: &mut mpsc::Receiver<twitch::ChatEvent>,
+    ) {
+        for i in 0.. {
+            let warmup_id = format!("warmup-{}", i);
+            owner_chat.send(message(&warmup_id)).await.unwrap();
+            assert_eq!(id(owner_events.recv().await.unwrap()), warmup_id);
+            if let Ok(Some(_)) = timeout(Duration::from_millis(100), follower_events.recv()).await {
+                // Drains what arrives late.
+                while timeout(Duration::from_millis(100), follower_events.recv())
+                    .await
+                    .is_ok()
+                {}
+                return;
+            }
+        }
+    }
+
+    #[sqlx::test(migrations = "../migrations")]
+    async fn one_instance_reads_and_relays(
+        pool_options: PgPoolOptions,
+        connect_options: PgConnectOptions,
+    ) {
+        // Not the pool of the test, whose cleanup waits for the spawned tasks.
+        let pool = Arc::new(pool_options.connect_with(connect_options).await.unwrap());
+        let (first_chat, first) = instance(&pool).await;
+        let (second_chat, second) = instance(&pool).await;
+
+        let mut first_events = first.join("Streamer");
+        let owner_chat = first_chat.joined().await;
+        let mut second_events = second.join("streamer");
+        until_followed(&owner_chat, &mut first_events, &mut second_events).await;
+
+        owner_chat.send(message("1")).await.unwrap();
+        assert_eq!(id(first_events.recv().await.unwrap()), "1");
+        assert_eq!(id(second_events.recv().await.unwrap()), "1");
+        assert_eq!(second_chat.join_count(), 0);
+    }
+
+    #[sqlx::test(migrations = "../migrations")]
+    async fn follower_takes_over(pool_options: PgPoolOptions, connect_options: PgConnectOptions) {
+        // Not the pool of the test, whose cleanup waits for the spawned tasks.
+        let pool = Arc::new(pool_options.connect_with(connect_options).await.unwrap());
+        let (first_chat, first) = instance(&pool).await;
+        let (second_chat, second) = instance(&pool).await;
+
+        let first_events = first.join("streamer");
+        first_chat.joined().await;
+        let mut second_events = second.join("streamer");
+
+        // The owner stops reading once nobody on its instance watches.
+        drop(first_events);
+        let owner_chat = second_chat.joined().await;
+
+        owner_chat.send(message("1")).await.unwrap();
+        assert_eq!(id(second_events.recv().await.unwrap()), "1");
+    }
+}
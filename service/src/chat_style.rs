@@ -0,0 +1,1030 @@
+use std::fmt::{self, Display, Formatter, Write};
+
+use sha2::{Digest, Sha256};
+
+use types::domain::{ChatAnimation, ChatSettings, ChatType, StackingDirection, ViewerGroup};
+
+/// Sizes are relative to the browser source height, so the chat scales with it.
+const SIZE_UNIT: &str = "vh";
+
+/// Style of an overlay, with the packed values of `ChatSettings` turned into CSS ones.
+#[derive(Debug, PartialEq, Clone)]
+pub struct ChatStyle {
+    pub layout: ChatLayout,
+    /// Newest messages are on top.
+    pub reverse: bool,
+    pub stacking: StackingDirection,
+    /// `None` when messages appear without animation.
+    pub enter: Option<MessageAnimation>,
+    /// Played on `.message--leaving`, before the page removes the message.
+    pub exit: Option<MessageAnimation>,
+    pub font: FontStyle,
+    pub text_color: CssColor,
+    pub background_color: CssColor,
+    pub nickname: NicknameFill,
+    /// Sorted by the precedence of the groups.
+    pub viewer_groups: Vec<ViewerGroupStyle>,
+    /// Win over the styles of viewer groups.
+    pub custom_nicknames: Vec<CustomNicknameStyle>,
+    pub margin: Sides,
+    pub padding: Sides,
+    pub border_radius: Corners,
+}
+
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub enum ChatLayout {
+    /// Nickname and text on the same line.
+    Inline,
+    /// Nickname above the text, in the same block.
+    Block,
+    /// Nickname on a tab above the text block.
+    AlternativeBlock,
+    /// Messages side by side on a single line, scrolled by the page.
+    Ticker { gap: Size },
+    /// Speech bubbles with the nickname above the text. The page marks every
+    /// other message as `.message--alternate`.
+    Bubble {
+        /// Percent of the overlay width.
+        max_width: f64,
+        alternate_sides: bool,
+        show_tail: bool,
+    },
+    /// Inline messages stuck together, cut after a few lines.
+    Compact { max_lines: i32, spacing: Size },
+    /// Names of the chatters, with their text below only when shown.
+    NameStream { show_text: bool },
+}
+
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub struct MessageAnimation {
+    pub animation: ChatAnimation,
+    /// Milliseconds.
+    pub duration: i32,
+}
+
+impl MessageAnimation {
+    fn new(animation: ChatAnimation, duration: i32) -> Option<Self> {
+        if animation == ChatAnimation::None || duration <= 0 {
+            return None;
+        }
+        Some(MessageAnimation {
+            animation,
+            duration,
+        })
+    }
+}
+
+#[derive(Debug, PartialEq, Clone)]
+pub struct FontStyle {
+    /// `None` falls back to the default sans-serif font.
+    pub family: Option<String>,
+    pub size: Size,
+    pub nickname_weight: i32,
+    pub text_weight: i32,
+}
+
+#[derive(Debug, PartialEq, Clone)]
+pub enum NicknameFill {
+    Color(CssColor),
+    /// Goes to the Twitch color of the chatter, when they have one.
+    GradientToUserColor(CssColor),
+    /// Colors spread evenly from left to right.
+    Gradient(Vec<CssColor>),
+}
+
+#[derive(Debug, PartialEq, Clone)]
+pub struct CustomNicknameStyle {
+    pub chatter: ChatterMatch,
+    pub fill: NicknameFill,
+    pub font_weight: Option<i32>,
+}
+
+/// Restyle of the messages of a viewer group, only rules setting something
+/// are kept.
+#[derive(Debug, PartialEq, Clone)]
+pub struct ViewerGroupStyle {
+    pub group: ViewerGroup,
+    pub nickname_color: Option<CssColor>,
+    pub background_color: Option<CssColor>,
+    pub font_weight: Option<i32>,
+}
+
+/// Chatter a custom nickname applies to, by Twitch user id when it's known
+/// so renames keep the style.
+#[derive(Debug, PartialEq, Clone)]
+pub enum ChatterMatch {
+    UserId(String),
+    /// Lowercase login.
+    Login(String),
+}
+
+/// Color stored as `0xRRGGBBAA`.
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub struct CssColor {
+    pub red: u8,
+    pub green: u8,
+    pub blue: u8,
+    pub alpha: u8,
+}
+
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub struct Size(pub f64);
+
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub struct Sides {
+    pub top: Size,
+    pub right: Size,
+    pub bottom: Size,
+    pub left: Size,
+}
+
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub struct Corners {
+    pub top_left: Size,
+    pub top_right: Size,
+    pub bottom_right: Size,
+    pub bottom_left: Size,
+}
+
+/// Stylesheet with its strong ETag, the quoted hash of the CSS.
+#[derive(Debug, PartialEq, Clone)]
+pub struct ChatStylesheet {
+    pub css: String,
+    pub etag: String,
+}
+
+impl From<&ChatSettings> for ChatStyle {
+    fn from(chat_settings: &ChatSettings) -> Self {
+        let color = &chat_settings.color;
+        let size = &chat_settings.size;
+        let font = &chat_settings.font;
+        let options = &chat_settings.chat_type_options;
+        let animation = &chat_settings.animation;
+
+        let (layout, reverse) = match chat_settings.chat_type {
+            ChatType::Default => (ChatLayout::Inline, false),
+            ChatType::DefaultReverse => (ChatLayout::Inline, true),
+            ChatType::Block => (ChatLayout::Block, false),
+            ChatType::BlockReverse => (ChatLayout::Block, true),
+            ChatType::AlternativeBlock => (ChatLayout::AlternativeBlock, false),
+            ChatType::AlternativeBlockReverse => (ChatLayout::AlternativeBlock, true),
+            ChatType::Ticker => (
+                ChatLayout::Ticker {
+                    gap: Size(options.ticker.gap),
+                },
+                false,
+            ),
+            ChatType::Bubble => (
+                ChatLayout::Bubble {
+                    max_width: options.bubble.max_width,
+                    alternate_sides: options.bubble.alternate_sides,
+                    show_tail: options.bubble.show_tail,
+                },
+                false,
+            ),
+            ChatType::Compact => (
+                ChatLayout::Compact {
+                    max_lines: options.compact.max_lines,
+                    spacing: Size(options.compact.spacing),
+                },
+                false,
+            ),
+            ChatType::NameStream => (
+                ChatLayout::NameStream {
+                    show_text: options.name_stream.show_text,
+                },
+                false,
+            ),
+        };
+
+        let nickname_color = CssColor::from(color.nickname_color);
+        let nickname = if color.gradient_only_for_custom_nicknames {
+            NicknameFill::Color(nickname_color)
+        } else {
+            NicknameFill::GradientToUserColor(nickname_color)
+        };
+
+        let mut viewer_groups: Vec<ViewerGroupStyle> = chat_settings
+            .hide
+            .viewer_groups
+            .iter()
+            .filter(|rule| {
+                rule.nickname_color.is_some()
+                    || rule.background_color.is_some()
+                    || rule.font_weight.is_some()
+            })
+            .map(|rule| ViewerGroupStyle {
+                group: rule.group,
+                nickname_color: rule.nickname_color.map(CssColor::from),
+                background_color: rule.background_color.map(CssColor::from),
+                font_weight: rule.font_weight,
+            })
+            .collect();
+        viewer_groups.sort_by_key(|style| style.group);
+
+        ChatStyle {
+            layout,
+            reverse,
+            stacking: animation.stacking_direction,
+            enter: MessageAnimation::new(animation.enter_animation, animation.enter_duration),
+            exit: MessageAnimation::new(animation.exit_animation, animation.exit_duration),
+            font: FontStyle {
+                family: font_family(&font.font_family),
+                size: Size(font.font_size),
+                nickname_weight: font.nickname_font_weight,
+                text_weight: font.text_font_weight,
+            },
+            text_color: CssColor::from(color.text_color),
+            background_color: CssColor::from(color.background_color),
+            nickname,
+            viewer_groups,
+            custom_nicknames: color
+                .custom_nicknames
+                .iter()
+                .map(|custom_nickname| CustomNicknameStyle {
+                    chatter: match &custom_nickname.user_id {
+                        Some(user_id) => ChatterMatch::UserId(user_id.clone()),
+                        None => ChatterMatch::Login(custom_nickname.nickname.to_lowercase()),
+                    },
+                    fill: NicknameFill::Gradient(
+                        std::iter::once(custom_nickname.start_color)
+                            .chain(custom_nickname.middle_colors.iter().copied())
+                            .chain(std::iter::once(custom_nickname.end_color))
+                            .map(CssColor::from)
+                            .collect(),
+                    ),
+                    font_weight: custom_nickname.font_weight,
+                })
+                .collect(),
+            margin: Sides {
+                top: Size(size.margin_top),
+                right: Size(size.margin_right),
+                bottom: Size(size.margin_bottom),
+                left: Size(size.margin_left),
+            },
+            padding: Sides {
+                top: Size(size.padding_top),
+                right: Size(size.padding_right),
+                bottom: Size(size.padding_bottom),
+                left: Size(size.padding_left),
+            },
+            border_radius: Corners {
+                top_left: Size(size.border_top_left_radius),
+                top_right: Size(size.border_top_right_radius),
+                bottom_right: Size(size.border_bottom_right_radius),
+                bottom_left: Size(size.border_bottom_left_radius),
+            },
+        }
+    }
+}
+
+impl ChatStyle {
+    /// Stylesheet for the markup of the overlay page: `.chat` holding `.message`
+    /// blocks with a `.nickname` wrapping the `.name`, and a `.text`. Messages
+    /// list the groups of their chatter in `data-viewer-groups`.
+    pub fn to_css(&self) -> String {
+        let mut css = String::new();
+
+        let _ = write!(
+            css,
+            r#"html, body {{
+  margin: 0;
+  height: 100%;
+  overflow: hidden;
+  background: transparent;
+}}
+.chat {{
+  box-sizing: border-box;
+  display: flex;
+  flex-direction: {direction};
+  justify-content: {justify_content};
+  height: 100%;
+  font-family: {font_family};
+  font-size: {font_size};
+  color: {text_color};
+}}
+.message {{
+  margin: {margin};
+  padding: {padding};
+  border-radius: {border_radius};
+  background-color: {background_color};
+  overflow-wrap: anywhere;
+}}
+.message--highlighted {{
+  background-color: var(--highlight-color);
+}}
+.nickname {{
+  font-weight: {nickname_weight};
+}}
+.name {{
+{nickname_fill}}}
+.text {{
+  font-weight: {text_weight};
+}}
+.mention {{
+  font-weight: {nickname_weight};
+}}
+.emote {{
+  height: 1.5em;
+  vertical-align: middle;
+}}
+.badge {{
+  height: 1em;
+  margin-right: 0.25em;
+  vertical-align: middle;
+}}
+"#,
+            direction = if self.reverse {
+                "column-reverse"
+            } else {
+                "column"
+            },
+            // `column-reverse` starts from the bottom edge.
+            justify_content = match (self.stacking, self.reverse) {
+                (StackingDirection::Auto, _) => "flex-end",
+                (StackingDirection::Up, false) | (StackingDirection::Down, true) => "flex-end",
+                (StackingDirection::Up, true) | (StackingDirection::Down, false) => "flex-start",
+            },
+            font_family = match &self.font.family {
+                Some(family) => format!("\"{}\", sans-serif", family),
+                None => "sans-serif".to_string(),
+            },
+            font_size = self.font.size,
+            text_color = self.text_color,
+            margin = self.margin,
+            padding = self.padding,
+            border_radius = self.border_radius,
+            background_color = self.background_color,
+            nickname_weight = self.font.nickname_weight,
+            nickname_fill = fill_declarations(&self.nickname),
+            text_weight = self.font.text_weight,
+        );
+
+        for viewer_group in self.viewer_groups.iter() {
+            let selector = format!(
+                ".message[data-viewer-groups~=\"{}\"]",
+                viewer_group.group.to_str()
+            );
+
+            // Highlighted redemptions keep their color.
+            if let Some(background_color) = viewer_group.background_color {
+                let _ = write!(
+                    css,
+                    "{}:not(.message--highlighted) {{\n  background-color: {};\n}}\n",
+                    selector, background_color,
+                );
+            }
+            if let Some(font_weight) = viewer_group.font_weight {
+                let _ = write!(
+                    css,
+                    "{} .nickname {{\n  font-weight: {};\n}}\n",
+                    selector, font_weight,
+                );
+            }
+            // Also clears the gradient the nicknames may have by default.
+            if let Some(nickname_color) = viewer_group.nickname_color {
+                let _ = write!(
+                    css,
+                    "{} .name {{\n  color: {color};\n  background-image: none;\n  -webkit-text-fill-color: {color};\n}}\n",
+                    selector,
+                    color = nickname_color,
+                );
+            }
+        }
+
+        for custom_nickname in self.custom_nicknames.iter() {
+            let selector = match &custom_nickname.chatter {
+                ChatterMatch::UserId(user_id) => {
+                    format!(".message[data-user-id=\"{}\"]", escape_attribute(user_id))
+                }
+                ChatterMatch::Login(login) => {
+                    format!(".message[data-login=\"{}\" i]", escape_attribute(login))
+                }
+            };
+
+            if let Some(font_weight) = custom_nickname.font_weight {
+                let _ = write!(
+                    css,
+                    "{} .nickname {{\n  font-weight: {};\n}}\n",
+                    selector, font_weight,
+                );
+            }
+            let _ = write!(
+                css,
+                "{} .name {{\n{}}}\n",
+                selector,
+                fill_declarations(&custom_nickname.fill),
+            );
+        }
+
+        css.push_str(&self.layout_css());
+        css.push_str(&self.animation_css());
+        css
+    }
+
+    fn animation_css(&self) -> String {
+        let mut css = String::new();
+
+        if let Some(enter) = self.enter {
+            let _ = write!(
+                css,
+                r#"@keyframes message-enter {{
+  from {{
+{from}  }}
+}}
+.message {{
+  animation: message-enter {duration}ms ease-out;
+}}
+"#,
+                from = animation_keyframe(enter.animation, false),
+                duration = enter.duration,
+            );
+        }
+
+        if let Some(exit) = self.exit {
+            let _ = write!(
+                css,
+                r#"@keyframes message-exit {{
+  to {{
+{to}  }}
+}}
+.message--leaving {{
+  animation: message-exit {duration}ms ease-in forwards;
+}}
+"#,
+                to = animation_keyframe(exit.animation, true),
+                duration = exit.duration,
+            );
+        }
+
+        css
+    }
+
+    fn layout_css(&self) -> String {
+        match self.layout {
+            ChatLayout::Inline => r#".nickname::after {
+  content: ": ";
+}
+"#
+            .to_string(),
+            ChatLayout::Block => r#".message {
+  display: flex;
+  flex-direction: column;
+  align-items: flex-start;
+  gap: 0.25em;
+}
+"#
+            .to_string(),
+            ChatLayout::AlternativeBlock => format!(
+                r#".message {{
+  display: flex;
+  flex-direction: column;
+  align-items: flex-start;
+  padding: 0;
+  background-color: transparent;
+}}
+.nickname {{
+  padding: {tab_padding};
+  border-radius: {tab_border_radius};
+  background-color: {background_color};
+}}
+.text {{
+  align-self: stretch;
+  padding: {padding};
+  border-radius: {text_border_radius};
+  background-color: {background_color};
+}}
+.message--highlighted .nickname, .message--highlighted .text {{
+  background-color: var(--highlight-color);
+}}
+"#,
+                tab_padding = Sides {
+                    bottom: Size(0.0),
+                    ..self.padding
+                },
+                tab_border_radius = Corners {
+                    bottom_right: Size(0.0),
+                    bottom_left: Size(0.0),
+                    ..self.border_radius
+                },
+                padding = self.padding,
+                text_border_radius = Corners {
+                    top_left: Size(0.0),
+                    ..self.border_radius
+                },
+                background_color = self.background_color,
+            ),
+            ChatLayout::Ticker { gap } => format!(
+                r#".chat {{
+  flex-direction: row;
+  align-items: center;
+  justify-content: flex-start;
+  gap: {gap};
+  padding-left: 100%;
+  white-space: nowrap;
+  will-change: transform;
+}}
+.message {{
+  flex: none;
+}}
+.nickname::after {{
+  content: ": ";
+}}
+"#
+            ),
+            ChatLayout::Bubble {
+                max_width,
+                alternate_sides,
+                show_tail,
+            } => {
+                let mut css = format!(
+                    r#".message {{
+  position: relative;
+  display: flex;
+  flex-direction: column;
+  align-items: flex-start;
+  align-self: flex-start;
+  gap: 0.25em;
+  max-width: {max_width}%;
+}}
+"#
+                );
+                if alternate_sides {
+                    css.push_str(
+                        r#".message--alternate {
+  align-self: flex-end;
+}
+"#,
+                    );
+                }
+                if show_tail {
+                    // Drawn at the bottom corner facing the side the bubble is on.
+                    let _ = write!(
+                        css,
+                        r#".message::after {{
+  content: "";
+  position: absolute;
+  bottom: 0;
+  left: -0.5em;
+  border-style: solid;
+  border-width: 0 0 0.75em 0.75em;
+  border-color: transparent transparent {background_color} transparent;
+}}
+.message--highlighted::after {{
+  border-bottom-color: var(--highlight-color);
+}}
+"#,
+                        background_color = self.background_color,
+                    );
+                    if alternate_sides {
+                        css.push_str(
+                            r#".message--alternate::after {
+  left: auto;
+  right: -0.5em;
+  border-width: 0 0.75em 0.75em 0;
+}
+"#,
+                        );
+                    }
+                }
+                css
+            }
+            ChatLayout::Compact { max_lines, spacing } => format!(
+                r#".message {{
+  display: -webkit-box;
+  -webkit-box-orient: vertical;
+  -webkit-line-clamp: {max_lines};
+  overflow: hidden;
+  margin: {spacing} 0 0;
+  border-radius: 0;
+}}
+.nickname::after {{
+  content: ": ";
+}}
+"#
+            ),
+            ChatLayout::NameStream { show_text } => {
+                let mut css = r#".message {
+  display: flex;
+  flex-direction: column;
+  align-items: center;
+  align-self: center;
+}
+"#
+                .to_string();
+                if !show_text {
+                    css.push_str(
+                        r#".text {
+  display: none;
+}
+"#,
+                    );
+                }
+                css
+            }
+        }
+    }
+
+    pub fn to_stylesheet(&self) -> ChatStylesheet {
+        let css = self.to_css();
+        let etag = format!("\"{}\"", hex::encode(Sha256::digest(css.as_bytes())));
+
+        ChatStylesheet { css, etag }
+    }
+}
+
+/// Declarations of the hidden end of an animation, where an entering message
+/// comes from or a leaving one goes to.
+fn animation_keyframe(animation: ChatAnimation, is_leaving: bool) -> String {
+    // Slides move the message the way they are named, so both ends are opposite.
+    let sign = if is_leaving { "-" } else { "" };
+    let transform = match animation {
+        ChatAnimation::None | ChatAnimation::Fade => None,
+        ChatAnimation::SlideUp => Some(format!("translateY({}100%)", sign)),
+        ChatAnimation::SlideDown => Some(format!("translateY({}100%)", flip(sign))),
+        ChatAnimation::SlideLeft => Some(format!("translateX({}100%)", sign)),
+        ChatAnimation::SlideRight => Some(format!("translateX({}100%)", flip(sign))),
+        ChatAnimation::Scale => Some("scale(0)".to_string()),
+    };
+
+    let mut declarations = "    opacity: 0;\n".to_string();
+    if let Some(transform) = transform {
+        let _ = writeln!(declarations, "    transform: {};", transform);
+    }
+    declarations
+}
+
+fn flip(sign: &str) -> &str {
+    if sign.is_empty() {
+        "-"
+    } else {
+        ""
+    }
+}
+
+/// Gradients are clipped to the text of `.name`, so the background of the
+/// `.nickname` around it stays visible.
+fn fill_declarations(fill: &NicknameFill) -> String {
+    let gradient = |start: String, stops: String| {
+        format!(
+            r#"  color: {start};
+  background-image: linear-gradient(90deg, {stops});
+  -webkit-background-clip: text;
+  background-clip: text;
+  -webkit-text-fill-color: transparent;
+"#
+        )
+    };
+
+    match fill {
+        NicknameFill::Color(color) => format!("  color: {};\n", color),
+        NicknameFill::GradientToUserColor(color) => gradient(
+            color.to_string(),
+            format!("{}, var(--user-color, {})", color, color),
+        ),
+        NicknameFill::Gradient(colors) => gradient(
+            colors.first().map(CssColor::to_string).unwrap_or_default(),
+            colors
+                .iter()
+                .map(CssColor::to_string)
+                .collect::<Vec<String>>()
+                .join(", "),
+        ),
+    }
+}
+
+fn font_family(font_family: &str) -> Option<String> {
+    // The name ends up inside a CSS string, anything that could close it is dropped.
+    let name: String = font_family
+        .chars()
+        .filter(|c| !matches!(c, '"' | '\\' | ';' | '{' | '}' | '<' | '>'))
+        .collect();
+
+    match name.trim() {
+        "" => None,
+        name => Some(name.to_string()),
+    }
+}
+
+fn escape_attribute(value: &str) -> String {
+    let mut escaped = String::with_capacity(value.len());
+    for c in value.chars() {
+        match c {
+            '"' | '\\' => {
+                escaped.push('\\');
+                escaped.push(c);
+            }
+            '<' | '>' | '{' | '}' | '\n' => {}
+            c => escaped.push(c),
+        }
+    }
+    escaped
+}
+
+impl From<i64> for CssColor {
+    fn from(color: i64) -> Self {
+        let [red, green, blue, alpha] = (color as u32).to_be_bytes();
+        CssColor {
+            red,
+            green,
+            blue,
+            alpha,
+        }
+    }
+}
+
+impl Display for CssColor {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "#{:02x}{:02x}{:02x}{:02x}",
+            self.red, self.green, self.blue, self.alpha
+        )
+    }
+}
+
+impl Display for Size {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        if self.0 == 0.0 {
+            return write!(f, "0");
+        }
+        write!(f, "{}{}", self.0, SIZE_UNIT)
+    }
+}
+
+impl Display for Sides {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{} {} {} {}",
+            self.top, self.right, self.bottom, self.left
+        )
+    }
+}
+
+impl Display for Corners {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{} {} {} {}",
+            self.top_left, self.top_right, self.bottom_right, self.bottom_left
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use uuid::Uuid;
+
+    use types::domain::{
+        ChatAnimation, ChatAnimationSettings, ChatColorSettings, ChatFontSettings,
+        ChatHideSettings, ChatSettings, ChatSizeSettings, ChatType, ChatTypeOptions,
+        CustomNickname, StackingDirection, ViewerGroup, ViewerGroupRule,
+    };
+
+    use crate::{ChatLayout, ChatStyle, CssColor, NicknameFill};
+
+    fn chat_settings() -> ChatSettings {
+        ChatSettings {
+            id: Uuid::nil(),
+            name: "chat".to_string(),
+            chat_type: ChatType::BlockReverse,
+            chat_type_options: ChatTypeOptions::default(),
+            color: ChatColorSettings {
+                nickname_color: 0xe9d5ffff,
+                background_color: 0x04040499,
+                text_color: 0xe6e6e6ff,
+                gradient_only_for_custom_nicknames: true,
+                custom_nicknames: vec![CustomNickname {
+                    nickname: "Streamer".to_string(),
+                    user_id: None,
+                    start_color: 0xff0000ff,
+                    middle_colors: Vec::new(),
+                    end_color: 0x0000ff80,
+                    display_name: None,
+                    badge_url: None,
+                    font_weight: None,
+                }],
+            },
+            size: ChatSizeSettings {
+                margin_top: 2.0,
+                margin_right: 2.0,
+                margin_bottom: 2.0,
+                margin_left: 2.0,
+                padding_top: 1.5,
+                padding_right: 2.0,
+                padding_bottom: 1.5,
+                padding_left: 2.0,
+                border_top_left_radius: 2.0,
+                border_top_right_radius: 2.0,
+                border_bottom_left_radius: 0.0,
+                border_bottom_right_radius: 2.0,
+                max_messages: 50,
+            },
+            hide: ChatHideSettings {
+                hide_message_pattern: String::new(),
+                hide_point_rewards: false,
+                point_rewards: Vec::new(),
+                hide_links: false,
+                link_replacement: String::new(),
+                ban_word_replacement: String::new(),
+                nicknames: Vec::new(),
+                ban_word_filter_id: None,
+                viewer_groups: Vec::new(),
+            },
+            font: ChatFontSettings {
+                font_family: "Roboto".to_string(),
+                nickname_font_weight: 700,
+                text_font_weight: 400,
+                font_size: 3.5,
+            },
+            animation: ChatAnimationSettings {
+                message_ttl: 0,
+                enter_animation: ChatAnimation::None,
+                enter_duration: 300,
+                exit_animation: ChatAnimation::None,
+                exit_duration: 300,
+                stacking_direction: StackingDirection::Auto,
+            },
+            user_id: "1337".to_string(),
+            revision: 1,
+        }
+    }
+
+    #[test]
+    fn style_model() {
+        let style = ChatStyle::from(&chat_settings());
+
+        assert_eq!(style.layout, ChatLayout::Block);
+        assert!(style.reverse);
+        assert_eq!(
+            style.background_color,
+            CssColor {
+                red: 0x04,
+                green: 0x04,
+                blue: 0x04,
+                alpha: 0x99,
+            }
+        );
+        assert_eq!(
+            style.nickname,
+            NicknameFill::Color(CssColor::from(0xe9d5ffff))
+        );
+        assert_eq!(style.border_radius.to_string(), "2vh 2vh 2vh 0");
+        assert_eq!(style.font.family.as_deref(), Some("Roboto"));
+    }
+
+    #[test]
+    fn gradient_nicknames() {
+        let css = ChatStyle::from(&chat_settings()).to_css();
+
+        assert!(css.contains(".name {\n  color: #e9d5ffff;\n}"));
+        assert!(css.contains(
+            ".message[data-login=\"streamer\" i] .name {\n  color: #ff0000ff;\n  background-image: linear-gradient(90deg, #ff0000ff, #0000ff80);"
+        ));
+
+        let mut chat_settings = chat_settings();
+        chat_settings.color.gradient_only_for_custom_nicknames = false;
+        let css = ChatStyle::from(&chat_settings).to_css();
+
+        assert!(css.contains(
+            "background-image: linear-gradient(90deg, #e9d5ffff, var(--user-color, #e9d5ffff));"
+        ));
+    }
+
+    #[test]
+    fn custom_nickname_styles() {
+        let mut chat_settings = chat_settings();
+        chat_settings.color.custom_nicknames[0].user_id = Some("42".to_string());
+        chat_settings.color.custom_nicknames[0].middle_colors = vec![0x00ff00ff];
+        chat_settings.color.custom_nicknames[0].font_weight = Some(800);
+        let css = ChatStyle::from(&chat_settings).to_css();
+
+        assert!(!css.contains("data-login"));
+        assert!(css.contains(".message[data-user-id=\"42\"] .nickname {\n  font-weight: 800;\n}"));
+        assert!(css.contains(
+            ".message[data-user-id=\"42\"] .name {\n  color: #ff0000ff;\n  background-image: linear-gradient(90deg, #ff0000ff, #00ff00ff, #0000ff80);"
+        ));
+    }
+
+    #[test]
+    fn viewer_group_styles() {
+        let mut chat_settings = chat_settings();
+        chat_settings.hide.viewer_groups = vec![
+            ViewerGroupRule {
+                group: ViewerGroup::Moderator,
+                hidden: false,
+                exempt_from_ban_words: false,
+                nickname_color: Some(0x00ff00ff),
+                background_color: Some(0x00000080),
+                font_weight: Some(900),
+            },
+            ViewerGroupRule {
+                group: ViewerGroup::KnownBot,
+                hidden: true,
+                exempt_from_ban_words: false,
+                nickname_color: None,
+                background_color: None,
+                font_weight: None,
+            },
+        ];
+        let css = ChatStyle::from(&chat_settings).to_css();
+
+        assert!(!css.contains("known-bot"));
+        assert!(css.contains(
+            ".message[data-viewer-groups~=\"moderator\"]:not(.message--highlighted) {\n  background-color: #00000080;\n}"
+        ));
+        assert!(css.contains(
+            ".message[data-viewer-groups~=\"moderator\"] .nickname {\n  font-weight: 900;\n}"
+        ));
+        assert!(css.contains(
+            ".message[data-viewer-groups~=\"moderator\"] .name {\n  color: #00ff00ff;\n  background-image: none;\n  -webkit-text-fill-color: #00ff00ff;\n}"
+        ));
+        // Custom nicknames come later, so they win.
+        assert!(
+            css.find("data-viewer-groups").unwrap() < css.find("data-login=\"streamer\"").unwrap()
+        );
+    }
+
+    #[test]
+    fn stylesheet_etag() {
+        let stylesheet = ChatStyle::from(&chat_settings()).to_stylesheet();
+        assert_eq!(
+            stylesheet,
+            ChatStyle::from(&chat_settings()).to_stylesheet()
+        );
+        assert!(stylesheet.etag.starts_with('"') && stylesheet.etag.ends_with('"'));
+
+        let mut chat_settings = chat_settings();
+        chat_settings.font.font_size = 4.0;
+        assert_ne!(
+            ChatStyle::from(&chat_settings).to_stylesheet().etag,
+            stylesheet.etag
+        );
+    }
+
+    #[test]
+    fn layout_options() {
+        let mut chat_settings = chat_settings();
+        chat_settings.chat_type = ChatType::Bubble;
+        chat_settings.chat_type_options.bubble.max_width = 60.0;
+        chat_settings.chat_type_options.bubble.alternate_sides = true;
+
+        let style = ChatStyle::from(&chat_settings);
+        assert!(!style.reverse);
+        assert_eq!(
+            style.layout,
+            ChatLayout::Bubble {
+                max_width: 60.0,
+                alternate_sides: true,
+                show_tail: true,
+            }
+        );
+
+        let css = style.to_css();
+        assert!(css.contains("max-width: 60%;"));
+        assert!(css.contains(".message--alternate {\n  align-self: flex-end;\n}"));
+        assert!(css.contains("border-color: transparent transparent #04040499 transparent;"));
+
+        chat_settings.chat_type = ChatType::NameStream;
+        chat_settings.chat_type_options.name_stream.show_text = true;
+        assert!(!ChatStyle::from(&chat_settings)
+            .to_css()
+            .contains(".text {\n  display: none;\n}"));
+
+        chat_settings.chat_type = ChatType::Compact;
+        let css = ChatStyle::from(&chat_settings).to_css();
+        assert!(css.contains("-webkit-line-clamp: 2;"));
+        assert!(css.contains("margin: 0 0 0;"));
+    }
+
+    #[test]
+    fn animations() {
+        let css = ChatStyle::from(&chat_settings()).to_css();
+        assert!(!css.contains("@keyframes"));
+        assert!(css.contains("justify-content: flex-end;"));
+
+        let mut chat_settings = chat_settings();
+        chat_settings.animation.enter_animation = ChatAnimation::SlideUp;
+        chat_settings.animation.exit_animation = ChatAnimation::SlideLeft;
+        chat_settings.animation.exit_duration = 0;
+        chat_settings.animation.stacking_direction = StackingDirection::Down;
+
+        let style = ChatStyle::from(&chat_settings);
+        assert!(style.exit.is_none());
+
+        let css = style.to_css();
+        assert!(css.contains("    opacity: 0;\n    transform: translateY(100%);\n"));
+        assert!(css.contains("animation: message-enter 300ms ease-out;"));
+        assert!(!css.contains("message-exit"));
+        // `block-reverse` grows from the bottom, stacking down sticks to the top.
+        assert!(css.contains("justify-content: flex-end;"));
+
+        chat_settings.chat_type = ChatType::Block;
+        chat_settings.animation.exit_duration = 400;
+        let css = ChatStyle::from(&chat_settings).to_css();
+        assert!(css.contains("justify-content: flex-start;"));
+        assert!(css.contains("    opacity: 0;\n    transform: translateX(-100%);\n"));
+        assert!(css.contains("animation: message-exit 400ms ease-in forwards;"));
+    }
+}
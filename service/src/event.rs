@@ -0,0 +1,210 @@
+use std::collections::HashSet;
+use std::sync::{Arc, Mutex};
+
+use axum::http::StatusCode;
+use tokio::sync::{broadcast, mpsc};
+use tracing::instrument;
+
+use config::TwitchConfig;
+use twitch_api::{
+    EventSubSubscription, EventSubWebSocket, EventSubWebhook, EventSubWebhookMessage,
+    SubscriptionType, TwitchApi,
+};
+use types::error::{AppError, AppResult};
+use types::twitch;
+
+use crate::TwitchService;
+
+const EVENTS_CAPACITY: usize = 256;
+/// Events overlays show as alerts, or apply on top of what the chat itself tells.
+const OVERLAY_SUBSCRIPTION_TYPES: &[SubscriptionType] = &[
+    SubscriptionType::CHANNEL_POINTS_REDEMPTION,
+    SubscriptionType::CHANNEL_FOLLOW,
+    SubscriptionType::CHANNEL_SUBSCRIBE,
+    SubscriptionType::CHANNEL_CHEER,
+    SubscriptionType::CHANNEL_RAID,
+    SubscriptionType::CHANNEL_CHAT_CLEAR,
+    SubscriptionType::CHANNEL_CHAT_MESSAGE_DELETE,
+];
+
+/// Fan-out point for channel events, whichever EventSub transport received them.
+pub struct EventService {
+    twitch_api: Arc<TwitchApi>,
+    twitch_service: Arc<TwitchService>,
+    webhook: Option<EventSubWebhook>,
+    callback_url: Option<String>,
+    events: broadcast::Sender<twitch::Event>,
+    watched: Mutex<HashSet<String>>,
+}
+
+impl EventService {
+    pub fn new(
+        twitch_api: Arc<TwitchApi>,
+        twitch_service: Arc<TwitchService>,
+        twitch_config: &TwitchConfig,
+    ) -> Self {
+        let (events, _) = broadcast::channel(EVENTS_CAPACITY);
+
+        EventService {
+            twitch_api,
+            twitch_service,
+            webhook: twitch_config.eventsub_secret().map(EventSubWebhook::new),
+            callback_url: twitch_config.eventsub_callback_url().map(str::to_string),
+            events,
+            watched: Mutex::new(HashSet::new()),
+        }
+    }
+
+    pub fn subscribe(&self) -> broadcast::Receiver<twitch::Event> {
+        self.events.subscribe()
+    }
+
+    pub fn dispatch(&self, event: twitch::Event) {
+        // No receivers is fine, nobody is watching the channel right now.
+        let _ = self.events.send(event);
+    }
+
+    /// Subscribes to the overlay events of the broadcaster, once per process.
+    /// Twitch keeps webhook subscriptions, so they aren't removed when the
+    /// channel is left. Without a webhook the events come over a WebSocket
+    /// with the broadcaster's token, for as long as the process runs.
+    pub fn watch(self: &Arc<Self>, broadcaster_id: &str) {
+        if !self
+            .watched
+            .lock()
+            .unwrap()
+            .insert(broadcaster_id.to_string())
+        {
+            return;
+        }
+
+        let event_service = self.clone();
+        let broadcaster_id = broadcaster_id.to_string();
+        tokio::spawn(async move {
+            if event_service.webhook.is_some() {
+                match event_service
+                    .subscribe_webhook(&broadcaster_id, OVERLAY_SUBSCRIPTION_TYPES)
+                    .await
+                {
+                    Ok(_) => return,
+                    // Already existing subscriptions fail too, a later join retries anyway.
+                    Err(e) => tracing::warn!({ error = ?e }, "fail subscribe eventsub webhook"),
+                }
+            } else if let Err(e) = event_service.run_websocket(&broadcaster_id).await {
+                tracing::warn!({ error = ?e }, "eventsub websocket stopped");
+            }
+
+            // A later join watches the channel again.
+            event_service
+                .watched
+                .lock()
+                .unwrap()
+                .remove(&broadcaster_id);
+        });
+    }
+
+    /// Returns the challenge to answer with when Twitch verifies the callback.
+    #[instrument(skip(self, signature, body))]
+    pub fn handle_webhook(
+        &self,
+        message_id: &str,
+        message_type: &str,
+        timestamp: &str,
+        signature: &str,
+        body: &[u8],
+    ) -> AppResult<Option<String>> {
+        let webhook = self
+            .webhook
+            .as_ref()
+            .ok_or(EventService::WEBHOOK_NOT_CONFIGURED_ERROR)?;
+
+        match webhook.handle(message_id, message_type, timestamp, signature, body)? {
+            EventSubWebhookMessage::Verification(challenge) => Ok(Some(challenge)),
+            EventSubWebhookMessage::Notification(event) => {
+                self.dispatch(*event);
+                Ok(None)
+            }
+            EventSubWebhookMessage::Ignored => Ok(None),
+        }
+    }
+
+    #[instrument(skip(self))]
+    async fn run_websocket(&self, broadcaster_id: &str) -> AppResult {
+        let user_token = self.twitch_service.get_user_token(broadcaster_id).await?;
+        let eventsub = EventSubWebSocket::new(self.twitch_api.clone());
+        let (sender, mut receiver) = mpsc::channel(EVENTS_CAPACITY);
+
+        let run = eventsub.run(
+            &user_token,
+            broadcaster_id,
+            OVERLAY_SUBSCRIPTION_TYPES,
+            sender,
+        );
+        let forward = async {
+            while let Some(event) = receiver.recv().await {
+                self.dispatch(event);
+            }
+        };
+
+        let (result, _) = tokio::join!(run, forward);
+        result
+    }
+
+    #[instrument(skip(self))]
+    pub async fn subscribe_webhook(
+        &self,
+        broadcaster_id: &str,
+        subscription_types: &[SubscriptionType],
+    ) -> AppResult<Vec<EventSubSubscription>> {
+        let (webhook, callback_url) = self
+            .webhook
+            .as_ref()
+            .zip(self.callback_url.as_deref())
+            .ok_or(EventService::WEBHOOK_NOT_CONFIGURED_ERROR)?;
+
+        let mut subscriptions = Vec::with_capacity(subscription_types.len());
+        for subscription_type in subscription_types {
+            let subscription = self
+                .twitch_api
+                .create_eventsub_webhook_subscription(
+                    subscription_type,
+                    broadcaster_id,
+                    callback_url,
+                    webhook.secret(),
+                )
+                .await?;
+            subscriptions.push(subscription);
+        }
+
+        Ok(subscriptions)
+    }
+}
+
+macro_rules! event_service_errors {
+    (
+        $(
+            $(#[$docs:meta])*
+            ($name:ident, $status:expr, $phrase:expr);
+        )+
+    ) => {
+        impl EventService {
+        $(
+            $(#[$docs])*
+            pub const $name: AppError = AppError {
+                status_code: $status,
+                message: Some($phrase),
+                cause: None,
+                other: None
+            };
+        )+
+        }
+    }
+}
+
+event_service_errors! {
+    (WEBHOOK_NOT_CONFIGURED_ERROR, StatusCode::NOT_FOUND, "eventsub webhook is not configured");
+    (MISSING_MESSAGE_ID_ERROR, StatusCode::BAD_REQUEST, "missing message id header");
+    (MISSING_MESSAGE_TYPE_ERROR, StatusCode::BAD_REQUEST, "missing message type header");
+    (MISSING_MESSAGE_TIMESTAMP_ERROR, StatusCode::BAD_REQUEST, "missing message timestamp header");
+    (MISSING_MESSAGE_SIGNATURE_ERROR, StatusCode::BAD_REQUEST, "missing message signature header");
+}
@@ -1,11 +1,35 @@
 pub use auth::*;
 pub use ban_word::*;
 pub use chat::*;
+pub use chat_cluster::*;
+pub use chat_style::*;
+pub use event::*;
+pub use message_filter::*;
+pub use overlay::*;
 pub use session::*;
+pub use settings_bus::*;
+pub use tokenizer::*;
+pub use trash::*;
 pub use twitch::*;
 
 mod auth;
+mod backoff;
 mod ban_word;
 mod chat;
+mod chat_cluster;
+mod chat_style;
+mod event;
+mod message_filter;
+mod overlay;
+mod overlay_page;
+mod preview;
 mod session;
+mod settings_bus;
+mod settings_diff;
+mod settings_export;
+mod settings_patch;
+mod settings_template;
+mod tokenizer;
+mod trash;
 mod twitch;
+mod viewer_group;
@@ -0,0 +1,523 @@
+use std::cmp::Reverse;
+use std::collections::{HashMap, HashSet};
+
+use regex::Regex;
+
+use types::domain::{
+    ChatHideSettings, CustomNickname, MessageFragment, OverlayMessage, PointReward, ViewerGroup,
+};
+
+use crate::tokenizer::FragmentsBuilder;
+
+/// Applies the `ChatHideSettings` and custom nicknames of an overlay to the
+/// messages it receives.
+pub struct MessageFilter {
+    hide_message_pattern: Option<Regex>,
+    hidden_nicknames: HashSet<String>,
+    hidden_viewer_groups: HashSet<ViewerGroup>,
+    ban_word_exempt_viewer_groups: HashSet<ViewerGroup>,
+    hide_point_rewards: bool,
+    point_rewards: HashMap<String, PointReward>,
+    hide_links: bool,
+    link_replacement: String,
+    /// Lowercase words of each ban word, several for a phrase, longest first.
+    ban_words: Vec<Vec<String>>,
+    ban_word_replacement: String,
+    custom_nicknames_by_user_id: HashMap<String, CustomNickname>,
+    custom_nicknames_by_login: HashMap<String, CustomNickname>,
+}
+
+impl MessageFilter {
+    /// `ban_words` are the words of the filter referenced by `ban_word_filter_id`.
+    pub fn new(hide_settings: &ChatHideSettings, ban_words: &[String]) -> Self {
+        let hide_message_pattern = match hide_settings.hide_message_pattern.as_str() {
+            "" => None,
+            pattern => Regex::new(pattern)
+                .map_err(|e| tracing::warn!({ error = %e }, "invalid hide message pattern"))
+                .ok(),
+        };
+
+        MessageFilter {
+            hide_message_pattern,
+            hidden_nicknames: hide_settings
+                .nicknames
+                .iter()
+                .map(|nickname| nickname.to_lowercase())
+                .collect(),
+            hidden_viewer_groups: hide_settings
+                .viewer_groups
+                .iter()
+                .filter(|rule| rule.hidden)
+                .map(|rule| rule.group)
+                .collect(),
+            ban_word_exempt_viewer_groups: hide_settings
+                .viewer_groups
+                .iter()
+                .filter(|rule| rule.exempt_from_ban_words)
+                .map(|rule| rule.group)
+                .collect(),
+            hide_point_rewards: hide_settings.hide_point_rewards,
+            point_rewards: hide_settings
+                .point_rewards
+                .iter()
+                .map(|point_reward| (point_reward.reward_id.clone(), point_reward.clone()))
+                .collect(),
+            hide_links: hide_settings.hide_links,
+            link_replacement: hide_settings.link_replacement.clone(),
+            ban_words: ban_phrases(ban_words),
+            ban_word_replacement: hide_settings.ban_word_replacement.clone(),
+            custom_nicknames_by_user_id: HashMap::new(),
+            custom_nicknames_by_login: HashMap::new(),
+        }
+    }
+
+    /// Entries are matched by Twitch user id when it's known, by login
+    /// otherwise, like the custom nickname rules of the stylesheet.
+    pub fn with_custom_nicknames(mut self, custom_nicknames: &[CustomNickname]) -> Self {
+        for custom_nickname in custom_nicknames {
+            match &custom_nickname.user_id {
+                Some(user_id) => self
+                    .custom_nicknames_by_user_id
+                    .insert(user_id.clone(), custom_nickname.clone()),
+                None => self.custom_nicknames_by_login.insert(
+                    custom_nickname.nickname.to_lowercase(),
+                    custom_nickname.clone(),
+                ),
+            };
+        }
+        self
+    }
+
+    /// Returns `None` when the message must not be shown at all.
+    pub fn apply(&self, mut message: OverlayMessage) -> Option<OverlayMessage> {
+        if self.hidden_nicknames.contains(&message.login)
+            || self
+                .hidden_nicknames
+                .contains(&message.display_name.to_lowercase())
+        {
+            return None;
+        }
+
+        if message
+            .viewer_groups
+            .iter()
+            .any(|group| self.hidden_viewer_groups.contains(group))
+        {
+            return None;
+        }
+
+        if let Some(hide_message_pattern) = &self.hide_message_pattern {
+            let text: String = message
+                .fragments
+                .iter()
+                .map(MessageFragment::text)
+                .collect();
+            if hide_message_pattern.is_match(&text) {
+                return None;
+            }
+        }
+
+        if let Some(reward_id) = &message.reward_id {
+            // A rule for the reward takes precedence over hiding every reward.
+            match self.point_rewards.get(reward_id) {
+                Some(point_reward) if point_reward.hidden => return None,
+                Some(point_reward) => message.highlight_color = point_reward.highlight_color,
+                None if self.hide_point_rewards => return None,
+                None => {}
+            }
+        }
+
+        let exempt_from_ban_words = message
+            .viewer_groups
+            .iter()
+            .any(|group| self.ban_word_exempt_viewer_groups.contains(group));
+        message.fragments = self.replace_fragments(message.fragments, exempt_from_ban_words);
+
+        let custom_nickname = self
+            .custom_nicknames_by_user_id
+            .get(&message.user_id)
+            .or_else(|| self.custom_nicknames_by_login.get(&message.login));
+        if let Some(custom_nickname) = custom_nickname {
+            if let Some(display_name) = &custom_nickname.display_name {
+                message.display_name = display_name.clone();
+            }
+            message.badge_url = custom_nickname.badge_url.clone();
+        }
+
+        Some(message)
+    }
+
+    /// Redemption alerts follow the same point reward rules as the messages.
+    pub fn is_reward_hidden(&self, reward_id: &str) -> bool {
+        match self.point_rewards.get(reward_id) {
+            Some(point_reward) => point_reward.hidden,
+            None => self.hide_point_rewards,
+        }
+    }
+
+    fn replace_fragments(
+        &self,
+        fragments: Vec<MessageFragment>,
+        exempt_from_ban_words: bool,
+    ) -> Vec<MessageFragment> {
+        let mut replaced = FragmentsBuilder::default();
+
+        for fragment in fragments {
+            match fragment {
+                MessageFragment::Text { text } if exempt_from_ban_words => {
+                    replaced.push_text(&text)
+                }
+                MessageFragment::Text { text } => {
+                    replaced.push_text(&self.replace_ban_words(&text))
+                }
+                MessageFragment::Link { .. } if self.hide_links => {
+                    replaced.push_text(&self.link_replacement)
+                }
+                fragment => replaced.push(fragment),
+            }
+        }
+
+        replaced.build()
+    }
+
+    /// Replaces whole words and phrases, the punctuation around and between
+    /// them stays, e.g. "bad," becomes "***," while "badly" is kept.
+    fn replace_ban_words(&self, text: &str) -> String {
+        if self.ban_words.is_empty() {
+            return text.to_string();
+        }
+
+        // Byte range and lowercase of each word.
+        let words: Vec<(usize, usize, String)> = split_words(text)
+            .map(|(start, word)| (start, start + word.len(), word.to_lowercase()))
+            .collect();
+        let mut replaced = String::with_capacity(text.len());
+        let mut copied = 0;
+        let mut i = 0;
+
+        while i < words.len() {
+            let ban_word = self.ban_words.iter().find(|ban_word| {
+                words.len() - i >= ban_word.len()
+                    && words[i..i + ban_word.len()]
+                        .iter()
+                        .zip(ban_word.iter())
+                        .all(|((_, _, word), ban_word)| word == ban_word)
+            });
+
+            let Some(ban_word) = ban_word else {
+                i += 1;
+                continue;
+            };
+
+            let start = words[i].0;
+            let end = words[i + ban_word.len() - 1].1;
+            replaced.push_str(&text[copied..start]);
+            replaced.push_str(&self.ban_word_replacement);
+            copied = end;
+            i += ban_word.len();
+        }
+
+        replaced.push_str(&text[copied..]);
+        replaced
+    }
+}
+
+/// Longest phrases first, so "bad word" is replaced whole even when "bad"
+/// is banned too.
+fn ban_phrases(ban_words: &[String]) -> Vec<Vec<String>> {
+    let mut ban_phrases: Vec<Vec<String>> = ban_words
+        .iter()
+        .map(|ban_word| {
+            split_words(ban_word)
+                .map(|(_, word)| word.to_lowercase())
+                .collect::<Vec<String>>()
+        })
+        .filter(|ban_phrase| !ban_phrase.is_empty())
+        .collect();
+    ban_phrases.sort_by_key(|ban_phrase| Reverse(ban_phrase.len()));
+    ban_phrases
+}
+
+/// Words of the text with their byte offset, anything but letters and digits
+/// separates them.
+fn split_words(text: &str) -> impl Iterator<Item = (usize, &str)> {
+    text.char_indices()
+        .filter(move |(i, c)| {
+            c.is_alphanumeric()
+                && !text[..*i]
+                    .chars()
+                    .next_back()
+                    .is_some_and(char::is_alphanumeric)
+        })
+        .map(move |(i, _)| {
+            let len = text[i..]
+                .find(|c: char| !c.is_alphanumeric())
+                .unwrap_or(text.len() - i);
+            (i, &text[i..i + len])
+        })
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::Utc;
+
+    use types::domain::{
+        ChatHideSettings, CustomNickname, MessageFragment, OverlayMessage, PointReward,
+        ViewerGroup, ViewerGroupRule,
+    };
+
+    use crate::MessageFilter;
+
+    fn hide_settings() -> ChatHideSettings {
+        ChatHideSettings {
+            hide_message_pattern: String::new(),
+            hide_point_rewards: false,
+            point_rewards: Vec::new(),
+            hide_links: false,
+            link_replacement: String::new(),
+            ban_word_replacement: String::new(),
+            nicknames: Vec::new(),
+            ban_word_filter_id: None,
+            viewer_groups: Vec::new(),
+        }
+    }
+
+    fn message(fragments: Vec<MessageFragment>) -> OverlayMessage {
+        OverlayMessage {
+            id: "1".to_string(),
+            user_id: "42".to_string(),
+            login: "viewer".to_string(),
+            display_name: "Viewer".to_string(),
+            color: None,
+            badges: Vec::new(),
+            viewer_groups: Vec::new(),
+            fragments,
+            reward_id: None,
+            highlight_color: None,
+            badge_url: None,
+            sent_at: Utc::now(),
+        }
+    }
+
+    fn text(text: &str) -> MessageFragment {
+        MessageFragment::Text {
+            text: text.to_string(),
+        }
+    }
+
+    #[test]
+    fn hides_messages() {
+        let filter = MessageFilter::new(
+            &ChatHideSettings {
+                hide_message_pattern: "^!".to_string(),
+                nicknames: vec!["NightBot".to_string()],
+                ..hide_settings()
+            },
+            &[],
+        );
+
+        assert!(filter.apply(message(vec![text("!uptime")])).is_none());
+        assert!(filter.apply(message(vec![text("hello !uptime")])).is_some());
+
+        let bot_message = OverlayMessage {
+            login: "nightbot".to_string(),
+            display_name: "Nightbot".to_string(),
+            ..message(vec![text("hello")])
+        };
+        assert!(filter.apply(bot_message).is_none());
+    }
+
+    #[test]
+    fn point_rewards() {
+        let filter = MessageFilter::new(
+            &ChatHideSettings {
+                hide_point_rewards: true,
+                point_rewards: vec![
+                    PointReward {
+                        reward_id: "highlighted".to_string(),
+                        hidden: false,
+                        highlight_color: Some(0xff0000ff),
+                    },
+                    PointReward {
+                        reward_id: "hidden".to_string(),
+                        hidden: true,
+                        highlight_color: None,
+                    },
+                ],
+                ..hide_settings()
+            },
+            &[],
+        );
+        let redemption = |reward_id: &str| OverlayMessage {
+            reward_id: Some(reward_id.to_string()),
+            ..message(vec![text("hello")])
+        };
+
+        assert_eq!(
+            filter
+                .apply(redemption("highlighted"))
+                .and_then(|message| message.highlight_color),
+            Some(0xff0000ff)
+        );
+        assert!(filter.apply(redemption("hidden")).is_none());
+        assert!(filter.apply(redemption("other")).is_none());
+        assert!(filter.apply(message(vec![text("hello")])).is_some());
+
+        assert!(!filter.is_reward_hidden("highlighted"));
+        assert!(filter.is_reward_hidden("hidden"));
+        assert!(filter.is_reward_hidden("other"));
+    }
+
+    #[test]
+    fn replaces_links_and_ban_words() {
+        let filter = MessageFilter::new(
+            &ChatHideSettings {
+                hide_links: true,
+                link_replacement: "<link>".to_string(),
+                ban_word_replacement: "***".to_string(),
+                ..hide_settings()
+            },
+            &["Bad".to_string()],
+        );
+
+        let filtered = filter.apply(message(vec![
+            text("so BAD, see "),
+            MessageFragment::Link {
+                text: "example.com".to_string(),
+                url: "https://example.com".to_string(),
+            },
+            text(" bad"),
+        ]));
+
+        assert_eq!(
+            filtered.map(|message| message.fragments),
+            Some(vec![text("so ***, see <link> ***")])
+        );
+    }
+
+    #[test]
+    fn ban_words_match_whole_words() {
+        let filter = MessageFilter::new(
+            &ChatHideSettings {
+                ban_word_replacement: "***".to_string(),
+                ..hide_settings()
+            },
+            &["ass".to_string()],
+        );
+
+        let filtered = filter.apply(message(vec![text("class, assassin and grass: ASS!")]));
+
+        assert_eq!(
+            filtered.map(|message| message.fragments),
+            Some(vec![text("class, assassin and grass: ***!")])
+        );
+    }
+
+    #[test]
+    fn ban_phrases_match_across_words() {
+        let filter = MessageFilter::new(
+            &ChatHideSettings {
+                ban_word_replacement: "***".to_string(),
+                ..hide_settings()
+            },
+            &["very".to_string(), "very bad  word".to_string()],
+        );
+
+        let filtered = filter.apply(message(vec![text("a Very, bad word. very good, bad word")]));
+
+        assert_eq!(
+            filtered.map(|message| message.fragments),
+            Some(vec![text("a ***. *** good, bad word")])
+        );
+    }
+
+    #[test]
+    fn viewer_group_rules() {
+        let rule =
+            |group: ViewerGroup, hidden: bool, exempt_from_ban_words: bool| ViewerGroupRule {
+                group,
+                hidden,
+                exempt_from_ban_words,
+                nickname_color: None,
+                background_color: None,
+                font_weight: None,
+            };
+        let filter = MessageFilter::new(
+            &ChatHideSettings {
+                ban_word_replacement: "***".to_string(),
+                viewer_groups: vec![
+                    rule(ViewerGroup::KnownBot, true, false),
+                    rule(ViewerGroup::Moderator, false, true),
+                ],
+                ..hide_settings()
+            },
+            &["bad".to_string()],
+        );
+        let from = |viewer_groups: Vec<ViewerGroup>| OverlayMessage {
+            viewer_groups,
+            ..message(vec![text("bad word")])
+        };
+
+        assert!(filter
+            .apply(from(vec![ViewerGroup::Moderator, ViewerGroup::KnownBot]))
+            .is_none());
+        assert_eq!(
+            filter
+                .apply(from(vec![ViewerGroup::Vip, ViewerGroup::Moderator]))
+                .map(|message| message.fragments),
+            Some(vec![text("bad word")])
+        );
+        assert_eq!(
+            filter
+                .apply(from(vec![ViewerGroup::Vip]))
+                .map(|message| message.fragments),
+            Some(vec![text("*** word")])
+        );
+    }
+
+    #[test]
+    fn applies_custom_nicknames() {
+        let custom_nickname = |nickname: &str, user_id: Option<&str>| CustomNickname {
+            nickname: nickname.to_string(),
+            user_id: user_id.map(str::to_string),
+            start_color: 0xff0000ff,
+            middle_colors: Vec::new(),
+            end_color: 0x0000ffff,
+            display_name: Some(format!("{} override", nickname)),
+            badge_url: Some("https://example.com/badge.png".to_string()),
+            font_weight: None,
+        };
+        let filter = MessageFilter::new(&hide_settings(), &[]).with_custom_nicknames(&[
+            custom_nickname("OldName", Some("42")),
+            custom_nickname("Other", None),
+        ]);
+
+        // The viewer renamed since the entry was saved.
+        let renamed = filter.apply(message(vec![text("hello")])).unwrap();
+        assert_eq!(renamed.display_name, "OldName override");
+        assert_eq!(
+            renamed.badge_url.as_deref(),
+            Some("https://example.com/badge.png")
+        );
+
+        let by_login = filter
+            .apply(OverlayMessage {
+                user_id: "7".to_string(),
+                login: "other".to_string(),
+                ..message(vec![text("hello")])
+            })
+            .unwrap();
+        assert_eq!(by_login.display_name, "Other override");
+
+        let unknown = filter
+            .apply(OverlayMessage {
+                user_id: "8".to_string(),
+                login: "unknown".to_string(),
+                display_name: "Unknown".to_string(),
+                ..message(vec![text("hello")])
+            })
+            .unwrap();
+        assert_eq!(unknown.display_name, "Unknown");
+        assert_eq!(unknown.badge_url, None);
+    }
+}
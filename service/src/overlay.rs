@@ -0,0 +1,1165 @@
+use std::collections::{HashMap, VecDeque};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use axum::http::StatusCode;
+use tokio::sync::{broadcast, mpsc};
+use tokio::time::{interval, Instant};
+use tracing::instrument;
+use uuid::Uuid;
+
+use dao::{BanWordFilterDao, ChatSettingsDao, UserDao};
+use twitch_api::{ChatSource, TwitchApi};
+use types::domain::{
+    ChatPreview, ChatSettings, OverlayEvent, OverlayMessage, PreviewChatSettings, PreviewMessage,
+    SettingsChange, User,
+};
+use types::error::AppResult;
+use types::twitch;
+
+use crate::viewer_group::viewer_groups;
+use crate::{
+    overlay_page, preview, ChatStyle, EventService, MessageFilter, MessageTokenizer, SettingsBus,
+};
+
+const EVENTS_CAPACITY: usize = 256;
+const IDLE_TIMEOUT: Duration = Duration::from_secs(5 * 60);
+/// A quiet chat sends no events, so idleness is checked on a timer too.
+const IDLE_CHECK_INTERVAL: Duration = Duration::from_secs(30);
+
+pub struct OverlayService {
+    hub: OverlayHub,
+    twitch_api: Arc<TwitchApi>,
+    event_service: Arc<EventService>,
+    settings: SettingsLoader,
+    settings_bus: Arc<SettingsBus>,
+    user_dao: Arc<UserDao>,
+}
+
+impl OverlayService {
+    pub fn new(
+        chat_source: Arc<dyn ChatSource>,
+        twitch_api: Arc<TwitchApi>,
+        event_service: Arc<EventService>,
+        settings_bus: Arc<SettingsBus>,
+        chat_settings_dao: Arc<ChatSettingsDao>,
+        ban_word_filter_dao: Arc<BanWordFilterDao>,
+        user_dao: Arc<UserDao>,
+    ) -> Self {
+        OverlayService {
+            hub: OverlayHub::new(chat_source),
+            twitch_api,
+            event_service,
+            settings: SettingsLoader {
+                chat_settings_dao,
+                ban_word_filter_dao,
+            },
+            settings_bus,
+            user_dao,
+        }
+    }
+
+    /// Applies the moderation events EventSub delivers to the channels being
+    /// read, for what the chat connection may have missed.
+    pub fn spawn_event_relay(&self) {
+        let mut events = self.event_service.subscribe();
+        let channels = self.hub.channels.clone();
+
+        tokio::spawn(async move {
+            loop {
+                match events.recv().await {
+                    Ok(event) => relay_event(&channels, event),
+                    Err(broadcast::error::RecvError::Lagged(skipped)) => {
+                        tracing::warn!({ skipped }, "overlay event relay lagged");
+                    }
+                    Err(broadcast::error::RecvError::Closed) => return,
+                }
+            }
+        });
+    }
+
+    /// Live events of the chat the settings belong to. `since` is the id of the
+    /// last message a reconnecting overlay got, the messages it missed after it
+    /// come first.
+    #[instrument(skip(self))]
+    pub async fn subscribe(
+        &self,
+        chat_settings_id: &Uuid,
+        since: Option<&str>,
+    ) -> AppResult<OverlayStream> {
+        // Subscribed first, so no change saved meanwhile is missed.
+        let settings_changes = self.settings_bus.subscribe();
+        let (chat_settings, user) = self.get_chat_settings_with_user(chat_settings_id).await?;
+        let filter = self.settings.get_filter(&chat_settings).await?;
+        let max_messages = chat_settings.size.max_messages as usize;
+
+        // Cheermotes and EventSub are only needed to start reading a channel.
+        let cheermotes = if self.hub.is_joined(&user.username) {
+            Vec::new()
+        } else {
+            self.event_service.watch(&user.id);
+            self.twitch_api
+                .get_channel_cheermotes(&user.id)
+                .await
+                .unwrap_or_else(|e| {
+                    tracing::warn!({ error = ?e }, "fail get cheermotes for overlay");
+                    Vec::new()
+                })
+        };
+
+        let (missed_messages, events) = self.hub.subscribe(
+            &user.username,
+            max_messages,
+            MessageTokenizer::new(Vec::new(), cheermotes),
+            since,
+        );
+
+        Ok(OverlayStream {
+            missed_messages: last_visible(&filter, missed_messages, max_messages).into(),
+            events,
+            filter,
+            chat_settings_id: chat_settings.id,
+            ban_word_filter_id: chat_settings.hide.ban_word_filter_id,
+            settings_changes: Some(settings_changes),
+            is_reload_pending: false,
+            settings: self.settings.clone(),
+        })
+    }
+
+    /// HTML page of the overlay, ready for an OBS browser source.
+    #[instrument(skip(self))]
+    pub async fn render_page(&self, chat_settings_id: &Uuid) -> AppResult<String> {
+        let chat_settings = self
+            .settings
+            .chat_settings_dao
+            .get(chat_settings_id)
+            .await?;
+
+        Ok(overlay_page::render(&chat_settings))
+    }
+
+    /// Unsaved settings applied to `preview_chat_settings.messages`, or to
+    /// built-in samples when there are none.
+    #[instrument(skip(self))]
+    pub async fn preview(
+        &self,
+        user_id: &str,
+        preview_chat_settings: &PreviewChatSettings,
+    ) -> AppResult<ChatPreview> {
+        let (chat_settings, messages) =
+            self.process_preview(user_id, preview_chat_settings).await?;
+
+        Ok(ChatPreview {
+            css: ChatStyle::from(&chat_settings).to_css(),
+            messages,
+        })
+    }
+
+    /// Overlay page showing the visible messages of `preview`.
+    #[instrument(skip(self))]
+    pub async fn render_preview_page(
+        &self,
+        user_id: &str,
+        preview_chat_settings: &PreviewChatSettings,
+    ) -> AppResult<String> {
+        let (chat_settings, messages) =
+            self.process_preview(user_id, preview_chat_settings).await?;
+        let visible_messages: Vec<OverlayMessage> = messages
+            .into_iter()
+            .filter(|preview_message| !preview_message.hidden)
+            .map(|preview_message| preview_message.message)
+            .collect();
+
+        Ok(overlay_page::render_preview(
+            &chat_settings,
+            &visible_messages,
+        ))
+    }
+
+    #[instrument(skip(self))]
+    async fn process_preview(
+        &self,
+        user_id: &str,
+        preview_chat_settings: &PreviewChatSettings,
+    ) -> AppResult<(ChatSettings, Vec<PreviewMessage>)> {
+        let settings = preview_chat_settings.settings.clone();
+        let chat_settings = ChatSettings {
+            id: Uuid::nil(),
+            name: settings.name,
+            chat_type: settings.chat_type,
+            chat_type_options: settings.chat_type_options,
+            color: settings.color.into(),
+            size: settings.size.into(),
+            hide: settings.hide.into(),
+            font: settings.font.into(),
+            animation: settings.animation.into(),
+            user_id: user_id.to_string(),
+            revision: 0,
+        };
+
+        let ban_words = self.settings.get_ban_words(&chat_settings).await?;
+        let filter = MessageFilter::new(&chat_settings.hide, &ban_words)
+            .with_custom_nicknames(&chat_settings.color.custom_nicknames);
+
+        let cheermotes = self
+            .twitch_api
+            .get_channel_cheermotes(user_id)
+            .await
+            .unwrap_or_else(|e| {
+                tracing::warn!({ error = ?e }, "fail get cheermotes for preview");
+                Vec::new()
+            });
+        let tokenizer = MessageTokenizer::new(Vec::new(), cheermotes);
+
+        let samples = match &preview_chat_settings.messages {
+            Some(messages) => messages.clone(),
+            None => preview::sample_messages(&chat_settings, &ban_words),
+        };
+
+        let messages = preview::to_overlay_messages(&tokenizer, samples)
+            .into_iter()
+            .map(|message| match filter.apply(message.clone()) {
+                Some(message) => PreviewMessage {
+                    message,
+                    hidden: false,
+                },
+                None => PreviewMessage {
+                    message,
+                    hidden: true,
+                },
+            })
+            .collect();
+
+        Ok((chat_settings, messages))
+    }
+
+    /// Last `limit` shown messages, never more than the overlay itself shows.
+    #[instrument(skip(self))]
+    pub async fn get_recent_messages(
+        &self,
+        chat_settings_id: &Uuid,
+        limit: Option<usize>,
+    ) -> AppResult<Vec<OverlayMessage>> {
+        let (chat_settings, user) = self.get_chat_settings_with_user(chat_settings_id).await?;
+        let filter = self.settings.get_filter(&chat_settings).await?;
+        let max_messages = chat_settings.size.max_messages as usize;
+
+        Ok(last_visible(
+            &filter,
+            self.hub.recent_messages(&user.username),
+            limit.map_or(max_messages, |limit| limit.min(max_messages)),
+        ))
+    }
+
+    #[instrument(skip(self))]
+    async fn get_chat_settings_with_user(
+        &self,
+        chat_settings_id: &Uuid,
+    ) -> AppResult<(ChatSettings, User)> {
+        let chat_settings = self
+            .settings
+            .chat_settings_dao
+            .get(chat_settings_id)
+            .await?;
+        let user = self.user_dao.get(&chat_settings.user_id).await?;
+
+        Ok((chat_settings, user))
+    }
+}
+
+#[derive(Clone)]
+struct SettingsLoader {
+    chat_settings_dao: Arc<ChatSettingsDao>,
+    ban_word_filter_dao: Arc<BanWordFilterDao>,
+}
+
+impl SettingsLoader {
+    #[instrument(skip(self, chat_settings))]
+    async fn get_filter(&self, chat_settings: &ChatSettings) -> AppResult<MessageFilter> {
+        let ban_words = self.get_ban_words(chat_settings).await?;
+
+        Ok(MessageFilter::new(&chat_settings.hide, &ban_words)
+            .with_custom_nicknames(&chat_settings.color.custom_nicknames))
+    }
+
+    #[instrument(skip(self, chat_settings))]
+    async fn get_ban_words(&self, chat_settings: &ChatSettings) -> AppResult<Vec<String>> {
+        let Some(ban_word_filter_id) = &chat_settings.hide.ban_word_filter_id else {
+            return Ok(Vec::new());
+        };
+
+        // Filters in the trash hide nothing until they are restored.
+        match self.ban_word_filter_dao.get(ban_word_filter_id).await {
+            Ok(ban_word_filter) => Ok(ban_word_filter.ban_words),
+            Err(e) if e.status_code == StatusCode::NOT_FOUND => Ok(Vec::new()),
+            Err(e) => Err(e),
+        }
+    }
+}
+
+fn last_visible(
+    filter: &MessageFilter,
+    messages: Vec<OverlayMessage>,
+    limit: usize,
+) -> Vec<OverlayMessage> {
+    let visible: Vec<OverlayMessage> = messages
+        .into_iter()
+        .filter_map(|message| filter.apply(message))
+        .collect();
+
+    let skip = visible.len().saturating_sub(limit);
+    visible.into_iter().skip(skip).collect()
+}
+
+/// Events of one overlay, with its hide settings applied.
+pub struct OverlayStream {
+    missed_messages: VecDeque<OverlayMessage>,
+    events: broadcast::Receiver<OverlayEvent>,
+    filter: MessageFilter,
+    chat_settings_id: Uuid,
+    ban_word_filter_id: Option<Uuid>,
+    settings_changes: Option<broadcast::Receiver<SettingsChange>>,
+    /// Survives `next` being cancelled while reloading.
+    is_reload_pending: bool,
+    settings: SettingsLoader,
+}
+
+impl OverlayStream {
+    /// Returns `None` once the channel is not read anymore or the settings are gone.
+    pub async fn next(&mut self) -> Option<OverlayEvent> {
+        if let Some(message) = self.missed_messages.pop_front() {
+            return Some(OverlayEvent::Message(Box::new(message)));
+        }
+
+        loop {
+            if self.is_reload_pending {
+                match self.reload_settings().await {
+                    Ok(event) => {
+                        self.is_reload_pending = false;
+                        return Some(event);
+                    }
+                    Err(e) if e.status_code == StatusCode::NOT_FOUND => return None,
+                    Err(e) => {
+                        tracing::warn!({ error = ?e }, "fail reload overlay settings");
+                        self.is_reload_pending = false;
+                    }
+                }
+            }
+
+            tokio::select! {
+                event = self.events.recv() => match event {
+                    Ok(OverlayEvent::Message(message)) => {
+                        if let Some(message) = self.filter.apply(*message) {
+                            return Some(OverlayEvent::Message(Box::new(message)));
+                        }
+                    }
+                    Ok(OverlayEvent::Redemption { reward_id, .. })
+                        if self.filter.is_reward_hidden(&reward_id) => {}
+                    Ok(event) => return Some(event),
+                    // A slow overlay misses some events instead of stalling the channel.
+                    Err(broadcast::error::RecvError::Lagged(_)) => continue,
+                    Err(broadcast::error::RecvError::Closed) => return None,
+                },
+                change = recv_change(&mut self.settings_changes) => match change {
+                    Ok(change) => self.is_reload_pending = self.is_affected_by(&change),
+                    // Whatever was missed may have been ours.
+                    Err(broadcast::error::RecvError::Lagged(_)) => self.is_reload_pending = true,
+                    Err(broadcast::error::RecvError::Closed) => self.settings_changes = None,
+                },
+            }
+        }
+    }
+
+    fn is_affected_by(&self, change: &SettingsChange) -> bool {
+        match change {
+            SettingsChange::ChatSettings { id } => *id == self.chat_settings_id,
+            SettingsChange::BanWordFilter { id } => Some(*id) == self.ban_word_filter_id,
+        }
+    }
+
+    async fn reload_settings(&mut self) -> AppResult<OverlayEvent> {
+        let chat_settings = self
+            .settings
+            .chat_settings_dao
+            .get(&self.chat_settings_id)
+            .await?;
+        self.filter = self.settings.get_filter(&chat_settings).await?;
+        self.ban_word_filter_id = chat_settings.hide.ban_word_filter_id;
+
+        Ok(OverlayEvent::Settings {
+            css: ChatStyle::from(&chat_settings).to_css(),
+            settings: Box::new(chat_settings),
+        })
+    }
+}
+
+async fn recv_change(
+    settings_changes: &mut Option<broadcast::Receiver<SettingsChange>>,
+) -> Result<SettingsChange, broadcast::error::RecvError> {
+    match settings_changes {
+        Some(settings_changes) => settings_changes.recv().await,
+        None => std::future::pending().await,
+    }
+}
+
+type Channels = Arc<Mutex<HashMap<String, Arc<ChannelStream>>>>;
+
+/// Reads each channel once and fans its events out to every overlay watching it.
+struct OverlayHub {
+    chat_source: Arc<dyn ChatSource>,
+    channels: Channels,
+}
+
+impl OverlayHub {
+    fn new(chat_source: Arc<dyn ChatSource>) -> Self {
+        OverlayHub {
+            chat_source,
+            channels: Arc::new(Mutex::new(HashMap::new())),
+        }
+    }
+
+    fn is_joined(&self, channel: &str) -> bool {
+        self.channels
+            .lock()
+            .unwrap()
+            .contains_key(&channel.to_ascii_lowercase())
+    }
+
+    fn recent_messages(&self, channel: &str) -> Vec<OverlayMessage> {
+        let channels = self.channels.lock().unwrap();
+
+        match channels.get(&channel.to_ascii_lowercase()) {
+            Some(stream) => stream.recent_messages.lock().unwrap().after(None),
+            None => Vec::new(),
+        }
+    }
+
+    /// `tokenizer` is only used when the channel isn't read yet. Returns the
+    /// messages after `since` along with the events following them.
+    fn subscribe(
+        &self,
+        channel: &str,
+        max_messages: usize,
+        tokenizer: MessageTokenizer,
+        since: Option<&str>,
+    ) -> (Vec<OverlayMessage>, broadcast::Receiver<OverlayEvent>) {
+        let channel = channel.to_ascii_lowercase();
+        let mut channels = self.channels.lock().unwrap();
+
+        if let Some(stream) = channels.get(&channel) {
+            // Events are sent under this lock, so none is missed or duplicated.
+            let mut recent_messages = stream.recent_messages.lock().unwrap();
+            recent_messages.grow(max_messages);
+
+            let missed_messages = match since {
+                Some(since) => recent_messages.after(Some(since)),
+                None => Vec::new(),
+            };
+            return (missed_messages, stream.events.subscribe());
+        }
+
+        let (events, receiver) = broadcast::channel(EVENTS_CAPACITY);
+        let stream = Arc::new(ChannelStream {
+            events,
+            recent_messages: Mutex::new(RecentMessages::new(max_messages)),
+            tokenizer,
+        });
+        channels.insert(channel.clone(), stream.clone());
+
+        tokio::spawn(read_channel(
+            channel.clone(),
+            self.chat_source.join(&channel),
+            stream,
+            self.channels.clone(),
+        ));
+
+        (Vec::new(), receiver)
+    }
+}
+
+/// What an EventSub event means for the overlays of its channel.
+enum RelayedEvent {
+    /// Goes through the history like the same event from the chat.
+    Chat(twitch::ChatEvent),
+    /// Shown once, reconnecting overlays don't get it again.
+    Alert(OverlayEvent),
+}
+
+fn relay_event(channels: &Channels, event: twitch::Event) {
+    let (channel, relayed_event) = match event {
+        twitch::Event::ChannelPointsRedemption(event) => (
+            event.broadcaster_user_login,
+            RelayedEvent::Alert(OverlayEvent::Redemption {
+                reward_id: event.reward.id,
+                reward_title: event.reward.title,
+                cost: event.reward.cost,
+                user_name: event.user_name,
+                user_input: event.user_input,
+            }),
+        ),
+        twitch::Event::Follow(event) => (
+            event.broadcaster_user_login,
+            RelayedEvent::Alert(OverlayEvent::Follow {
+                user_name: event.user_name,
+            }),
+        ),
+        twitch::Event::Subscribe(event) => (
+            event.broadcaster_user_login,
+            RelayedEvent::Alert(OverlayEvent::Subscription {
+                user_name: event.user_name,
+                tier: event.tier,
+                is_gift: event.is_gift,
+            }),
+        ),
+        twitch::Event::Cheer(event) => (
+            event.broadcaster_user_login,
+            RelayedEvent::Alert(OverlayEvent::Cheer {
+                user_name: event.user_name.filter(|_| !event.is_anonymous),
+                bits: event.bits,
+                message: event.message,
+            }),
+        ),
+        twitch::Event::Raid(event) => (
+            event.to_broadcaster_user_login,
+            RelayedEvent::Alert(OverlayEvent::Raid {
+                from_broadcaster_name: event.from_broadcaster_user_name,
+                viewers: event.viewers,
+            }),
+        ),
+        twitch::Event::ChatClear(event) => (
+            event.broadcaster_user_login.clone(),
+            RelayedEvent::Chat(twitch::ChatEvent::ClearChat {
+                channel: event.broadcaster_user_login,
+                user_id: None,
+            }),
+        ),
+        twitch::Event::ChatMessageDelete(event) => (
+            event.broadcaster_user_login.clone(),
+            RelayedEvent::Chat(twitch::ChatEvent::ClearMessage {
+                channel: event.broadcaster_user_login,
+                message_id: event.message_id,
+            }),
+        ),
+    };
+
+    let stream = channels
+        .lock()
+        .unwrap()
+        .get(&channel.to_ascii_lowercase())
+        .cloned();
+    let Some(stream) = stream else {
+        return;
+    };
+
+    match relayed_event {
+        RelayedEvent::Chat(chat_event) => stream.handle(chat_event),
+        RelayedEvent::Alert(overlay_event) => {
+            let _ = stream.events.send(overlay_event);
+        }
+    }
+}
+
+async fn read_channel(
+    channel: String,
+    mut chat_events: mpsc::Receiver<twitch::ChatEvent>,
+    stream: Arc<ChannelStream>,
+    channels: Channels,
+) {
+    let mut idle_since: Option<Instant> = None;
+    let mut idle_check = interval(IDLE_CHECK_INTERVAL);
+
+    loop {
+        tokio::select! {
+            chat_event = chat_events.recv() => match chat_event {
+                Some(chat_event) => stream.handle(chat_event),
+                None => break,
+            },
+            _ = idle_check.tick() => {}
+        }
+
+        // Checked under the lock, so nobody can subscribe in between.
+        let mut channels = channels.lock().unwrap();
+        if stream.events.receiver_count() > 0 {
+            idle_since = None;
+            continue;
+        }
+
+        // The history outlives overlays for a while, so a reloaded one gets it back.
+        let idle_since = idle_since.get_or_insert_with(Instant::now);
+        if idle_since.elapsed() >= IDLE_TIMEOUT {
+            channels.remove(&channel);
+            return;
+        }
+    }
+
+    channels.lock().unwrap().remove(&channel);
+}
+
+struct ChannelStream {
+    events: broadcast::Sender<OverlayEvent>,
+    recent_messages: Mutex<RecentMessages>,
+    tokenizer: MessageTokenizer,
+}
+
+impl ChannelStream {
+    fn handle(&self, chat_event: twitch::ChatEvent) {
+        let mut recent_messages = self.recent_messages.lock().unwrap();
+
+        let overlay_event = match chat_event {
+            twitch::ChatEvent::Message(message) => {
+                let overlay_message = OverlayMessage {
+                    fragments: self.tokenizer.tokenize(
+                        &message.text,
+                        &message.emotes,
+                        message.bits.is_some(),
+                    ),
+                    viewer_groups: viewer_groups(
+                        &message.login,
+                        &message.badges,
+                        message.first_message,
+                    ),
+                    id: message.id,
+                    user_id: message.user_id,
+                    login: message.login,
+                    display_name: message.display_name,
+                    color: message.color,
+                    badges: message.badges,
+                    reward_id: message.reward_id,
+                    highlight_color: None,
+                    badge_url: None,
+                    sent_at: message.sent_at,
+                };
+                recent_messages.push(overlay_message.clone());
+
+                OverlayEvent::Message(Box::new(overlay_message))
+            }
+            twitch::ChatEvent::ClearMessage { message_id, .. } => {
+                // Messages out of the history are already gone from every overlay.
+                if !recent_messages.remove(&message_id) {
+                    return;
+                }
+                OverlayEvent::Delete {
+                    message_ids: vec![message_id],
+                }
+            }
+            twitch::ChatEvent::ClearChat {
+                user_id: Some(user_id),
+                ..
+            } => {
+                let message_ids = recent_messages.remove_by_user(&user_id);
+                if message_ids.is_empty() {
+                    return;
+                }
+                OverlayEvent::Delete { message_ids }
+            }
+            twitch::ChatEvent::ClearChat { user_id: None, .. } => {
+                // Both the chat and EventSub tell about the same clear.
+                if recent_messages.is_empty() {
+                    return;
+                }
+                recent_messages.clear();
+                OverlayEvent::Clear
+            }
+        };
+
+        // No receivers is fine, the reading task notices and stops.
+        let _ = self.events.send(overlay_event);
+    }
+}
+
+/// Ring buffer of the messages overlays can still show, sized by the biggest
+/// `max_messages` among them.
+struct RecentMessages {
+    messages: VecDeque<OverlayMessage>,
+    capacity: usize,
+}
+
+impl RecentMessages {
+    fn new(capacity: usize) -> Self {
+        RecentMessages {
+            messages: VecDeque::with_capacity(capacity),
+            capacity,
+        }
+    }
+
+    fn grow(&mut self, capacity: usize) {
+        self.capacity = self.capacity.max(capacity);
+    }
+
+    fn push(&mut self, message: OverlayMessage) {
+        if self.messages.len() >= self.capacity {
+            self.messages.pop_front();
+        }
+        self.messages.push_back(message);
+    }
+
+    /// Messages after the `since` one, or all of them without it. An unknown
+    /// `since` fell out of the history already, so which messages the overlay
+    /// has is unknown and none are sent rather than showing some twice.
+    fn after(&self, since: Option<&str>) -> Vec<OverlayMessage> {
+        let skip = match since {
+            Some(since) => match self.messages.iter().position(|message| message.id == since) {
+                Some(position) => position + 1,
+                None => return Vec::new(),
+            },
+            None => 0,
+        };
+
+        self.messages.iter().skip(skip).cloned().collect()
+    }
+
+    fn remove(&mut self, id: &str) -> bool {
+        let len = self.messages.len();
+        self.messages.retain(|message| message.id != id);
+        self.messages.len() != len
+    }
+
+    fn remove_by_user(&mut self, user_id: &str) -> Vec<String> {
+        let mut removed: Vec<String> = Vec::new();
+        self.messages.retain(|message| {
+            let is_removed = message.user_id == user_id;
+            if is_removed {
+                removed.push(message.id.clone());
+            }
+            !is_removed
+        });
+        removed
+    }
+
+    fn clear(&mut self) {
+        self.messages.clear();
+    }
+
+    fn is_empty(&self) -> bool {
+        self.messages.is_empty()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::VecDeque;
+    use std::sync::{Arc, Mutex};
+
+    use chrono::Utc;
+    use sqlx::PgPool;
+    use tokio::sync::mpsc;
+    use tokio::time::sleep;
+    use uuid::Uuid;
+
+    use dao::{BanWordFilterDao, ChatSettingsDao, UserDao};
+    use twitch_api::ChatSource;
+    use types::domain::{
+        ChatType, MessageFragment, OverlayEvent, OverlayMessage, SettingsChange,
+        UpdateBanWordFilter, UpdateChatSettings,
+    };
+    use types::twitch;
+
+    use crate::overlay::{
+        relay_event, OverlayHub, OverlayStream, SettingsLoader, IDLE_CHECK_INTERVAL, IDLE_TIMEOUT,
+    };
+    use crate::{MessageTokenizer, SettingsBus};
+
+    /// Chat whose events are sent by the test itself.
+    struct TestChatSource {
+        events: Mutex<Option<mpsc::Receiver<twitch::ChatEvent>>>,
+    }
+
+    fn chat_source() -> (mpsc::Sender<twitch::ChatEvent>, Arc<TestChatSource>) {
+        let (sender, events) = mpsc::channel(16);
+        let chat_source = TestChatSource {
+            events: Mutex::new(Some(events)),
+        };
+        (sender, Arc::new(chat_source))
+    }
+
+    impl ChatSource for TestChatSource {
+        fn join(&self, _channel: &str) -> mpsc::Receiver<twitch::ChatEvent> {
+            self.events.lock().unwrap().take().unwrap()
+        }
+    }
+
+    fn message(id: &str, user_id: &str) -> twitch::ChatEvent {
+        twitch::ChatEvent::Message(Box::new(twitch::ChatMessage {
+            id: id.to_string(),
+            channel: "streamer".to_string(),
+            user_id: user_id.to_string(),
+            login: format!("user{}", user_id),
+            display_name: format!("User{}", user_id),
+            color: None,
+            badges: Vec::new(),
+            text: format!("message {}", id),
+            emotes: String::new(),
+            bits: None,
+            reward_id: None,
+            first_message: false,
+            sent_at: Utc::now(),
+        }))
+    }
+
+    fn delete(message_ids: &[&str]) -> OverlayEvent {
+        OverlayEvent::Delete {
+            message_ids: message_ids.iter().map(|id| id.to_string()).collect(),
+        }
+    }
+
+    fn ids(messages: &[OverlayMessage]) -> Vec<&str> {
+        messages.iter().map(|message| message.id.as_str()).collect()
+    }
+
+    fn tokenizer() -> MessageTokenizer {
+        MessageTokenizer::new(Vec::new(), Vec::new())
+    }
+
+    #[tokio::test]
+    async fn moderation_events() {
+        let (chat, chat_source) = chat_source();
+        let hub = OverlayHub::new(chat_source);
+        let (_, mut events) = hub.subscribe("Streamer", 2, tokenizer(), None);
+
+        for event in [
+            message("1", "a"),
+            message("2", "b"),
+            message("3", "a"),
+            // "1" was pushed out of the history by "3".
+            twitch::ChatEvent::ClearMessage {
+                channel: "streamer".to_string(),
+                message_id: "1".to_string(),
+            },
+            twitch::ChatEvent::ClearMessage {
+                channel: "streamer".to_string(),
+                message_id: "2".to_string(),
+            },
+            twitch::ChatEvent::ClearChat {
+                channel: "streamer".to_string(),
+                user_id: Some("a".to_string()),
+            },
+            message("4", "b"),
+            twitch::ChatEvent::ClearChat {
+                channel: "streamer".to_string(),
+                user_id: None,
+            },
+        ] {
+            chat.send(event).await.unwrap();
+        }
+        drop(chat);
+
+        let mut received: Vec<OverlayEvent> = Vec::new();
+        while let Ok(event) = events.recv().await {
+            received.push(event);
+        }
+
+        let messages: Vec<OverlayMessage> = received
+            .iter()
+            .filter_map(|event| match event {
+                OverlayEvent::Message(message) => Some(message.as_ref().clone()),
+                _ => None,
+            })
+            .collect();
+        assert_eq!(ids(&messages), vec!["1", "2", "3", "4"]);
+        assert_eq!(
+            messages[0].fragments,
+            vec![MessageFragment::Text {
+                text: "message 1".to_string()
+            }]
+        );
+
+        let moderation: Vec<OverlayEvent> = received
+            .iter()
+            .filter(|event| !matches!(event, OverlayEvent::Message(_)))
+            .cloned()
+            .collect();
+        assert_eq!(
+            moderation,
+            vec![delete(&["2"]), delete(&["3"]), OverlayEvent::Clear]
+        );
+
+        assert!(!hub.is_joined("streamer"));
+    }
+
+    #[tokio::test]
+    async fn backfill_since() {
+        let (chat, chat_source) = chat_source();
+        let hub = OverlayHub::new(chat_source);
+        let (_, mut events) = hub.subscribe("streamer", 3, tokenizer(), None);
+
+        for id in ["1", "2", "3", "4"] {
+            chat.send(message(id, "a")).await.unwrap();
+            events.recv().await.unwrap();
+        }
+        assert_eq!(ids(&hub.recent_messages("streamer")), vec!["2", "3", "4"]);
+
+        let (missed_messages, mut reconnected) =
+            hub.subscribe("streamer", 3, tokenizer(), Some("3"));
+        assert_eq!(ids(&missed_messages), vec!["4"]);
+
+        // Too old to know what was missed, nothing is sent twice.
+        let (missed_messages, _) = hub.subscribe("streamer", 3, tokenizer(), Some("1"));
+        assert!(missed_messages.is_empty());
+
+        chat.send(message("5", "a")).await.unwrap();
+        match reconnected.recv().await.unwrap() {
+            OverlayEvent::Message(message) => assert_eq!(message.id, "5"),
+            event => panic!("unexpected event: {:?}", event),
+        }
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn quiet_channel_left_when_idle() {
+        let (_chat, chat_source) = chat_source();
+        let hub = OverlayHub::new(chat_source);
+        let (_, events) = hub.subscribe("streamer", 3, tokenizer(), None);
+        drop(events);
+
+        // The chat stays quiet, the channel is still left after the idle timeout.
+        sleep(IDLE_TIMEOUT - IDLE_CHECK_INTERVAL).await;
+        assert!(hub.is_joined("streamer"));
+        sleep(IDLE_CHECK_INTERVAL * 3).await;
+        assert!(!hub.is_joined("streamer"));
+    }
+
+    #[tokio::test]
+    async fn relayed_eventsub_events() {
+        let (chat, chat_source) = chat_source();
+        let hub = OverlayHub::new(chat_source);
+        let (_, mut events) = hub.subscribe("streamer", 3, tokenizer(), None);
+
+        for id in ["1", "2"] {
+            chat.send(message(id, "a")).await.unwrap();
+            events.recv().await.unwrap();
+        }
+
+        let chat_clear = twitch::Event::ChatClear(twitch::ChatClearEvent {
+            broadcaster_user_id: "1".to_string(),
+            broadcaster_user_login: "Streamer".to_string(),
+        });
+        relay_event(
+            &hub.channels,
+            twitch::Event::ChatMessageDelete(twitch::ChatMessageDeleteEvent {
+                broadcaster_user_id: "1".to_string(),
+                broadcaster_user_login: "streamer".to_string(),
+                target_user_id: "a".to_string(),
+                target_user_login: "usera".to_string(),
+                message_id: "1".to_string(),
+            }),
+        );
+        relay_event(&hub.channels, chat_clear.clone());
+        // The chat tells about the same clear, overlays only hear of it once.
+        relay_event(&hub.channels, chat_clear);
+        chat.send(message("3", "a")).await.unwrap();
+
+        assert_eq!(events.recv().await.unwrap(), delete(&["1"]));
+        assert_eq!(events.recv().await.unwrap(), OverlayEvent::Clear);
+        match events.recv().await.unwrap() {
+            OverlayEvent::Message(message) => assert_eq!(message.id, "3"),
+            event => panic!("unexpected event: {:?}", event),
+        }
+
+        for event in [
+            twitch::Event::ChannelPointsRedemption(twitch::ChannelPointsRedemptionEvent {
+                id: "redemption".to_string(),
+                broadcaster_user_id: "1".to_string(),
+                broadcaster_user_login: "streamer".to_string(),
+                user_id: "a".to_string(),
+                user_login: "usera".to_string(),
+                user_name: "UserA".to_string(),
+                user_input: "hi".to_string(),
+                status: "unfulfilled".to_string(),
+                reward: twitch::RedemptionReward {
+                    id: "reward".to_string(),
+                    title: "Hydrate".to_string(),
+                    cost: 100,
+                    prompt: String::new(),
+                },
+                redeemed_at: Utc::now(),
+            }),
+            twitch::Event::Follow(twitch::FollowEvent {
+                broadcaster_user_id: "1".to_string(),
+                broadcaster_user_login: "streamer".to_string(),
+                user_id: "b".to_string(),
+                user_login: "userb".to_string(),
+                user_name: "UserB".to_string(),
+                followed_at: Utc::now(),
+            }),
+            twitch::Event::Subscribe(twitch::SubscribeEvent {
+                broadcaster_user_id: "1".to_string(),
+                broadcaster_user_login: "streamer".to_string(),
+                user_id: "c".to_string(),
+                user_login: "userc".to_string(),
+                user_name: "UserC".to_string(),
+                tier: "2000".to_string(),
+                is_gift: true,
+            }),
+            twitch::Event::Cheer(twitch::CheerEvent {
+                broadcaster_user_id: "1".to_string(),
+                broadcaster_user_login: "streamer".to_string(),
+                is_anonymous: true,
+                user_id: None,
+                user_login: None,
+                user_name: None,
+                message: "Cheer100".to_string(),
+                bits: 100,
+            }),
+            // Raids of other channels don't reach this one.
+            twitch::Event::Raid(twitch::RaidEvent {
+                from_broadcaster_user_id: "1".to_string(),
+                from_broadcaster_user_login: "streamer".to_string(),
+                from_broadcaster_user_name: "Streamer".to_string(),
+                to_broadcaster_user_id: "2".to_string(),
+                to_broadcaster_user_login: "other".to_string(),
+                viewers: 1,
+            }),
+            twitch::Event::Raid(twitch::RaidEvent {
+                from_broadcaster_user_id: "2".to_string(),
+                from_broadcaster_user_login: "raider".to_string(),
+                from_broadcaster_user_name: "Raider".to_string(),
+                to_broadcaster_user_id: "1".to_string(),
+                to_broadcaster_user_login: "Streamer".to_string(),
+                viewers: 42,
+            }),
+        ] {
+            relay_event(&hub.channels, event);
+        }
+
+        assert_eq!(
+            events.recv().await.unwrap(),
+            OverlayEvent::Redemption {
+                reward_id: "reward".to_string(),
+                reward_title: "Hydrate".to_string(),
+                cost: 100,
+                user_name: "UserA".to_string(),
+                user_input: "hi".to_string(),
+            }
+        );
+        assert_eq!(
+            events.recv().await.unwrap(),
+            OverlayEvent::Follow {
+                user_name: "UserB".to_string(),
+            }
+        );
+        assert_eq!(
+            events.recv().await.unwrap(),
+            OverlayEvent::Subscription {
+                user_name: "UserC".to_string(),
+                tier: "2000".to_string(),
+                is_gift: true,
+            }
+        );
+        assert_eq!(
+            events.recv().await.unwrap(),
+            OverlayEvent::Cheer {
+                user_name: None,
+                bits: 100,
+                message: "Cheer100".to_string(),
+            }
+        );
+        assert_eq!(
+            events.recv().await.unwrap(),
+            OverlayEvent::Raid {
+                from_broadcaster_name: "Raider".to_string(),
+                viewers: 42,
+            }
+        );
+        // Alerts aren't part of the history reconnecting overlays get back.
+        assert_eq!(ids(&hub.recent_messages("streamer")), vec!["3"]);
+    }
+
+    #[sqlx::test(migrations = "../migrations")]
+    async fn stream_reloads_changed_settings(pool: PgPool) {
+        let pool = Arc::new(pool);
+        UserDao::new(pool.clone())
+            .get_or_create("1", "owner")
+            .await
+            .unwrap();
+        let chat_settings_dao = Arc::new(ChatSettingsDao::new(pool.clone()));
+        let ban_word_filter_dao = Arc::new(BanWordFilterDao::new(pool));
+        let chat_settings = chat_settings_dao
+            .create("1", "chat", &ChatType::Default)
+            .await
+            .unwrap();
+        let ban_word_filter = ban_word_filter_dao.create("1", "filter").await.unwrap();
+
+        let settings = SettingsLoader {
+            chat_settings_dao: chat_settings_dao.clone(),
+            ban_word_filter_dao: ban_word_filter_dao.clone(),
+        };
+        let settings_bus = SettingsBus::new();
+        let (chat, chat_source) = chat_source();
+        let hub = OverlayHub::new(chat_source);
+        let (_, events) = hub.subscribe("streamer", 3, tokenizer(), None);
+        let mut stream = OverlayStream {
+            missed_messages: VecDeque::new(),
+            events,
+            filter: settings.get_filter(&chat_settings).await.unwrap(),
+            chat_settings_id: chat_settings.id,
+            ban_word_filter_id: None,
+            settings_changes: Some(settings_bus.subscribe()),
+            is_reload_pending: false,
+            settings,
+        };
+
+        let mut update_chat_settings = UpdateChatSettings::from(chat_settings.clone());
+        update_chat_settings.hide.nicknames = vec!["UserB".to_string()];
+        update_chat_settings.hide.ban_word_filter_id = Some(ban_word_filter.id);
+        chat_settings_dao
+            .update(&chat_settings.id, &update_chat_settings, None)
+            .await
+            .unwrap();
+        // Changes of other settings don't reload anything.
+        settings_bus
+            .publish(SettingsChange::ChatSettings { id: Uuid::new_v4() })
+            .await;
+        settings_bus
+            .publish(SettingsChange::ChatSettings {
+                id: chat_settings.id,
+            })
+            .await;
+        match stream.next().await.unwrap() {
+            OverlayEvent::Settings { settings, css } => {
+                assert_eq!(settings.hide.nicknames, vec!["UserB".to_string()]);
+                assert!(!css.is_empty());
+            }
+            event => panic!("unexpected event: {:?}", event),
+        }
+
+        // The filter now used by the settings is followed too.
+        ban_word_filter_dao
+            .update(
+                &ban_word_filter.id,
+                &UpdateBanWordFilter {
+                    name: "filter".to_string(),
+                    ban_words: vec!["bad".to_string()],
+                },
+                None,
+            )
+            .await
+            .unwrap();
+        settings_bus
+            .publish(SettingsChange::BanWordFilter {
+                id: ban_word_filter.id,
+            })
+            .await;
+        assert!(matches!(
+            stream.next().await.unwrap(),
+            OverlayEvent::Settings { .. }
+        ));
+
+        let mut bad_message = message("2", "a");
+        if let twitch::ChatEvent::Message(message) = &mut bad_message {
+            message.text = "so bad".to_string();
+        }
+        chat.send(message("1", "b")).await.unwrap();
+        chat.send(bad_message).await.unwrap();
+        match stream.next().await.unwrap() {
+            OverlayEvent::Message(message) => {
+                assert_eq!(message.id, "2");
+                assert_ne!(
+                    message.fragments,
+                    vec![MessageFragment::Text {
+                        text: "so bad".to_string()
+                    }]
+                );
+            }
+            event => panic!("unexpected event: {:?}", event),
+        }
+    }
+}
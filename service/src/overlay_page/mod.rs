@@ -0,0 +1,187 @@
+use serde_json::{json, Value};
+
+use types::domain::{ChatSettings, OverlayMessage};
+
+use crate::ChatStyle;
+
+const SCRIPT: &str = include_str!("overlay.js");
+
+/// Self-contained page showing the chat with the settings, for an OBS browser source.
+pub(crate) fn render(chat_settings: &ChatSettings) -> String {
+    let config = json!({
+        "chatSettingsId": chat_settings.id,
+        "maxMessages": chat_settings.size.max_messages,
+    });
+
+    render_with_config(chat_settings, config)
+}
+
+/// Page showing the given messages only, without connecting to the chat.
+pub(crate) fn render_preview(chat_settings: &ChatSettings, messages: &[OverlayMessage]) -> String {
+    let config = json!({
+        "maxMessages": chat_settings.size.max_messages,
+        "messages": messages,
+    });
+
+    render_with_config(chat_settings, config)
+}
+
+fn render_with_config(chat_settings: &ChatSettings, mut config: Value) -> String {
+    config["chatType"] = json!(chat_settings.chat_type);
+    config["chatTypeOptions"] = json!(chat_settings.chat_type_options);
+    config["animation"] = json!(chat_settings.animation);
+
+    format!(
+        r#"<!DOCTYPE html>
+<html>
+<head>
+<meta charset="utf-8">
+<title>{title}</title>
+<style id="overlay-style">
+{style}
+</style>
+</head>
+<body>
+<main id="chat" class="chat chat--{chat_type}"></main>
+<script id="overlay-config" type="application/json">{config}</script>
+<script>
+{script}
+</script>
+</body>
+</html>
+"#,
+        title = escape_html(&chat_settings.name),
+        style = ChatStyle::from(chat_settings).to_css(),
+        chat_type = chat_settings.chat_type.to_str(),
+        config = config.to_string().replace("</", "<\\/"),
+        script = SCRIPT,
+    )
+}
+
+fn escape_html(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            '&' => escaped.push_str("&amp;"),
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            '"' => escaped.push_str("&quot;"),
+            '\'' => escaped.push_str("&#39;"),
+            c => escaped.push(c),
+        }
+    }
+    escaped
+}
+
+#[cfg(test)]
+mod tests {
+    use uuid::Uuid;
+
+    use types::domain::{
+        ChatAnimation, ChatAnimationSettings, ChatColorSettings, ChatFontSettings,
+        ChatHideSettings, ChatSettings, ChatSizeSettings, ChatType, ChatTypeOptions,
+        StackingDirection,
+    };
+
+    use crate::overlay_page::render;
+
+    fn chat_settings(chat_type: ChatType) -> ChatSettings {
+        ChatSettings {
+            id: Uuid::nil(),
+            name: "</title><script>alert(1)</script>".to_string(),
+            chat_type,
+            chat_type_options: ChatTypeOptions::default(),
+            color: ChatColorSettings {
+                nickname_color: 0xe9d5ffff,
+                background_color: 0x04040499,
+                text_color: 0xe6e6e6ff,
+                gradient_only_for_custom_nicknames: false,
+                custom_nicknames: Vec::new(),
+            },
+            size: ChatSizeSettings {
+                margin_top: 2.0,
+                margin_right: 2.0,
+                margin_bottom: 2.0,
+                margin_left: 2.0,
+                padding_top: 1.5,
+                padding_right: 2.0,
+                padding_bottom: 1.5,
+                padding_left: 2.0,
+                border_top_left_radius: 2.0,
+                border_top_right_radius: 2.0,
+                border_bottom_left_radius: 0.0,
+                border_bottom_right_radius: 2.0,
+                max_messages: 50,
+            },
+            hide: ChatHideSettings {
+                hide_message_pattern: String::new(),
+                hide_point_rewards: false,
+                point_rewards: Vec::new(),
+                hide_links: false,
+                link_replacement: String::new(),
+                ban_word_replacement: String::new(),
+                nicknames: Vec::new(),
+                ban_word_filter_id: None,
+                viewer_groups: Vec::new(),
+            },
+            font: ChatFontSettings {
+                font_family: "Roboto\"; } body { display: none".to_string(),
+                nickname_font_weight: 700,
+                text_font_weight: 400,
+                font_size: 3.5,
+            },
+            animation: ChatAnimationSettings {
+                message_ttl: 30,
+                enter_animation: ChatAnimation::SlideUp,
+                enter_duration: 300,
+                exit_animation: ChatAnimation::Fade,
+                exit_duration: 500,
+                stacking_direction: StackingDirection::Auto,
+            },
+            user_id: "1337".to_string(),
+            revision: 1,
+        }
+    }
+
+    #[test]
+    fn renders_settings() {
+        let page = render(&chat_settings(ChatType::Default));
+
+        assert!(page.contains(r#"<main id="chat" class="chat chat--default">"#));
+        assert!(page.contains("background-color: #04040499;"));
+        assert!(page.contains("border-radius: 2vh 2vh 2vh 0;"));
+        assert!(page.contains("font-size: 3.5vh;"));
+        assert!(page.contains("font-family: \"Roboto  body  display: none\", sans-serif;"));
+        assert!(page.contains(r#""maxMessages":50"#));
+        assert!(page.contains(r#""chatType":"default""#));
+        assert!(page.contains(r#""ticker":{"gap":4.0,"speed":10.0}"#));
+        assert!(page.contains(r#""messageTtl":30"#));
+        assert!(page.contains(r#""exitAnimation":"fade""#));
+        assert!(page.contains("animation: message-enter 300ms ease-out;"));
+        assert!(!page.contains("<script>alert(1)"));
+    }
+
+    #[test]
+    fn renders_every_chat_type() {
+        let chat_types = [
+            ChatType::Default,
+            ChatType::DefaultReverse,
+            ChatType::Block,
+            ChatType::BlockReverse,
+            ChatType::AlternativeBlock,
+            ChatType::AlternativeBlockReverse,
+            ChatType::Ticker,
+            ChatType::Bubble,
+            ChatType::Compact,
+            ChatType::NameStream,
+        ];
+
+        for chat_type in chat_types {
+            let page = render(&chat_settings(chat_type.clone()));
+            let is_reverse = chat_type.to_str().ends_with("-reverse");
+
+            assert!(page.contains(&format!("chat--{}", chat_type.to_str())));
+            assert_eq!(page.contains("flex-direction: column-reverse;"), is_reverse);
+        }
+    }
+}
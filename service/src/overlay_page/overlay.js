@@ -0,0 +1,279 @@
+(() => {
+  const { chatSettingsId, messages, ...config } = JSON.parse(
+    document.getElementById("overlay-config").textContent
+  );
+  const chat = document.getElementById("chat");
+  const style = document.getElementById("overlay-style");
+  let { maxMessages, chatType, chatTypeOptions, animation } = config;
+  const base = `/v1/chat-settings/${chatSettingsId}`;
+  const reconnectDelay = 3000;
+  let lastMessageId = null;
+  let messageCount = 0;
+
+  const fragmentNode = (fragment) => {
+    switch (fragment.type) {
+      case "emote":
+      case "thirdPartyEmote": {
+        const image = document.createElement("img");
+        image.className = "emote";
+        image.src = fragment.image;
+        image.alt = fragment.text;
+        return image;
+      }
+      case "cheermote": {
+        const cheer = document.createElement("span");
+        cheer.className = "cheer";
+        if (fragment.tier) {
+          const image = document.createElement("img");
+          image.className = "emote";
+          image.src = fragment.tier.animatedImage;
+          image.alt = fragment.prefix;
+          cheer.style.color = fragment.tier.color;
+          cheer.append(image);
+        }
+        cheer.append(String(fragment.bits));
+        return cheer;
+      }
+      case "mention":
+      case "link": {
+        const span = document.createElement("span");
+        span.className = fragment.type;
+        span.textContent = fragment.text;
+        return span;
+      }
+      default:
+        return document.createTextNode(fragment.text);
+    }
+  };
+
+  // Messages leave with the exit animation, if any, before they are removed.
+  const removeMessage = (node) => {
+    if (node.classList.contains("message--leaving")) {
+      return;
+    }
+    if (animation.exitAnimation === "none" || animation.exitDuration <= 0) {
+      node.remove();
+      return;
+    }
+    node.classList.add("message--leaving");
+    setTimeout(() => node.remove(), animation.exitDuration);
+  };
+
+  const trimMessages = () => {
+    while (chat.children.length > maxMessages) {
+      chat.firstElementChild.remove();
+    }
+  };
+
+  const addMessage = (message) => {
+    lastMessageId = message.id;
+
+    // Backfilled messages only stay for what is left of their lifetime.
+    const ttl = animation.messageTtl * 1000 - (Date.now() - Date.parse(message.sentAt));
+    if (animation.messageTtl > 0 && ttl <= 0) {
+      return;
+    }
+
+    const node = document.createElement("div");
+    node.className = "message";
+    node.dataset.id = message.id;
+    node.dataset.userId = message.userId;
+    node.dataset.login = message.login;
+    node.dataset.viewerGroups = message.viewerGroups.join(" ");
+    node.classList.toggle("message--alternate", messageCount++ % 2 === 1);
+    if (message.highlightColor !== null && message.highlightColor !== undefined) {
+      const color = message.highlightColor.toString(16).padStart(8, "0");
+      node.classList.add("message--highlighted");
+      node.style.setProperty("--highlight-color", `#${color}`);
+    }
+
+    if (message.color) {
+      node.style.setProperty("--user-color", message.color);
+    }
+
+    const name = document.createElement("span");
+    name.className = "name";
+    name.textContent = message.displayName;
+
+    const nickname = document.createElement("span");
+    nickname.className = "nickname";
+    if (message.badgeUrl) {
+      const badge = document.createElement("img");
+      badge.className = "badge";
+      badge.src = message.badgeUrl;
+      badge.alt = "";
+      nickname.append(badge);
+    }
+    nickname.append(name);
+
+    const text = document.createElement("span");
+    text.className = "text";
+    text.append(...message.fragments.map(fragmentNode));
+
+    node.append(nickname, text);
+
+    if (chatType === "name-stream" && chatTypeOptions.nameStream.uniqueNames) {
+      chat
+        .querySelectorAll(`[data-user-id="${CSS.escape(message.userId)}"]`)
+        .forEach((previous) => previous.remove());
+    }
+    if (chatType === "ticker") {
+      enterTicker(node);
+    }
+    chat.append(node);
+    trimMessages();
+
+    if (animation.messageTtl > 0) {
+      setTimeout(() => node.parentNode === chat && removeMessage(node), ttl);
+    }
+  };
+
+  const alertText = (event) => {
+    switch (event.type) {
+      case "redemption":
+        return `${event.userName} redeemed ${event.rewardTitle}`;
+      case "follow":
+        return `${event.userName} followed`;
+      case "subscription":
+        return event.isGift
+          ? `${event.userName} got a gifted subscription`
+          : `${event.userName} subscribed`;
+      case "cheer":
+        return `${event.userName ?? "Anonymous"} cheered ${event.bits} bits`;
+      case "raid":
+        return `${event.fromBroadcasterName} is raiding with ${event.viewers} viewers`;
+    }
+  };
+
+  // Alerts take a place in the chat like a message, without a nickname.
+  const addAlert = (event) => {
+    const node = document.createElement("div");
+    node.className = `message message--alert message--${event.type}`;
+
+    const text = document.createElement("span");
+    text.className = "text";
+    text.textContent = alertText(event);
+    if (event.type === "redemption" && event.userInput) {
+      text.append(`: ${event.userInput}`);
+    }
+    node.append(text);
+
+    if (chatType === "ticker") {
+      enterTicker(node);
+    }
+    chat.append(node);
+    trimMessages();
+
+    if (animation.messageTtl > 0) {
+      setTimeout(
+        () => node.parentNode === chat && removeMessage(node),
+        animation.messageTtl * 1000
+      );
+    }
+  };
+
+  // The ticker moves the whole chat left, messages start past the right edge
+  // and are dropped once they went past the left one.
+  let tickerOffset = 0;
+  let tickerAnchor = null;
+  let tickerAnchorLeft = 0;
+  let lastFrame = null;
+
+  const enterTicker = (node) => {
+    const last = chat.lastElementChild;
+    if (!last) {
+      tickerOffset = 0;
+      return;
+    }
+
+    const lastEnd = last.offsetLeft + last.offsetWidth - tickerOffset;
+    if (lastEnd < window.innerWidth) {
+      node.style.marginLeft = `${window.innerWidth - lastEnd}px`;
+    }
+  };
+
+  const tick = (time) => {
+    if (chatType === "ticker" && lastFrame !== null) {
+      const pixelsPerSecond = (chatTypeOptions.ticker.speed * window.innerHeight) / 100;
+      tickerOffset += (pixelsPerSecond * (time - lastFrame)) / 1000;
+
+      const first = chat.firstElementChild;
+      if (first && first.offsetLeft + first.offsetWidth < tickerOffset) {
+        first.remove();
+      }
+
+      // Removed messages shift the rest left, the offset follows so nothing jumps.
+      if (tickerAnchor && tickerAnchor.parentNode === chat) {
+        tickerOffset += tickerAnchor.offsetLeft - tickerAnchorLeft;
+      }
+      tickerAnchor = chat.lastElementChild;
+      tickerAnchorLeft = tickerAnchor ? tickerAnchor.offsetLeft : 0;
+      if (!tickerAnchor) {
+        tickerOffset = 0;
+      }
+
+      chat.style.transform = `translateX(${-tickerOffset}px)`;
+    }
+
+    lastFrame = time;
+    window.requestAnimationFrame(tick);
+  };
+
+  const handleEvent = (event) => {
+    switch (event.type) {
+      case "message":
+        addMessage(event);
+        break;
+      case "delete":
+        for (const id of event.messageIds) {
+          chat.querySelector(`[data-id="${CSS.escape(id)}"]`)?.remove();
+        }
+        break;
+      case "clear":
+        chat.replaceChildren();
+        break;
+      case "redemption":
+      case "follow":
+      case "subscription":
+      case "cheer":
+      case "raid":
+        addAlert(event);
+        break;
+      case "settings":
+        style.textContent = event.css;
+        chat.className = `chat chat--${event.settings.chatType}`;
+        ({ chatType, chatTypeOptions, animation } = event.settings);
+        maxMessages = event.settings.size.maxMessages;
+        if (chatType !== "ticker") {
+          chat.style.transform = "";
+        }
+        trimMessages();
+        break;
+    }
+  };
+
+  const connect = () => {
+    const url = new URL(`${base}/stream`, window.location.href);
+    url.protocol = url.protocol === "https:" ? "wss:" : "ws:";
+    if (lastMessageId) {
+      url.searchParams.set("since", lastMessageId);
+    }
+
+    const socket = new WebSocket(url);
+    socket.onmessage = (message) => handleEvent(JSON.parse(message.data));
+    socket.onclose = () => setTimeout(connect, reconnectDelay);
+  };
+
+  window.requestAnimationFrame(tick);
+
+  if (messages) {
+    messages.forEach(addMessage);
+    return;
+  }
+
+  fetch(`${base}/messages`)
+    .then((response) => (response.ok ? response.json() : []))
+    .then((messages) => messages.forEach(addMessage))
+    .catch(() => {})
+    .finally(connect);
+})();
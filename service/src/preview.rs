@@ -0,0 +1,238 @@
+use chrono::{Duration, Utc};
+
+use types::domain::{ChatSettings, OverlayMessage, SampleMessage};
+use types::twitch::ChatBadge;
+
+use crate::viewer_group::viewer_groups;
+use crate::MessageTokenizer;
+
+/// Samples showing every rule of the settings: emotes, cheers, links, banned
+/// words, hidden nicknames and patterns, rewards, custom nicknames and a
+/// moderator for the viewer group rules.
+pub(crate) fn sample_messages(
+    chat_settings: &ChatSettings,
+    ban_words: &[String],
+) -> Vec<SampleMessage> {
+    let sample = |login: &str, text: &str| SampleMessage {
+        login: login.to_lowercase(),
+        user_id: None,
+        display_name: Some(login.to_string()),
+        color: None,
+        text: text.to_string(),
+        emotes: String::new(),
+        bits: None,
+        reward_id: None,
+        badges: String::new(),
+        first_message: false,
+    };
+
+    let mut samples = vec![
+        sample("Viewer", "Hello chat! How is everyone doing today?"),
+        SampleMessage {
+            emotes: "25:0-4/305954156:27-34".to_string(),
+            color: Some("#1E90FF".to_string()),
+            ..sample("EmoteFan", "Kappa this stream is great PogChamp")
+        },
+        SampleMessage {
+            badges: "moderator/1,subscriber/12".to_string(),
+            ..sample(
+                "Moderator",
+                "Schedule is at https://example.com/schedule @Viewer",
+            )
+        },
+        SampleMessage {
+            bits: Some(100),
+            ..sample("Supporter", "Cheer100 keep it up!")
+        },
+        sample(
+            "Viewer",
+            &format!(
+                "This message has {} in it",
+                ban_words.first().map_or("a banned word", String::as_str)
+            ),
+        ),
+        sample("Viewer", "!uptime"),
+    ];
+
+    if let Some(nickname) = chat_settings.hide.nicknames.first() {
+        samples.push(sample(nickname, "Messages from this nickname are hidden"));
+    }
+
+    if let Some(custom_nickname) = chat_settings.color.custom_nicknames.first() {
+        samples.push(SampleMessage {
+            user_id: custom_nickname.user_id.clone(),
+            ..sample(
+                &custom_nickname.nickname,
+                "This nickname has a custom style",
+            )
+        });
+    }
+
+    if let Some(point_reward) = chat_settings.hide.point_rewards.first() {
+        samples.push(SampleMessage {
+            reward_id: Some(point_reward.reward_id.clone()),
+            ..sample("Redeemer", "Sent by redeeming a channel point reward")
+        });
+    }
+
+    samples
+}
+
+/// Processes samples like the messages read from chat, oldest first.
+pub(crate) fn to_overlay_messages(
+    tokenizer: &MessageTokenizer,
+    samples: Vec<SampleMessage>,
+) -> Vec<OverlayMessage> {
+    let now = Utc::now();
+    let count = samples.len() as i64;
+
+    samples
+        .into_iter()
+        .enumerate()
+        .map(|(index, sample)| {
+            let badges: Vec<ChatBadge> = sample
+                .badges
+                .split(',')
+                .filter_map(|badge| badge.split_once('/'))
+                .map(|(set, version)| ChatBadge {
+                    set: set.to_string(),
+                    version: version.to_string(),
+                })
+                .collect();
+
+            OverlayMessage {
+                id: format!("preview-{}", index + 1),
+                user_id: sample
+                    .user_id
+                    .unwrap_or_else(|| format!("preview-{}", sample.login)),
+                fragments: tokenizer.tokenize(&sample.text, &sample.emotes, sample.bits.is_some()),
+                display_name: sample.display_name.unwrap_or_else(|| sample.login.clone()),
+                viewer_groups: viewer_groups(&sample.login, &badges, sample.first_message),
+                login: sample.login,
+                color: sample.color,
+                badges,
+                reward_id: sample.reward_id,
+                highlight_color: None,
+                badge_url: None,
+                sent_at: now - Duration::seconds(count - index as i64),
+            }
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use uuid::Uuid;
+
+    use types::domain::{
+        ChatAnimation, ChatAnimationSettings, ChatColorSettings, ChatFontSettings,
+        ChatHideSettings, ChatSettings, ChatSizeSettings, ChatType, ChatTypeOptions,
+        CustomNickname, MessageFragment, PointReward, StackingDirection,
+    };
+
+    use crate::preview::{sample_messages, to_overlay_messages};
+    use crate::{MessageFilter, MessageTokenizer};
+
+    fn chat_settings() -> ChatSettings {
+        ChatSettings {
+            id: Uuid::nil(),
+            name: "preview".to_string(),
+            chat_type: ChatType::Default,
+            chat_type_options: ChatTypeOptions::default(),
+            color: ChatColorSettings {
+                nickname_color: 0xe9d5ffff,
+                background_color: 0x04040499,
+                text_color: 0xe6e6e6ff,
+                gradient_only_for_custom_nicknames: true,
+                custom_nicknames: vec![CustomNickname {
+                    nickname: "Streamer".to_string(),
+                    user_id: None,
+                    start_color: 0xff0000ff,
+                    middle_colors: Vec::new(),
+                    end_color: 0x0000ffff,
+                    display_name: None,
+                    badge_url: None,
+                    font_weight: None,
+                }],
+            },
+            size: ChatSizeSettings {
+                margin_top: 2.0,
+                margin_right: 2.0,
+                margin_bottom: 2.0,
+                margin_left: 2.0,
+                padding_top: 2.0,
+                padding_right: 2.0,
+                padding_bottom: 2.0,
+                padding_left: 2.0,
+                border_top_left_radius: 2.0,
+                border_top_right_radius: 2.0,
+                border_bottom_left_radius: 2.0,
+                border_bottom_right_radius: 2.0,
+                max_messages: 50,
+            },
+            hide: ChatHideSettings {
+                hide_message_pattern: "^!".to_string(),
+                hide_point_rewards: false,
+                point_rewards: vec![PointReward {
+                    reward_id: "reward".to_string(),
+                    hidden: true,
+                    highlight_color: None,
+                }],
+                hide_links: true,
+                link_replacement: "<link>".to_string(),
+                ban_word_replacement: "***".to_string(),
+                nicknames: vec!["Nightbot".to_string()],
+                ban_word_filter_id: None,
+                viewer_groups: Vec::new(),
+            },
+            font: ChatFontSettings {
+                font_family: String::new(),
+                nickname_font_weight: 700,
+                text_font_weight: 400,
+                font_size: 3.5,
+            },
+            animation: ChatAnimationSettings {
+                message_ttl: 0,
+                enter_animation: ChatAnimation::None,
+                enter_duration: 300,
+                exit_animation: ChatAnimation::None,
+                exit_duration: 300,
+                stacking_direction: StackingDirection::Auto,
+            },
+            user_id: "1337".to_string(),
+            revision: 1,
+        }
+    }
+
+    #[test]
+    fn samples_show_every_rule() {
+        let chat_settings = chat_settings();
+        let ban_words = vec!["darn".to_string()];
+        let filter = MessageFilter::new(&chat_settings.hide, &ban_words);
+
+        let messages = to_overlay_messages(
+            &MessageTokenizer::new(Vec::new(), Vec::new()),
+            sample_messages(&chat_settings, &ban_words),
+        );
+        let visible_texts: Vec<String> = messages
+            .iter()
+            .filter_map(|message| filter.apply(message.clone()))
+            .map(|message| {
+                message
+                    .fragments
+                    .iter()
+                    .map(MessageFragment::text)
+                    .collect::<String>()
+            })
+            .collect();
+
+        assert!(messages
+            .iter()
+            .any(|message| matches!(message.fragments[0], MessageFragment::Emote { .. })));
+        assert!(messages.iter().any(|message| message.login == "streamer"));
+        assert!(visible_texts.contains(&"Schedule is at <link> @Viewer".to_string()));
+        assert!(visible_texts.contains(&"This message has *** in it".to_string()));
+        // The pattern, the hidden nickname and the hidden reward.
+        assert_eq!(messages.len() - visible_texts.len(), 3);
+    }
+}
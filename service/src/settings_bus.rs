@@ -0,0 +1,119 @@
+use std::sync::Arc;
+use std::time::Duration;
+
+use tokio::sync::broadcast;
+use tracing::instrument;
+
+use dao::NotificationDao;
+use types::domain::SettingsChange;
+use types::error::AppResult;
+
+use crate::backoff::Backoff;
+
+const CHANGES_CAPACITY: usize = 256;
+const CHANNEL: &str = "settings_changes";
+const LISTEN_RETRY_MIN: Duration = Duration::from_secs(1);
+const LISTEN_RETRY_MAX: Duration = Duration::from_secs(60);
+
+/// Tells open overlays about saved settings. With Postgres, changes saved on
+/// any instance reach the overlays of every instance.
+pub struct SettingsBus {
+    changes: broadcast::Sender<SettingsChange>,
+    notification_dao: Option<Arc<NotificationDao>>,
+}
+
+impl SettingsBus {
+    pub fn new() -> Self {
+        let (changes, _) = broadcast::channel(CHANGES_CAPACITY);
+
+        SettingsBus {
+            changes,
+            notification_dao: None,
+        }
+    }
+
+    pub async fn with_postgres(notification_dao: Arc<NotificationDao>) -> AppResult<Self> {
+        let (changes, _) = broadcast::channel(CHANGES_CAPACITY);
+        let mut listener = notification_dao.listen(CHANNEL).await?;
+
+        let local_changes = changes.clone();
+        tokio::spawn(async move {
+            let mut backoff = Backoff::new(LISTEN_RETRY_MIN, LISTEN_RETRY_MAX);
+            loop {
+                let notification = match listener.recv().await {
+                    Ok(notification) => {
+                        backoff.reset();
+                        notification
+                    }
+                    // The listener failed to reconnect, the database may be down.
+                    Err(e) => {
+                        tracing::warn!({ error = %e }, "fail receive settings change");
+                        backoff.wait().await;
+                        continue;
+                    }
+                };
+
+                match serde_json::from_str::<SettingsChange>(notification.payload()) {
+                    Ok(change) => {
+                        let _ = local_changes.send(change);
+                    }
+                    Err(e) => tracing::warn!({ error = %e }, "invalid settings change"),
+                }
+            }
+        });
+
+        Ok(SettingsBus {
+            changes,
+            notification_dao: Some(notification_dao),
+        })
+    }
+
+    pub fn subscribe(&self) -> broadcast::Receiver<SettingsChange> {
+        self.changes.subscribe()
+    }
+
+    /// Never fails, the change is already saved and overlays get it on reload anyway.
+    #[instrument(skip(self))]
+    pub async fn publish(&self, change: SettingsChange) {
+        let Some(notification_dao) = &self.notification_dao else {
+            // No receivers is fine, no overlay is open right now.
+            let _ = self.changes.send(change);
+            return;
+        };
+
+        // The listener of this instance delivers it locally too.
+        let payload = serde_json::to_string(&change).unwrap();
+        if let Err(e) = notification_dao.notify(CHANNEL, &payload).await {
+            tracing::warn!({ error = ?e }, "fail publish settings change");
+            let _ = self.changes.send(change);
+        }
+    }
+}
+
+impl Default for SettingsBus {
+    fn default() -> Self {
+        SettingsBus::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use uuid::Uuid;
+
+    use types::domain::SettingsChange;
+
+    use crate::SettingsBus;
+
+    #[tokio::test]
+    async fn publish_reaches_subscribers() {
+        let settings_bus = SettingsBus::new();
+        let mut first = settings_bus.subscribe();
+        let mut second = settings_bus.subscribe();
+
+        let change = SettingsChange::ChatSettings { id: Uuid::nil() };
+        settings_bus.publish(change).await;
+
+        assert_eq!(first.recv().await.unwrap(), change);
+        assert_eq!(second.recv().await.unwrap(), change);
+    }
+}
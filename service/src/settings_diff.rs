@@ -0,0 +1,274 @@
+use serde_json::{Map, Value};
+
+use types::domain::{ChatSettingsDiff, ChatSettingsFieldChange, ChatSettingsVersion};
+
+/// Fields identifying an entry of a list, so entries are compared with the
+/// entry of the other version instead of the one at the same position.
+const ENTRY_KEYS: [&str; 3] = ["nickname", "rewardId", "group"];
+
+pub(crate) fn diff_versions(
+    from: &ChatSettingsVersion,
+    to: &ChatSettingsVersion,
+) -> ChatSettingsDiff {
+    let mut changes = Vec::new();
+
+    diff_values(
+        "",
+        &serde_json::to_value(&from.settings).unwrap_or(Value::Null),
+        &serde_json::to_value(&to.settings).unwrap_or(Value::Null),
+        &mut changes,
+    );
+
+    ChatSettingsDiff {
+        from_version: from.version,
+        to_version: to.version,
+        changes,
+    }
+}
+
+fn diff_values(path: &str, from: &Value, to: &Value, changes: &mut Vec<ChatSettingsFieldChange>) {
+    if from == to {
+        return;
+    }
+
+    match (from, to) {
+        (Value::Object(from), Value::Object(to)) => {
+            let mut keys: Vec<&String> = from.keys().chain(to.keys()).collect();
+            keys.sort();
+            keys.dedup();
+
+            for key in keys {
+                diff_values(
+                    &join(path, key),
+                    from.get(key).unwrap_or(&Value::Null),
+                    to.get(key).unwrap_or(&Value::Null),
+                    changes,
+                );
+            }
+        }
+        (Value::Array(from), Value::Array(to)) => match (entries(from), entries(to)) {
+            (Some(from), Some(to)) => {
+                let mut keys: Vec<&String> = from.iter().chain(to.iter()).map(|e| &e.0).collect();
+                keys.sort();
+                keys.dedup();
+
+                for key in keys {
+                    diff_values(
+                        &format!("{}[{}]", path, key),
+                        find_entry(&from, key),
+                        find_entry(&to, key),
+                        changes,
+                    );
+                }
+            }
+            _ => changes.push(list_change(path, from, to)),
+        },
+        _ => changes.push(ChatSettingsFieldChange {
+            path: path.to_string(),
+            from: from.clone(),
+            to: to.clone(),
+        }),
+    }
+}
+
+/// Entries of a list of strings or of objects with one of [`ENTRY_KEYS`],
+/// `None` for other lists, which are compared as a whole.
+fn entries(values: &[Value]) -> Option<Vec<(String, &Value)>> {
+    values
+        .iter()
+        .map(|value| match value {
+            Value::String(key) => Some((key.clone(), value)),
+            Value::Object(object) => entry_key(object).map(|key| (key, value)),
+            _ => None,
+        })
+        .collect()
+}
+
+fn entry_key(object: &Map<String, Value>) -> Option<String> {
+    ENTRY_KEYS
+        .iter()
+        .find_map(|key| object.get(*key).and_then(Value::as_str))
+        .map(str::to_string)
+}
+
+fn find_entry<'a>(entries: &[(String, &'a Value)], key: &str) -> &'a Value {
+    entries
+        .iter()
+        .find(|entry| entry.0 == key)
+        .map_or(&Value::Null, |entry| entry.1)
+}
+
+fn list_change(path: &str, from: &[Value], to: &[Value]) -> ChatSettingsFieldChange {
+    ChatSettingsFieldChange {
+        path: path.to_string(),
+        from: Value::Array(from.to_vec()),
+        to: Value::Array(to.to_vec()),
+    }
+}
+
+fn join(path: &str, key: &str) -> String {
+    if path.is_empty() {
+        key.to_string()
+    } else {
+        format!("{}.{}", path, key)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::NaiveDateTime;
+    use serde_json::{json, Value};
+    use uuid::Uuid;
+
+    use types::domain::{
+        ChatAnimation, ChatAnimationSettings, ChatColorSettings, ChatFontSettings,
+        ChatHideSettings, ChatSettings, ChatSettingsVersion, ChatSizeSettings, ChatType,
+        ChatTypeOptions, CustomNickname, StackingDirection,
+    };
+
+    use crate::settings_diff::diff_versions;
+
+    fn version(version: i32, settings: ChatSettings) -> ChatSettingsVersion {
+        ChatSettingsVersion {
+            chat_settings_id: Uuid::nil(),
+            version,
+            settings,
+            created_at: NaiveDateTime::default(),
+        }
+    }
+
+    fn chat_settings() -> ChatSettings {
+        ChatSettings {
+            id: Uuid::nil(),
+            name: "chat".to_string(),
+            chat_type: ChatType::Default,
+            chat_type_options: ChatTypeOptions::default(),
+            color: ChatColorSettings {
+                nickname_color: 0xe9d5ffff,
+                background_color: 0x04040499,
+                text_color: 0xe6e6e6ff,
+                gradient_only_for_custom_nicknames: false,
+                custom_nicknames: vec![CustomNickname {
+                    nickname: "Streamer".to_string(),
+                    user_id: None,
+                    start_color: 0xff0000ff,
+                    middle_colors: Vec::new(),
+                    end_color: 0x0000ffff,
+                    display_name: None,
+                    badge_url: None,
+                    font_weight: None,
+                }],
+            },
+            size: ChatSizeSettings {
+                margin_top: 2.0,
+                margin_right: 2.0,
+                margin_bottom: 2.0,
+                margin_left: 2.0,
+                padding_top: 2.0,
+                padding_right: 2.0,
+                padding_bottom: 2.0,
+                padding_left: 2.0,
+                border_top_left_radius: 2.0,
+                border_top_right_radius: 2.0,
+                border_bottom_left_radius: 2.0,
+                border_bottom_right_radius: 2.0,
+                max_messages: 50,
+            },
+            hide: ChatHideSettings {
+                hide_message_pattern: "!".to_string(),
+                hide_point_rewards: false,
+                point_rewards: Vec::new(),
+                hide_links: true,
+                link_replacement: String::new(),
+                ban_word_replacement: "***".to_string(),
+                nicknames: vec!["Nightbot".to_string()],
+                ban_word_filter_id: None,
+                viewer_groups: Vec::new(),
+            },
+            font: ChatFontSettings {
+                font_family: String::new(),
+                nickname_font_weight: 700,
+                text_font_weight: 400,
+                font_size: 3.5,
+            },
+            animation: ChatAnimationSettings {
+                message_ttl: 0,
+                enter_animation: ChatAnimation::None,
+                enter_duration: 300,
+                exit_animation: ChatAnimation::None,
+                exit_duration: 300,
+                stacking_direction: StackingDirection::Auto,
+            },
+            user_id: "1337".to_string(),
+            revision: 1,
+        }
+    }
+
+    #[test]
+    fn same_versions() {
+        let diff = diff_versions(&version(1, chat_settings()), &version(2, chat_settings()));
+
+        assert_eq!(diff.from_version, 1);
+        assert_eq!(diff.to_version, 2);
+        assert!(diff.changes.is_empty());
+    }
+
+    #[test]
+    fn changed_fields_and_entries() {
+        let mut changed = chat_settings();
+        changed.size.max_messages = 20;
+        changed.animation.enter_animation = ChatAnimation::Fade;
+        changed.color.custom_nicknames[0].end_color = 0x00ff00ff;
+        changed.color.custom_nicknames.push(CustomNickname {
+            nickname: "Moderator".to_string(),
+            user_id: None,
+            start_color: 0xffffffff,
+            middle_colors: Vec::new(),
+            end_color: 0xffffffff,
+            display_name: None,
+            badge_url: None,
+            font_weight: None,
+        });
+        changed.hide.nicknames = vec!["StreamElements".to_string()];
+
+        let diff = diff_versions(&version(1, chat_settings()), &version(2, changed));
+        let changes: Vec<(&str, &Value, &Value)> = diff
+            .changes
+            .iter()
+            .map(|change| (change.path.as_str(), &change.from, &change.to))
+            .collect();
+
+        assert_eq!(
+            changes,
+            vec![
+                ("animation.enterAnimation", &json!("none"), &json!("fade")),
+                (
+                    "color.customNicknames[Moderator]",
+                    &Value::Null,
+                    &json!({
+                        "nickname": "Moderator",
+                        "userId": null,
+                        "startColor": 0xffffffffu32,
+                        "middleColors": [],
+                        "endColor": 0xffffffffu32,
+                        "displayName": null,
+                        "badgeUrl": null,
+                        "fontWeight": null,
+                    })
+                ),
+                (
+                    "color.customNicknames[Streamer].endColor",
+                    &json!(0x0000ffffu32),
+                    &json!(0x00ff00ffu32)
+                ),
+                ("hide.nicknames[Nightbot]", &json!("Nightbot"), &Value::Null),
+                (
+                    "hide.nicknames[StreamElements]",
+                    &Value::Null,
+                    &json!("StreamElements")
+                ),
+                ("size.maxMessages", &json!(50), &json!(20)),
+            ]
+        );
+    }
+}
@@ -0,0 +1,205 @@
+use chrono::Utc;
+use serde_json::{Map, Value};
+use validator::Validate;
+
+use types::domain::{
+    BanWordFilter, ChatSettings, ChatSettingsExport, ChatTypeOptions, UpdateBanWordFilter,
+    UpdateChatAnimationSettings,
+};
+use types::error::{AppResult, ValidationErrorsWrapper};
+
+use crate::ChatService;
+
+/// Upgrades of the document, the first one from schema version 1 to 2.
+const UPGRADES: [fn(&mut Map<String, Value>); 1] = [upgrade_v1];
+
+pub(crate) fn export(
+    chat_settings: ChatSettings,
+    ban_word_filter: Option<BanWordFilter>,
+) -> ChatSettingsExport {
+    ChatSettingsExport {
+        format: ChatSettingsExport::FORMAT.to_string(),
+        schema_version: ChatSettingsExport::SCHEMA_VERSION,
+        exported_at: Utc::now().naive_utc(),
+        settings: chat_settings.into(),
+        ban_word_filter: ban_word_filter.map(|filter| UpdateBanWordFilter {
+            name: filter.name,
+            ban_words: filter.ban_words,
+        }),
+    }
+}
+
+/// Reads a document of any known schema version, validated like the settings
+/// and filters sent by the editor.
+pub(crate) fn parse_export(document: Value) -> AppResult<ChatSettingsExport> {
+    let Value::Object(mut document) = document else {
+        return Err(ChatService::UNKNOWN_EXPORT_FORMAT_ERROR);
+    };
+
+    if document.get("format").and_then(Value::as_str) != Some(ChatSettingsExport::FORMAT) {
+        return Err(ChatService::UNKNOWN_EXPORT_FORMAT_ERROR);
+    }
+
+    let schema_version = document
+        .get("schemaVersion")
+        .and_then(Value::as_u64)
+        .unwrap_or(0) as u32;
+    if !(1..=ChatSettingsExport::SCHEMA_VERSION).contains(&schema_version) {
+        return Err(ChatService::UNSUPPORTED_EXPORT_VERSION_ERROR
+            .clone()
+            .other("schemaVersion".to_string(), schema_version.into()));
+    }
+
+    for upgrade in &UPGRADES[(schema_version as usize - 1)..] {
+        upgrade(&mut document);
+    }
+    document.insert(
+        "schemaVersion".to_string(),
+        ChatSettingsExport::SCHEMA_VERSION.into(),
+    );
+
+    let export: ChatSettingsExport =
+        serde_json::from_value(Value::Object(document)).map_err(|e| {
+            ChatService::INVALID_EXPORT_ERROR
+                .clone()
+                .other("reason".to_string(), e.to_string().into())
+        })?;
+
+    export.validate().map_err(ValidationErrorsWrapper::from)?;
+
+    Ok(export)
+}
+
+/// Chat type options and animations were added, documents without them get
+/// the defaults the settings had before.
+fn upgrade_v1(document: &mut Map<String, Value>) {
+    let Some(Value::Object(settings)) = document.get_mut("settings") else {
+        return;
+    };
+
+    settings
+        .entry("chatTypeOptions")
+        .or_insert_with(|| serde_json::to_value(ChatTypeOptions::default()).unwrap_or_default());
+    settings.entry("animation").or_insert_with(|| {
+        serde_json::to_value(UpdateChatAnimationSettings::default()).unwrap_or_default()
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use axum::http::StatusCode;
+    use serde_json::{json, Value};
+
+    use types::domain::{ChatAnimation, ChatSettingsExport, ChatType};
+
+    use crate::settings_export::parse_export;
+
+    fn document(schema_version: u32) -> Value {
+        json!({
+            "format": "yggdrasil/chat-settings",
+            "schemaVersion": schema_version,
+            "exportedAt": "2023-10-30T12:00:00",
+            "settings": {
+                "name": "gaming",
+                "chatType": "block",
+                "color": {
+                    "nicknameColor": 0xe9d5ffffu32,
+                    "backgroundColor": 0x04040499,
+                    "textColor": 0xe6e6e6ffu32,
+                    "gradientOnlyForCustomNicknames": false,
+                    "customNicknames": [],
+                },
+                "size": {
+                    "marginTop": 2.0,
+                    "marginRight": 2.0,
+                    "marginBottom": 2.0,
+                    "marginLeft": 2.0,
+                    "paddingTop": 2.0,
+                    "paddingRight": 2.0,
+                    "paddingBottom": 2.0,
+                    "paddingLeft": 2.0,
+                    "borderTopLeftRadius": 2.0,
+                    "borderTopRightRadius": 2.0,
+                    "borderBottomLeftRadius": 2.0,
+                    "borderBottomRightRadius": 2.0,
+                    "maxMessages": 50,
+                },
+                "hide": {
+                    "hideMessagePattern": "!",
+                    "hidePointRewards": false,
+                    "pointRewards": [],
+                    "hideLinks": true,
+                    "linkReplacement": "<link>",
+                    "banWordReplacement": "***",
+                    "nicknames": ["Nightbot"],
+                    "banWordFilterId": null,
+                },
+                "font": {
+                    "fontFamily": "Roboto",
+                    "nicknameFontWeight": 700,
+                    "textFontWeight": 400,
+                    "fontSize": 3.5,
+                },
+            },
+            "banWordFilter": {
+                "name": "swears",
+                "banWords": ["darn"],
+            },
+        })
+    }
+
+    #[test]
+    fn upgrades_old_documents() {
+        let export = parse_export(document(1)).unwrap();
+
+        assert_eq!(export.schema_version, ChatSettingsExport::SCHEMA_VERSION);
+        assert_eq!(export.settings.chat_type, ChatType::Block);
+        assert_eq!(
+            export.settings.animation.enter_animation,
+            ChatAnimation::None
+        );
+        assert_eq!(export.settings.animation.enter_duration, 300);
+        assert_eq!(export.settings.chat_type_options.ticker.speed, 10.0);
+        assert_eq!(export.ban_word_filter.unwrap().ban_words, vec!["darn"]);
+    }
+
+    #[test]
+    fn rejects_unknown_documents() {
+        let mut document = document(2);
+        document["format"] = json!("something-else");
+        let error = parse_export(document).unwrap_err();
+        assert_eq!(error.message, Some("unknown chat settings export format"));
+
+        let error = parse_export(self::document(3)).unwrap_err();
+        assert_eq!(error.status_code, StatusCode::BAD_REQUEST);
+        assert_eq!(
+            error.message,
+            Some("unsupported chat settings export version")
+        );
+
+        let mut document = self::document(2);
+        document["settings"]
+            .as_object_mut()
+            .unwrap()
+            .remove("color");
+        let error = parse_export(document).unwrap_err();
+        assert!(error.other.unwrap()["reason"]
+            .as_str()
+            .unwrap()
+            .contains("missing field `color`"));
+    }
+
+    #[test]
+    fn validates_fields() {
+        let mut document = document(2);
+        document["settings"]["size"]["maxMessages"] = json!(1000);
+        document["banWordFilter"]["banWords"] = json!([""]);
+
+        let error = parse_export(document).unwrap_err();
+        let other = error.other.unwrap();
+
+        assert_eq!(error.status_code, StatusCode::BAD_REQUEST);
+        assert!(other.contains_key("settings.size.max_messages"));
+        assert!(other.contains_key("ban_word_filter.ban_words"));
+    }
+}
@@ -0,0 +1,164 @@
+use serde_json::{Map, Value};
+use validator::Validate;
+
+use types::domain::UpdateChatSettings;
+use types::error::{AppResult, ValidationErrorsWrapper};
+
+use crate::ChatService;
+
+/// Applies a JSON Merge Patch (RFC 7386) to the settings: objects are merged,
+/// `null` removes a field and anything else, lists included, replaces it.
+/// The result is validated like a full update.
+pub(crate) fn apply_patch(
+    settings: &UpdateChatSettings,
+    patch: &Value,
+) -> AppResult<UpdateChatSettings> {
+    if !patch.is_object() {
+        return Err(ChatService::INVALID_PATCH_ERROR
+            .clone()
+            .other("reason".to_string(), "patch must be an object".into()));
+    }
+
+    let mut document = serde_json::to_value(settings).unwrap_or(Value::Null);
+    merge_patch(&mut document, patch);
+
+    let patched: UpdateChatSettings = serde_json::from_value(document).map_err(|e| {
+        ChatService::INVALID_PATCH_ERROR
+            .clone()
+            .other("reason".to_string(), e.to_string().into())
+    })?;
+
+    patched.validate().map_err(ValidationErrorsWrapper::from)?;
+
+    Ok(patched)
+}
+
+fn merge_patch(target: &mut Value, patch: &Value) {
+    let Value::Object(patch) = patch else {
+        *target = patch.clone();
+        return;
+    };
+
+    if !target.is_object() {
+        *target = Value::Object(Map::new());
+    }
+    let Value::Object(target) = target else {
+        return;
+    };
+
+    for (key, value) in patch {
+        if value.is_null() {
+            target.remove(key);
+        } else {
+            merge_patch(target.entry(key.clone()).or_insert(Value::Null), value);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use axum::http::StatusCode;
+    use serde_json::json;
+    use uuid::Uuid;
+
+    use types::domain::{
+        ChatAnimation, ChatType, ChatTypeOptions, UpdateChatAnimationSettings,
+        UpdateChatColorSettings, UpdateChatFontSettings, UpdateChatHideSettings,
+        UpdateChatSettings, UpdateChatSizeSettings, UpdateCustomNickname,
+    };
+
+    use crate::settings_patch::apply_patch;
+
+    fn settings() -> UpdateChatSettings {
+        UpdateChatSettings {
+            name: "chat".to_string(),
+            chat_type: ChatType::Default,
+            chat_type_options: ChatTypeOptions::default(),
+            color: UpdateChatColorSettings {
+                nickname_color: 0xe9d5ffff,
+                background_color: 0x04040499,
+                text_color: 0xe6e6e6ff,
+                gradient_only_for_custom_nicknames: false,
+                custom_nicknames: vec![UpdateCustomNickname {
+                    nickname: "Streamer".to_string(),
+                    user_id: None,
+                    start_color: 0xff0000ff,
+                    middle_colors: Vec::new(),
+                    end_color: 0x0000ffff,
+                    display_name: None,
+                    badge_url: None,
+                    font_weight: None,
+                }],
+            },
+            size: UpdateChatSizeSettings {
+                margin_top: 2.0,
+                margin_right: 2.0,
+                margin_bottom: 2.0,
+                margin_left: 2.0,
+                padding_top: 2.0,
+                padding_right: 2.0,
+                padding_bottom: 2.0,
+                padding_left: 2.0,
+                border_top_left_radius: 2.0,
+                border_top_right_radius: 2.0,
+                border_bottom_left_radius: 2.0,
+                border_bottom_right_radius: 2.0,
+                max_messages: 50,
+            },
+            hide: UpdateChatHideSettings {
+                hide_message_pattern: "!".to_string(),
+                hide_point_rewards: false,
+                point_rewards: Vec::new(),
+                hide_links: true,
+                link_replacement: String::new(),
+                ban_word_replacement: "***".to_string(),
+                nicknames: vec!["Nightbot".to_string()],
+                ban_word_filter_id: Some(Uuid::nil()),
+                viewer_groups: Vec::new(),
+            },
+            font: UpdateChatFontSettings {
+                font_family: String::new(),
+                nickname_font_weight: 700,
+                text_font_weight: 400,
+                font_size: 3.5,
+            },
+            animation: UpdateChatAnimationSettings::default(),
+        }
+    }
+
+    #[test]
+    fn merges_fields() {
+        let patched = apply_patch(
+            &settings(),
+            &json!({
+                "size": { "maxMessages": 20 },
+                "hide": { "nicknames": ["StreamElements"], "banWordFilterId": null },
+                "animation": { "enterAnimation": "fade" },
+            }),
+        )
+        .unwrap();
+
+        let mut expected = settings();
+        expected.size.max_messages = 20;
+        expected.hide.nicknames = vec!["StreamElements".to_string()];
+        expected.hide.ban_word_filter_id = None;
+        expected.animation.enter_animation = ChatAnimation::Fade;
+
+        assert_eq!(patched, expected);
+        assert_eq!(apply_patch(&settings(), &json!({})).unwrap(), settings());
+    }
+
+    #[test]
+    fn rejects_invalid_results() {
+        let error =
+            apply_patch(&settings(), &json!({ "size": { "maxMessages": 1000 } })).unwrap_err();
+        assert_eq!(error.status_code, StatusCode::BAD_REQUEST);
+        assert!(error.other.unwrap().contains_key("size.max_messages"));
+
+        let error = apply_patch(&settings(), &json!({ "color": null })).unwrap_err();
+        assert_eq!(error.message, Some("invalid chat settings patch"));
+
+        let error = apply_patch(&settings(), &json!([])).unwrap_err();
+        assert_eq!(error.message, Some("invalid chat settings patch"));
+    }
+}
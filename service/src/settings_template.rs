@@ -0,0 +1,144 @@
+use serde_json::{json, Value};
+
+use types::domain::{
+    ChatSettingsExport, ChatSettingsTemplate, ChatSettingsTemplateInfo,
+    ChatSettingsTemplatePreview, UpdateChatSettings,
+};
+use types::error::AppResult;
+
+use crate::settings_export::parse_export;
+
+/// Settings of the template in the current schema version. Templates are
+/// read like exports, so they are upgraded and validated the same way.
+pub(crate) fn template_settings(template: &ChatSettingsTemplate) -> AppResult<UpdateChatSettings> {
+    let document = json!({
+        "format": ChatSettingsExport::FORMAT,
+        "schemaVersion": template.schema_version,
+        "exportedAt": template.created_at,
+        "settings": template.settings,
+    });
+
+    Ok(parse_export(document)?.settings)
+}
+
+/// Template with its settings upgraded to the current schema version.
+pub(crate) fn upgrade_template(template: ChatSettingsTemplate) -> AppResult<ChatSettingsTemplate> {
+    let settings = template_settings(&template)?;
+
+    Ok(ChatSettingsTemplate {
+        schema_version: ChatSettingsExport::SCHEMA_VERSION,
+        settings: serde_json::to_value(settings).unwrap_or(Value::Null),
+        ..template
+    })
+}
+
+pub(crate) fn template_info(
+    template: ChatSettingsTemplate,
+    settings: &UpdateChatSettings,
+) -> ChatSettingsTemplateInfo {
+    ChatSettingsTemplateInfo {
+        id: template.id,
+        name: template.name,
+        description: template.description,
+        user_id: template.user_id,
+        preview: ChatSettingsTemplatePreview {
+            chat_type: settings.chat_type.clone(),
+            nickname_color: settings.color.nickname_color,
+            background_color: settings.color.background_color,
+            text_color: settings.color.text_color,
+            font_family: settings.font.font_family.clone(),
+            font_size: settings.font.font_size,
+        },
+        created_at: template.created_at,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::NaiveDateTime;
+    use serde_json::json;
+    use uuid::Uuid;
+
+    use types::domain::{ChatAnimation, ChatSettingsExport, ChatSettingsTemplate, ChatType};
+
+    use crate::settings_template::{template_info, template_settings, upgrade_template};
+
+    fn template(schema_version: u32) -> ChatSettingsTemplate {
+        ChatSettingsTemplate {
+            id: Uuid::nil(),
+            name: "Neon".to_string(),
+            description: "Bright nicknames on black".to_string(),
+            user_id: None,
+            schema_version,
+            settings: json!({
+                "name": "Neon",
+                "chatType": "alternative-block",
+                "color": {
+                    "nicknameColor": 0x39ff14ffu32,
+                    "backgroundColor": 0x000000ff,
+                    "textColor": 0xffffffffu32,
+                    "gradientOnlyForCustomNicknames": false,
+                    "customNicknames": [],
+                },
+                "size": {
+                    "marginTop": 1.0,
+                    "marginRight": 1.0,
+                    "marginBottom": 1.0,
+                    "marginLeft": 1.0,
+                    "paddingTop": 2.0,
+                    "paddingRight": 2.0,
+                    "paddingBottom": 2.0,
+                    "paddingLeft": 2.0,
+                    "borderTopLeftRadius": 0.0,
+                    "borderTopRightRadius": 0.0,
+                    "borderBottomLeftRadius": 0.0,
+                    "borderBottomRightRadius": 0.0,
+                    "maxMessages": 30,
+                },
+                "hide": {
+                    "hideMessagePattern": "!",
+                    "hidePointRewards": false,
+                    "pointRewards": [],
+                    "hideLinks": true,
+                    "linkReplacement": "<link>",
+                    "banWordReplacement": "***",
+                    "nicknames": [],
+                    "banWordFilterId": null,
+                },
+                "font": {
+                    "fontFamily": "Orbitron",
+                    "nicknameFontWeight": 700,
+                    "textFontWeight": 400,
+                    "fontSize": 4.0,
+                },
+            }),
+            created_at: NaiveDateTime::default(),
+        }
+    }
+
+    #[test]
+    fn upgrades_templates() {
+        let settings = template_settings(&template(1)).unwrap();
+        assert_eq!(settings.animation.enter_animation, ChatAnimation::None);
+
+        let upgraded = upgrade_template(template(1)).unwrap();
+        assert_eq!(upgraded.schema_version, ChatSettingsExport::SCHEMA_VERSION);
+        assert_eq!(upgraded.settings["animation"]["enterDuration"], json!(300));
+
+        let info = template_info(template(1), &settings);
+        assert_eq!(info.name, "Neon");
+        assert_eq!(info.preview.chat_type, ChatType::AlternativeBlock);
+        assert_eq!(info.preview.nickname_color, 0x39ff14ff);
+        assert_eq!(info.preview.font_family, "Orbitron");
+        assert_eq!(info.preview.font_size, 4.0);
+    }
+
+    #[test]
+    fn rejects_invalid_templates() {
+        let mut template = template(2);
+        template.settings["size"]["maxMessages"] = json!(1000);
+        assert!(template_settings(&template).is_err());
+
+        assert!(template_settings(&self::template(3)).is_err());
+    }
+}
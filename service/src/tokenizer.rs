@@ -0,0 +1,418 @@
+use std::collections::HashMap;
+use std::iter;
+
+use types::domain::{MessageFragment, ThirdPartyEmote};
+use types::twitch;
+
+const EMOTE_IMAGE_TEMPLATE: &str =
+    "https://static-cdn.jtvnw.net/emoticons/v2/{{id}}/default/dark/3.0";
+const TRAILING_PUNCTUATION: &[char] = &['.', ',', '!', '?', ':', ';', ')', '"', '\''];
+
+/// Turns raw chat messages into fragments, so overlays don't have to parse
+/// emote offsets, mentions and links themselves.
+pub struct MessageTokenizer {
+    third_party_emotes: HashMap<String, ThirdPartyEmote>,
+    cheermotes: Vec<twitch::Cheermote>,
+}
+
+impl MessageTokenizer {
+    pub fn new(
+        third_party_emotes: Vec<ThirdPartyEmote>,
+        cheermotes: Vec<twitch::Cheermote>,
+    ) -> Self {
+        MessageTokenizer {
+            third_party_emotes: third_party_emotes
+                .into_iter()
+                .map(|emote| (emote.name.clone(), emote))
+                .collect(),
+            cheermotes,
+        }
+    }
+
+    /// `emotes_tag` is the IRC `emotes` tag, e.g. `25:0-4,12-16/1902:6-10`.
+    /// Cheermotes are only matched in messages that carry bits, like Twitch does.
+    pub fn tokenize(&self, text: &str, emotes_tag: &str, has_bits: bool) -> Vec<MessageFragment> {
+        let mut fragments = FragmentsBuilder::default();
+        let mut position = 0;
+
+        for emote in parse_emotes_tag(emotes_tag, text) {
+            self.tokenize_words(&text[position..emote.start], has_bits, &mut fragments);
+            fragments.push(MessageFragment::Emote {
+                text: text[emote.start..emote.end].to_string(),
+                id: emote.id.to_string(),
+                image: EMOTE_IMAGE_TEMPLATE.replace("{{id}}", emote.id),
+            });
+            position = emote.end;
+        }
+        self.tokenize_words(&text[position..], has_bits, &mut fragments);
+
+        fragments.build()
+    }
+
+    fn tokenize_words(&self, text: &str, has_bits: bool, fragments: &mut FragmentsBuilder) {
+        for word in text.split_inclusive(char::is_whitespace) {
+            let token = word.trim_end_matches(char::is_whitespace);
+
+            match self.tokenize_word(token, has_bits) {
+                Some(fragment) => {
+                    let rest = &word[fragment.text().len()..];
+                    fragments.push(fragment);
+                    fragments.push_text(rest);
+                }
+                None => fragments.push_text(word),
+            }
+        }
+    }
+
+    /// The returned fragment may cover only the beginning of `token`,
+    /// e.g. `@streamer,` is a mention followed by a comma.
+    fn tokenize_word(&self, token: &str, has_bits: bool) -> Option<MessageFragment> {
+        if token.is_empty() {
+            return None;
+        }
+
+        if has_bits {
+            if let Some(fragment) = self.parse_cheer(token) {
+                return Some(fragment);
+            }
+        }
+
+        if let Some(emote) = self.third_party_emotes.get(token) {
+            return Some(MessageFragment::ThirdPartyEmote {
+                text: token.to_string(),
+                provider: emote.provider.clone(),
+                id: emote.id.clone(),
+                image: emote.image.clone(),
+            });
+        }
+
+        parse_mention(token).or_else(|| parse_link(token))
+    }
+
+    fn parse_cheer(&self, token: &str) -> Option<MessageFragment> {
+        let digits_start = token.trim_end_matches(|c: char| c.is_ascii_digit()).len();
+        let (prefix, digits) = token.split_at(digits_start);
+        if prefix.is_empty() || digits.is_empty() {
+            return None;
+        }
+
+        let bits = digits.parse::<i64>().ok()?;
+        let cheermote = self
+            .cheermotes
+            .iter()
+            .find(|cheermote| cheermote.prefix.eq_ignore_ascii_case(prefix))?;
+        let tier = cheermote.tier(bits)?;
+
+        Some(MessageFragment::Cheermote {
+            text: token.to_string(),
+            prefix: cheermote.prefix.clone(),
+            bits,
+            tier: tier.clone(),
+        })
+    }
+}
+
+fn parse_mention(token: &str) -> Option<MessageFragment> {
+    let mention = token.trim_end_matches(TRAILING_PUNCTUATION);
+    let login = mention.strip_prefix('@')?;
+    if login.is_empty() || !login.chars().all(|c| c.is_ascii_alphanumeric() || c == '_') {
+        return None;
+    }
+
+    Some(MessageFragment::Mention {
+        text: mention.to_string(),
+        login: login.to_ascii_lowercase(),
+    })
+}
+
+fn parse_link(token: &str) -> Option<MessageFragment> {
+    let link = token.trim_end_matches(TRAILING_PUNCTUATION);
+    let lowercase = link.to_ascii_lowercase();
+
+    let (host, url) = match lowercase
+        .strip_prefix("https://")
+        .or_else(|| lowercase.strip_prefix("http://"))
+    {
+        Some(rest) => (rest, link.to_string()),
+        None => (lowercase.as_str(), format!("https://{}", link)),
+    };
+    let host = host.split(['/', '?', '#']).next().unwrap_or_default();
+    let host = host.split(':').next().unwrap_or_default();
+
+    let labels: Vec<&str> = host.split('.').collect();
+    let is_host = labels.len() >= 2
+        && labels.iter().all(|label| {
+            !label.is_empty() && label.chars().all(|c| c.is_ascii_alphanumeric() || c == '-')
+        });
+    let tld = labels.last().unwrap_or(&"");
+    if !is_host || tld.len() < 2 || !tld.chars().all(|c| c.is_ascii_alphabetic()) {
+        return None;
+    }
+
+    Some(MessageFragment::Link {
+        text: link.to_string(),
+        url,
+    })
+}
+
+struct EmoteRange<'a> {
+    id: &'a str,
+    start: usize,
+    end: usize,
+}
+
+/// Twitch counts emote offsets in code points, the returned ranges are byte
+/// offsets into `text`. Invalid and overlapping ranges are skipped.
+fn parse_emotes_tag<'a>(emotes_tag: &'a str, text: &str) -> Vec<EmoteRange<'a>> {
+    let byte_offsets: Vec<usize> = text
+        .char_indices()
+        .map(|(offset, _)| offset)
+        .chain(iter::once(text.len()))
+        .collect();
+    let code_points = byte_offsets.len() - 1;
+
+    let mut emotes: Vec<EmoteRange> = Vec::new();
+    for emote in emotes_tag.split('/') {
+        let Some((id, ranges)) = emote.split_once(':') else {
+            continue;
+        };
+
+        for range in ranges.split(',') {
+            let Some((start, end)) = range.split_once('-') else {
+                continue;
+            };
+            let (Ok(start), Ok(end)) = (start.parse::<usize>(), end.parse::<usize>()) else {
+                continue;
+            };
+            if id.is_empty() || start > end || end >= code_points {
+                continue;
+            }
+
+            emotes.push(EmoteRange {
+                id,
+                start: byte_offsets[start],
+                end: byte_offsets[end + 1],
+            });
+        }
+    }
+
+    emotes.sort_by_key(|emote| emote.start);
+
+    let mut position = 0;
+    emotes.retain(|emote| {
+        let is_free = emote.start >= position;
+        if is_free {
+            position = emote.end;
+        }
+        is_free
+    });
+
+    emotes
+}
+
+#[derive(Default)]
+pub(crate) struct FragmentsBuilder {
+    fragments: Vec<MessageFragment>,
+    text: String,
+}
+
+impl FragmentsBuilder {
+    pub(crate) fn push_text(&mut self, text: &str) {
+        self.text.push_str(text);
+    }
+
+    pub(crate) fn push(&mut self, fragment: MessageFragment) {
+        self.flush_text();
+        self.fragments.push(fragment);
+    }
+
+    pub(crate) fn build(mut self) -> Vec<MessageFragment> {
+        self.flush_text();
+        self.fragments
+    }
+
+    fn flush_text(&mut self) {
+        if !self.text.is_empty() {
+            self.fragments.push(MessageFragment::Text {
+                text: std::mem::take(&mut self.text),
+            });
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use proptest::prelude::*;
+
+    use types::domain::{MessageFragment, ThirdPartyEmote};
+    use types::twitch;
+
+    use crate::MessageTokenizer;
+
+    fn tier(min_bits: i64) -> twitch::CheermoteTier {
+        twitch::CheermoteTier {
+            id: min_bits.to_string(),
+            min_bits,
+            color: "#979797".to_string(),
+            animated_image: format!("https://cdn/animated/{}.gif", min_bits),
+            static_image: format!("https://cdn/static/{}.png", min_bits),
+        }
+    }
+
+    fn tokenizer() -> MessageTokenizer {
+        MessageTokenizer::new(
+            vec![ThirdPartyEmote {
+                provider: "7tv".to_string(),
+                id: "60ae958e229664e8667aea38".to_string(),
+                name: "catJAM".to_string(),
+                image: "https://cdn.7tv.app/emote/60ae958e229664e8667aea38/4x.webp".to_string(),
+            }],
+            vec![twitch::Cheermote {
+                prefix: "Cheer".to_string(),
+                tiers: vec![tier(1), tier(100), tier(1000)],
+            }],
+        )
+    }
+
+    fn text(text: &str) -> MessageFragment {
+        MessageFragment::Text {
+            text: text.to_string(),
+        }
+    }
+
+    fn emote(text: &str, id: &str) -> MessageFragment {
+        MessageFragment::Emote {
+            text: text.to_string(),
+            id: id.to_string(),
+            image: format!(
+                "https://static-cdn.jtvnw.net/emoticons/v2/{}/default/dark/3.0",
+                id
+            ),
+        }
+    }
+
+    #[test]
+    fn plain_text() {
+        let fragments = tokenizer().tokenize("hello world", "", false);
+
+        assert_eq!(fragments, vec![text("hello world")]);
+    }
+
+    #[test]
+    fn all_fragments() {
+        let fragments = tokenizer().tokenize(
+            "Kappa @Streamer, catJAM cheer150 see twitch.tv/streamer",
+            "25:0-4",
+            true,
+        );
+
+        assert_eq!(
+            fragments,
+            vec![
+                emote("Kappa", "25"),
+                text(" "),
+                MessageFragment::Mention {
+                    text: "@Streamer".to_string(),
+                    login: "streamer".to_string(),
+                },
+                text(", "),
+                MessageFragment::ThirdPartyEmote {
+                    text: "catJAM".to_string(),
+                    provider: "7tv".to_string(),
+                    id: "60ae958e229664e8667aea38".to_string(),
+                    image: "https://cdn.7tv.app/emote/60ae958e229664e8667aea38/4x.webp".to_string(),
+                },
+                text(" "),
+                MessageFragment::Cheermote {
+                    text: "cheer150".to_string(),
+                    prefix: "Cheer".to_string(),
+                    bits: 150,
+                    tier: tier(100),
+                },
+                text(" see "),
+                MessageFragment::Link {
+                    text: "twitch.tv/streamer".to_string(),
+                    url: "https://twitch.tv/streamer".to_string(),
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn emote_offsets_count_code_points() {
+        let fragments = tokenizer().tokenize("😂 Kappa 𝕏 Kappa", "25:2-6,10-14", false);
+
+        assert_eq!(
+            fragments,
+            vec![
+                text("😂 "),
+                emote("Kappa", "25"),
+                text(" 𝕏 "),
+                emote("Kappa", "25"),
+            ]
+        );
+    }
+
+    #[test]
+    fn cheers_need_bits() {
+        let fragments = tokenizer().tokenize("Cheer100", "", false);
+
+        assert_eq!(fragments, vec![text("Cheer100")]);
+    }
+
+    #[test]
+    fn not_cheers() {
+        let fragments = tokenizer().tokenize("Cheer cheer0 Cheerful100 100", "", true);
+
+        assert_eq!(fragments, vec![text("Cheer cheer0 Cheerful100 100")]);
+    }
+
+    #[test]
+    fn not_links() {
+        let fragments = tokenizer().tokenize("pi is 3.14, e.g. ...", "", false);
+
+        assert_eq!(fragments, vec![text("pi is 3.14, e.g. ...")]);
+    }
+
+    proptest! {
+        #[test]
+        fn fragments_join_into_message(
+            message in "\\PC{0,40}",
+            emotes_tag in "([0-9a-z_]{1,6}:[0-9]{1,2}-[0-9]{1,2}(,[0-9]{1,2}-[0-9]{1,2}){0,2}/?){0,3}",
+            has_bits in any::<bool>(),
+        ) {
+            let fragments = tokenizer().tokenize(&message, &emotes_tag, has_bits);
+
+            let joined: String = fragments.iter().map(|fragment| fragment.text()).collect();
+            prop_assert_eq!(joined, message);
+        }
+
+        #[test]
+        fn no_empty_or_adjacent_text_fragments(
+            message in "\\PC{0,40}",
+            emotes_tag in "\\PC{0,20}",
+        ) {
+            let fragments = tokenizer().tokenize(&message, &emotes_tag, true);
+
+            for fragment in fragments.iter() {
+                prop_assert!(!fragment.text().is_empty());
+            }
+            for pair in fragments.windows(2) {
+                let is_both_text = matches!(pair[0], MessageFragment::Text { .. })
+                    && matches!(pair[1], MessageFragment::Text { .. });
+                prop_assert!(!is_both_text);
+            }
+        }
+
+        #[test]
+        fn emote_found_after_any_prefix(prefix in "\\PC{0,20}", suffix in "\\PC{0,20}") {
+            let message = format!("{} Kappa {}", prefix, suffix);
+            let start = prefix.chars().count() + 1;
+            let emotes_tag = format!("25:{}-{}", start, start + 4);
+
+            let fragments = tokenizer().tokenize(&message, &emotes_tag, false);
+
+            prop_assert!(fragments.contains(&emote("Kappa", "25")));
+        }
+    }
+}
@@ -0,0 +1,60 @@
+use std::sync::Arc;
+use std::time::Duration;
+
+use tokio::time::{interval, MissedTickBehavior};
+use tracing::instrument;
+
+use dao::{BanWordFilterDao, ChatSettingsDao};
+use types::error::AppResult;
+
+const PURGE_INTERVAL: Duration = Duration::from_secs(60 * 60);
+
+/// Deletes chat settings and ban word filters which stayed in the trash for
+/// longer than the retention window. Every instance purges, a purge of rows
+/// already deleted by another instance does nothing.
+pub struct TrashPurge {
+    chat_settings_dao: Arc<ChatSettingsDao>,
+    ban_word_filter_dao: Arc<BanWordFilterDao>,
+    retention_days: i32,
+}
+
+impl TrashPurge {
+    pub fn new(
+        chat_settings_dao: Arc<ChatSettingsDao>,
+        ban_word_filter_dao: Arc<BanWordFilterDao>,
+        retention_days: u32,
+    ) -> Self {
+        TrashPurge {
+            chat_settings_dao,
+            ban_word_filter_dao,
+            retention_days: i32::try_from(retention_days).unwrap_or(i32::MAX),
+        }
+    }
+
+    pub fn spawn(self) {
+        tokio::spawn(async move {
+            let mut tick = interval(PURGE_INTERVAL);
+            tick.set_missed_tick_behavior(MissedTickBehavior::Delay);
+
+            loop {
+                tick.tick().await;
+
+                if let Err(e) = self.purge().await {
+                    tracing::warn!({ error = ?e }, "fail purge trash");
+                }
+            }
+        });
+    }
+
+    #[instrument(skip(self))]
+    async fn purge(&self) -> AppResult {
+        let chat_settings = self.chat_settings_dao.purge(self.retention_days).await?;
+        let ban_word_filters = self.ban_word_filter_dao.purge(self.retention_days).await?;
+
+        if chat_settings > 0 || ban_word_filters > 0 {
+            tracing::info!({ chat_settings, ban_word_filters }, "trash purged");
+        }
+
+        Ok(())
+    }
+}
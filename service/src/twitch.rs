@@ -1,18 +1,40 @@
+use std::collections::HashMap;
 use std::sync::Arc;
 
+use axum::http::StatusCode;
+use chrono::{DateTime, Duration, Utc};
+use tokio::sync::RwLock;
 use tracing::instrument;
 
+use dao::TwitchDataDao;
 use twitch_api::TwitchApi;
 use types::error::AppResult;
 use types::twitch;
 
 pub struct TwitchService {
     twitch_api: Arc<TwitchApi>,
+    twitch_data_dao: Arc<TwitchDataDao>,
+    user_tokens: RwLock<HashMap<String, UserToken>>,
+}
+
+struct UserToken {
+    access_token: String,
+    expired_at: DateTime<Utc>,
+}
+
+impl UserToken {
+    fn is_expired(&self) -> bool {
+        self.expired_at < Utc::now() + Duration::minutes(1)
+    }
 }
 
 impl TwitchService {
-    pub fn new(twitch_api: Arc<TwitchApi>) -> Self {
-        TwitchService { twitch_api }
+    pub fn new(twitch_api: Arc<TwitchApi>, twitch_data_dao: Arc<TwitchDataDao>) -> Self {
+        TwitchService {
+            twitch_api,
+            twitch_data_dao,
+            user_tokens: RwLock::new(HashMap::new()),
+        }
     }
 
     #[instrument(skip(self))]
@@ -39,4 +61,77 @@ impl TwitchService {
     pub async fn get_channel_badges(&self, channel_id: &str) -> AppResult<Vec<twitch::Badge>> {
         self.twitch_api.get_channel_badges(channel_id).await
     }
+
+    #[instrument(skip(self))]
+    pub async fn get_global_cheermotes(&self) -> AppResult<Vec<twitch::Cheermote>> {
+        self.twitch_api.get_global_cheermotes().await
+    }
+
+    #[instrument(skip(self))]
+    pub async fn get_channel_cheermotes(
+        &self,
+        channel_id: &str,
+    ) -> AppResult<Vec<twitch::Cheermote>> {
+        self.twitch_api.get_channel_cheermotes(channel_id).await
+    }
+
+    #[instrument(skip(self))]
+    pub async fn get_rewards(&self, user_id: &str) -> AppResult<Vec<twitch::Reward>> {
+        let user_token = self.get_user_token(user_id).await?;
+
+        match self
+            .twitch_api
+            .get_custom_rewards(&user_token, user_id)
+            .await
+        {
+            // Revoked before it expired, a fresh one may still be granted.
+            Err(e) if e.status_code == StatusCode::UNAUTHORIZED => {
+                let user_token = self.refresh_user_token(user_id).await?;
+                self.twitch_api
+                    .get_custom_rewards(&user_token, user_id)
+                    .await
+            }
+            result => result,
+        }
+    }
+
+    #[instrument(skip(self))]
+    pub(crate) async fn get_user_token(&self, user_id: &str) -> AppResult<String> {
+        {
+            let user_tokens = self.user_tokens.read().await;
+            if let Some(user_token) = user_tokens.get(user_id) {
+                if !user_token.is_expired() {
+                    return Ok(user_token.access_token.clone());
+                }
+            }
+        }
+
+        self.refresh_user_token(user_id).await
+    }
+
+    /// Twitch may rotate the refresh token, so the new one is stored right away.
+    #[instrument(skip(self))]
+    async fn refresh_user_token(&self, user_id: &str) -> AppResult<String> {
+        let twitch_data = self.twitch_data_dao.get(user_id).await?;
+        let token = self
+            .twitch_api
+            .refresh_user_token(&twitch_data.refresh_token)
+            .await?;
+
+        if token.refresh_token != twitch_data.refresh_token {
+            self.twitch_data_dao
+                .create_or_update(user_id, &token.refresh_token)
+                .await?;
+        }
+
+        self.user_tokens.write().await.insert(
+            user_id.to_string(),
+            UserToken {
+                access_token: token.access_token.clone(),
+                expired_at: Utc::now() + Duration::seconds(token.expires_in),
+            },
+        );
+
+        Ok(token.access_token)
+    }
 }
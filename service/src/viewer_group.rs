@@ -0,0 +1,101 @@
+use types::domain::ViewerGroup;
+use types::twitch::ChatBadge;
+
+/// Logins of widespread chat bots, matched by the `known-bot` group.
+const KNOWN_BOTS: [&str; 12] = [
+    "nightbot",
+    "streamelements",
+    "streamlabs",
+    "moobot",
+    "fossabot",
+    "wizebot",
+    "sery_bot",
+    "soundalerts",
+    "pokemoncommunitygame",
+    "commanderroot",
+    "botrixoficial",
+    "kofistreambot",
+];
+
+/// Groups of a chatter, read from the badges and tags of their message.
+pub(crate) fn viewer_groups(
+    login: &str,
+    badges: &[ChatBadge],
+    first_message: bool,
+) -> Vec<ViewerGroup> {
+    let mut groups = Vec::new();
+
+    for badge in badges {
+        match badge.set.as_str() {
+            "broadcaster" => groups.push(ViewerGroup::Broadcaster),
+            "moderator" => groups.push(ViewerGroup::Moderator),
+            "vip" => groups.push(ViewerGroup::Vip),
+            // Tier 2 and 3 badge versions are offset by 2000 and 3000.
+            "subscriber" => {
+                let version = badge.version.parse::<u32>().unwrap_or_default();
+                groups.push(ViewerGroup::Subscriber);
+                if version >= 2000 {
+                    groups.push(ViewerGroup::SubscriberTier2);
+                }
+                if version >= 3000 {
+                    groups.push(ViewerGroup::SubscriberTier3);
+                }
+            }
+            "founder" => groups.push(ViewerGroup::Subscriber),
+            _ => {}
+        }
+    }
+
+    if first_message {
+        groups.push(ViewerGroup::FirstTimeChatter);
+    }
+    if KNOWN_BOTS.contains(&login) {
+        groups.push(ViewerGroup::KnownBot);
+    }
+
+    // Founders may also have a subscriber badge.
+    groups.sort();
+    groups.dedup();
+    groups
+}
+
+#[cfg(test)]
+mod tests {
+    use types::domain::ViewerGroup;
+    use types::twitch::ChatBadge;
+
+    use crate::viewer_group::viewer_groups;
+
+    fn badge(set: &str, version: &str) -> ChatBadge {
+        ChatBadge {
+            set: set.to_string(),
+            version: version.to_string(),
+        }
+    }
+
+    #[test]
+    fn groups_from_badges() {
+        assert_eq!(
+            viewer_groups(
+                "viewer",
+                &[badge("moderator", "1"), badge("subscriber", "3012")],
+                false
+            ),
+            vec![
+                ViewerGroup::Subscriber,
+                ViewerGroup::SubscriberTier2,
+                ViewerGroup::SubscriberTier3,
+                ViewerGroup::Moderator,
+            ]
+        );
+        assert_eq!(
+            viewer_groups("viewer", &[badge("subscriber", "12")], true),
+            vec![ViewerGroup::FirstTimeChatter, ViewerGroup::Subscriber]
+        );
+        assert_eq!(
+            viewer_groups("nightbot", &[badge("moderator", "1")], false),
+            vec![ViewerGroup::Moderator, ViewerGroup::KnownBot]
+        );
+        assert!(viewer_groups("viewer", &[badge("premium", "1")], false).is_empty());
+    }
+}
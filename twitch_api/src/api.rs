@@ -3,23 +3,31 @@ use std::ops::Add;
 
 use axum::http::StatusCode;
 use chrono::{Duration, Utc};
-use reqwest::{Client, Error, RequestBuilder, Response, Url};
+use futures_util::{stream, Stream, TryStreamExt};
+use reqwest::header::{HeaderValue, AUTHORIZATION};
+use reqwest::{Client, Error, Method, RequestBuilder, Response, Url};
 use serde::de::DeserializeOwned;
 use tokio::sync::RwLock;
 use tracing::instrument;
 
 use config::TwitchConfig;
 use types::error::{AppError, AppResult};
-use types::twitch::{Badge, Emote, UserInfo};
+use types::twitch::{Badge, Cheermote, Emote, Reward, UserInfo};
 
 use crate::consts::{AUTHORIZE_URL, HELIX_URL, TOKEN_URL};
 use crate::domain::{
-    AppAccessToken, GetAppAccessTokenResponse, GetBadgesResponse, GetEmotesResponse,
-    GetUserInfoResponse, GetUserTokenResponse, Scope,
+    AppAccessToken, CreateEventSubSubscriptionRequest, CreateEventSubSubscriptionResponse,
+    EventSubSubscription, EventSubTransport, GetAppAccessTokenResponse, GetBadgesResponse,
+    GetCheermotesResponse, GetCustomRewardsResponse, GetEmotesResponse, GetPageResponse, GetUserInfoResponse, GetUserTokenResponse, Scope,
+    SubscriptionType,
 };
 
+const MAX_USERS_PER_REQUEST: usize = 100;
+
 pub struct TwitchApi {
     twitch_config: TwitchConfig,
+    helix_url: String,
+    token_url: String,
     app_token: RwLock<AppAccessToken>,
 }
 
@@ -27,10 +35,24 @@ impl TwitchApi {
     pub fn new(twitch_config: TwitchConfig) -> Self {
         TwitchApi {
             twitch_config,
+            helix_url: HELIX_URL.to_string(),
+            token_url: TOKEN_URL.to_string(),
             app_token: RwLock::new(AppAccessToken::default()),
         }
     }
 
+    /// Overrides the Helix base url, e.g. to point at the Twitch CLI mock server.
+    pub fn helix_url(mut self, helix_url: &str) -> Self {
+        self.helix_url = helix_url.to_string();
+        self
+    }
+
+    /// Overrides the OAuth token url, e.g. to point at the Twitch CLI mock server.
+    pub fn token_url(mut self, token_url: &str) -> Self {
+        self.token_url = token_url.to_string();
+        self
+    }
+
     #[instrument(skip(self))]
     pub fn get_authorize_url(&self, scope: Vec<Scope>) -> String {
         let scope: Vec<String> = scope.iter().map(Scope::string).collect();
@@ -45,11 +67,11 @@ impl TwitchApi {
 
     #[instrument(skip(self))]
     pub async fn get_user_info(&self, login: &str) -> AppResult<UserInfo> {
-        let query_params: HashMap<&str, &str> = HashMap::from([("login", login)]);
+        let query_params: Vec<(&str, &str)> = vec![("login", login)];
 
         let request = self.request("/users", Some(query_params)).await?;
 
-        let mut response = self.send_with_retry_on_unauthorized(request).await
+        let response = self.send_with_retry_on_unauthorized(request).await
             .map_err(|e| TwitchApi::FAIL_GET_USER_INFO_ERROR.clone().cause(e.into()))?;
 
         if !response.status().is_success() {
@@ -69,6 +91,76 @@ impl TwitchApi {
         }
     }
 
+    /// Resolves users by ids and logins, splitting them into batches of
+    /// at most 100 per request. Unknown users are skipped by Twitch.
+    #[instrument(skip(self))]
+    pub async fn get_users_info(
+        &self,
+        ids: &[String],
+        logins: &[String],
+    ) -> AppResult<Vec<UserInfo>> {
+        let query_params: Vec<(&str, &str)> = ids
+            .iter()
+            .map(|id| ("id", id.as_str()))
+            .chain(logins.iter().map(|login| ("login", login.as_str())))
+            .collect();
+
+        let mut users: Vec<UserInfo> = Vec::new();
+
+        for chunk in query_params.chunks(MAX_USERS_PER_REQUEST) {
+            let request = self.request("/users", Some(chunk.to_vec())).await?;
+
+            let response = self.send_with_retry_on_unauthorized(request).await
+                .map_err(|e| TwitchApi::FAIL_GET_USER_INFO_ERROR.clone().cause(e.into()))?;
+
+            if !response.status().is_success() {
+                return Err(TwitchApi::FAIL_REQUEST_WITH_STATUS_CODE_ERROR
+                    .clone()
+                    .message(&format!(
+                        "fail get users info with status code: {}",
+                        response.status().as_u16()
+                    )));
+            }
+
+            let get_user_info_response = self.parse_json::<GetUserInfoResponse>(response).await?;
+
+            users.extend(get_user_info_response.data);
+        }
+
+        Ok(users)
+    }
+
+    /// Streams every item of a paginated Helix endpoint, following
+    /// `pagination.cursor` until Twitch stops returning one.
+    pub fn paginate<'a, T: DeserializeOwned + 'a>(
+        &'a self,
+        path: &'a str,
+        query_params: Vec<(&'a str, &'a str)>,
+    ) -> impl Stream<Item = AppResult<T>> + 'a {
+        stream::try_unfold(PageState::First, move |state| {
+            let query_params = query_params.clone();
+            async move {
+                let cursor = match state {
+                    PageState::First => None,
+                    PageState::Next(cursor) => Some(cursor),
+                    PageState::Last => return AppResult::Ok(None),
+                };
+
+                let page = self
+                    .get_page::<T>(path, query_params, cursor.as_deref())
+                    .await?;
+
+                let state = match page.pagination.cursor {
+                    Some(cursor) if !cursor.is_empty() => PageState::Next(cursor),
+                    _ => PageState::Last,
+                };
+
+                Ok(Some((stream::iter(page.data.into_iter().map(Ok)), state)))
+            }
+        })
+        .try_flatten()
+    }
+
     #[instrument(skip(self))]
     pub async fn get_global_emotes(&self) -> AppResult<Vec<Emote>> {
         let request = self.request("/chat/emotes/global", None).await?;
@@ -98,7 +190,7 @@ impl TwitchApi {
 
     #[instrument(skip(self))]
     pub async fn get_channel_emotes(&self, channel_id: &str) -> AppResult<Vec<Emote>> {
-        let query_params: HashMap<&str, &str> = HashMap::from([("broadcaster_id", channel_id)]);
+        let query_params: Vec<(&str, &str)> = vec![("broadcaster_id", channel_id)];
 
         let request = self.request("/chat/emotes", Some(query_params)).await?;
 
@@ -154,7 +246,7 @@ impl TwitchApi {
 
     #[instrument(skip(self))]
     pub async fn get_channel_badges(&self, channel_id: &str) -> AppResult<Vec<Badge>> {
-        let query_params: HashMap<&str, &str> = HashMap::from([("broadcaster_id", channel_id)]);
+        let query_params: Vec<(&str, &str)> = vec![("broadcaster_id", channel_id)];
 
         let request = self.request("/chat/badges", Some(query_params)).await?;
 
@@ -181,6 +273,106 @@ impl TwitchApi {
         Ok(badges)
     }
 
+    #[instrument(skip(self))]
+    pub async fn get_global_cheermotes(&self) -> AppResult<Vec<Cheermote>> {
+        let request = self.request("/bits/cheermotes", None).await?;
+
+        let response = self.send_with_retry_on_unauthorized(request).await
+            .map_err(|e| {
+                TwitchApi::FAIL_GET_GLOBAL_CHEERMOTES_ERROR
+                    .clone()
+                    .cause(e.into())
+            })?;
+
+        if !response.status().is_success() {
+            return Err(TwitchApi::FAIL_REQUEST_WITH_STATUS_CODE_ERROR
+                .clone()
+                .message(&format!(
+                    "fail get global cheermotes with status code: {}",
+                    response.status().as_u16()
+                )));
+        }
+
+        let get_cheermotes_response = self.parse_json::<GetCheermotesResponse>(response).await?;
+
+        let cheermotes = get_cheermotes_response.to_twitch_cheermotes();
+
+        Ok(cheermotes)
+    }
+
+    /// Returns the global cheermotes together with the broadcaster's custom ones.
+    #[instrument(skip(self))]
+    pub async fn get_channel_cheermotes(&self, channel_id: &str) -> AppResult<Vec<Cheermote>> {
+        let query_params: Vec<(&str, &str)> = vec![("broadcaster_id", channel_id)];
+
+        let request = self.request("/bits/cheermotes", Some(query_params)).await?;
+
+        let response = self.send_with_retry_on_unauthorized(request).await
+            .map_err(|e| {
+                TwitchApi::FAIL_GET_CHANNEL_CHEERMOTES_ERROR
+                    .clone()
+                    .cause(e.into())
+            })?;
+
+        if !response.status().is_success() {
+            return Err(TwitchApi::FAIL_REQUEST_WITH_STATUS_CODE_ERROR
+                .clone()
+                .message(&format!(
+                    "fail get channel cheermotes with status code: {}",
+                    response.status().as_u16()
+                )));
+        }
+
+        let get_cheermotes_response = self.parse_json::<GetCheermotesResponse>(response).await?;
+
+        let cheermotes = get_cheermotes_response.to_twitch_cheermotes();
+
+        Ok(cheermotes)
+    }
+
+    /// Custom rewards can only be read by the broadcaster, so this needs their user token.
+    #[instrument(skip(self, user_token))]
+    pub async fn get_custom_rewards(
+        &self,
+        user_token: &str,
+        broadcaster_id: &str,
+    ) -> AppResult<Vec<Reward>> {
+        let client = Client::new();
+
+        let request = client
+            .get(format!("{}/channel_points/custom_rewards", self.helix_url))
+            .query(&[("broadcaster_id", broadcaster_id)])
+            .bearer_auth(user_token)
+            .header("Client-Id", self.twitch_config.client_id());
+
+        let response = request.send().await.map_err(|e| {
+            TwitchApi::FAIL_GET_CUSTOM_REWARDS_ERROR
+                .clone()
+                .cause(e.into())
+        })?;
+
+        if response.status() == StatusCode::UNAUTHORIZED {
+            return Err(TwitchApi::UNAUTHORIZED_USER_TOKEN_ERROR);
+        }
+
+        if !response.status().is_success() {
+            return Err(TwitchApi::FAIL_REQUEST_WITH_STATUS_CODE_ERROR
+                .clone()
+                .message(&format!(
+                    "fail get custom rewards with status code: {}",
+                    response.status().as_u16()
+                )));
+        }
+
+        let get_custom_rewards_response = self
+            .parse_json::<GetCustomRewardsResponse>(response)
+            .await?;
+
+        let rewards = get_custom_rewards_response.to_twitch_rewards();
+
+        Ok(rewards)
+    }
+
     #[instrument(skip_all)]
     pub async fn exchange_code(&self, code: &str) -> AppResult<(GetUserTokenResponse, UserInfo)> {
         let response = self.get_user_token(code).await?;
@@ -191,6 +383,148 @@ impl TwitchApi {
         Ok((response, user_info))
     }
 
+    #[instrument(skip_all)]
+    pub async fn refresh_user_token(&self, refresh_token: &str) -> AppResult<GetUserTokenResponse> {
+        let form = HashMap::from([
+            ("client_id", self.twitch_config.client_id()),
+            ("client_secret", self.twitch_config.client_secret()),
+            ("refresh_token", refresh_token),
+            ("grant_type", "refresh_token"),
+        ]);
+
+        let client = Client::new();
+
+        let request = client.post(&self.token_url).form(&form);
+
+        let response = request
+            .send()
+            .await
+            .map_err(|e| TwitchApi::FAIL_REFRESH_USER_TOKEN_ERROR.clone().cause(e.into()))?;
+
+        if !response.status().is_success() {
+            return Err(TwitchApi::FAIL_REQUEST_WITH_STATUS_CODE_ERROR
+                .clone()
+                .message(&format!(
+                    "fail refresh user token with status code: {}",
+                    response.status().as_u16()
+                )));
+        }
+
+        self.parse_json::<GetUserTokenResponse>(response).await
+    }
+
+    #[instrument(skip(self, user_token))]
+    pub async fn create_eventsub_subscription(
+        &self,
+        user_token: &str,
+        subscription_type: &SubscriptionType,
+        broadcaster_id: &str,
+        transport: &EventSubTransport,
+    ) -> AppResult<EventSubSubscription> {
+        let body = CreateEventSubSubscriptionRequest {
+            subscription_type: subscription_type.name(),
+            version: subscription_type.version(),
+            condition: subscription_type.condition(broadcaster_id),
+            transport,
+        };
+
+        let client = Client::new();
+
+        let request = client
+            .post(format!("{}/eventsub/subscriptions", self.helix_url))
+            .bearer_auth(user_token)
+            .header("Client-Id", self.twitch_config.client_id())
+            .json(&body);
+
+        let response = request.send().await.map_err(|e| {
+            TwitchApi::FAIL_CREATE_EVENTSUB_SUBSCRIPTION_ERROR
+                .clone()
+                .cause(e.into())
+        })?;
+
+        if !response.status().is_success() {
+            return Err(TwitchApi::FAIL_REQUEST_WITH_STATUS_CODE_ERROR
+                .clone()
+                .message(&format!(
+                    "fail create eventsub subscription {} with status code: {}",
+                    subscription_type.name(),
+                    response.status().as_u16()
+                )));
+        }
+
+        let create_response = self
+            .parse_json::<CreateEventSubSubscriptionResponse>(response)
+            .await?;
+
+        create_response
+            .data
+            .into_iter()
+            .next()
+            .ok_or(TwitchApi::FAIL_CREATE_EVENTSUB_SUBSCRIPTION_ERROR)
+    }
+
+    #[instrument(skip(self, secret))]
+    pub async fn create_eventsub_webhook_subscription(
+        &self,
+        subscription_type: &SubscriptionType,
+        broadcaster_id: &str,
+        callback: &str,
+        secret: &str,
+    ) -> AppResult<EventSubSubscription> {
+        // Webhook subscriptions are created with an app token, the broadcaster
+        // only has to have authorized the scopes beforehand.
+        let access_token = self.get_app_access_token().await?;
+        let transport = EventSubTransport::Webhook {
+            callback: callback.to_string(),
+            secret: secret.to_string(),
+        };
+
+        self.create_eventsub_subscription(
+            &access_token,
+            subscription_type,
+            broadcaster_id,
+            &transport,
+        )
+        .await
+    }
+
+    pub fn get_eventsub_subscriptions(
+        &self,
+    ) -> impl Stream<Item = AppResult<EventSubSubscription>> + '_ {
+        self.paginate("/eventsub/subscriptions", Vec::new())
+    }
+
+    #[instrument(skip(self))]
+    pub async fn delete_eventsub_subscription(&self, subscription_id: &str) -> AppResult {
+        let query_params: Vec<(&str, &str)> = vec![("id", subscription_id)];
+
+        let request = self
+            .request_with_method(
+                Method::DELETE,
+                "/eventsub/subscriptions",
+                Some(query_params),
+            )
+            .await?;
+
+        let response = self.send_with_retry_on_unauthorized(request).await
+            .map_err(|e| {
+                TwitchApi::FAIL_DELETE_EVENTSUB_SUBSCRIPTION_ERROR
+                    .clone()
+                    .cause(e.into())
+            })?;
+
+        if !response.status().is_success() {
+            return Err(TwitchApi::FAIL_REQUEST_WITH_STATUS_CODE_ERROR
+                .clone()
+                .message(&format!(
+                    "fail delete eventsub subscription with status code: {}",
+                    response.status().as_u16()
+                )));
+        }
+
+        Ok(())
+    }
+
     #[instrument(skip_all)]
     async fn get_user_token(&self, code: &str) -> AppResult<GetUserTokenResponse> {
         let form = HashMap::from([
@@ -203,7 +537,7 @@ impl TwitchApi {
 
         let client = Client::new();
 
-        let request = client.post(TOKEN_URL).form(&form);
+        let request = client.post(&self.token_url).form(&form);
 
         let response = self.send_with_retry_on_unauthorized(request).await
             .map_err(|e| TwitchApi::FAIL_GET_USER_TOKEN_ERROR.clone().cause(e.into()))?;
@@ -225,7 +559,7 @@ impl TwitchApi {
         let client = Client::new();
 
         let request = client
-            .get(&format!("{}/users", HELIX_URL))
+            .get(format!("{}/users", self.helix_url))
             .bearer_auth(token)
             .header("Client-Id", self.twitch_config.client_id());
 
@@ -253,10 +587,12 @@ impl TwitchApi {
 
     #[instrument(skip_all)]
     async fn get_app_access_token(&self) -> AppResult<String> {
-        let app_token = self.app_token.read().await;
-        if !app_token.is_expired() {
-            let token = app_token.token().ok_or(AppError::UNEXPECTED)?;
-            return Ok(token);
+        {
+            let app_token = self.app_token.read().await;
+            if !app_token.is_expired() {
+                let token = app_token.token().ok_or(AppError::UNEXPECTED)?;
+                return Ok(token);
+            }
         }
 
         self.request_app_access_token().await
@@ -272,7 +608,7 @@ impl TwitchApi {
 
         let client = Client::new();
 
-        let request = client.post(TOKEN_URL).form(&form);
+        let request = client.post(&self.token_url).form(&form);
 
         let response = request.send().await.map_err(|e| {
             TwitchApi::FAIL_GET_APP_ACCESS_TOKEN_ERROR
@@ -312,13 +648,52 @@ impl TwitchApi {
         })
     }
 
+    #[instrument(skip_all)]
+    async fn get_page<T: DeserializeOwned>(
+        &self,
+        path: &str,
+        mut query_params: Vec<(&str, &str)>,
+        cursor: Option<&str>,
+    ) -> AppResult<GetPageResponse<T>> {
+        if let Some(cursor) = cursor {
+            query_params.push(("after", cursor));
+        }
+
+        let request = self.request(path, Some(query_params)).await?;
+
+        let response = self.send_with_retry_on_unauthorized(request).await
+            .map_err(|e| TwitchApi::FAIL_GET_PAGE_ERROR.clone().cause(e.into()))?;
+
+        if !response.status().is_success() {
+            return Err(TwitchApi::FAIL_REQUEST_WITH_STATUS_CODE_ERROR
+                .clone()
+                .message(&format!(
+                    "fail get page of {} with status code: {}",
+                    path,
+                    response.status().as_u16()
+                )));
+        }
+
+        self.parse_json::<GetPageResponse<T>>(response).await
+    }
+
     #[instrument(skip_all)]
     async fn request(
         &self,
         path: &str,
-        query_params: Option<HashMap<&str, &str>>,
+        query_params: Option<Vec<(&str, &str)>>,
+    ) -> AppResult<RequestBuilder> {
+        self.request_with_method(Method::GET, path, query_params).await
+    }
+
+    #[instrument(skip_all)]
+    async fn request_with_method(
+        &self,
+        method: Method,
+        path: &str,
+        query_params: Option<Vec<(&str, &str)>>,
     ) -> AppResult<RequestBuilder> {
-        let url = format!("{}{}", HELIX_URL, path);
+        let url = format!("{}{}", self.helix_url, path);
         let url = match query_params {
             Some(params) => Url::parse_with_params(&url, params),
             None => Url::parse(&url),
@@ -328,7 +703,7 @@ impl TwitchApi {
         let client = Client::new();
 
         Ok(client
-            .get(url)
+            .request(method, url)
             .bearer_auth(access_token)
             .header("Client-Id", self.twitch_config.client_id()))
     }
@@ -336,19 +711,52 @@ impl TwitchApi {
     #[instrument(skip_all)]
     async fn send_with_retry_on_unauthorized(
         &self,
-        request: RequestBuilder
+        request: RequestBuilder,
     ) -> Result<Response, Error> {
-        let mut response = request.send().await?;
-
-        if response.status() == StatusCode::UNAUTHORIZED {
-            let access_token = self.request_app_access_token().await?;
-            return request.bearer_auth(access_token).send().await;
+        let (client, request) = request.build_split();
+        let request = request?;
+        let retry_request = request.try_clone();
+        let response = client.execute(request).await?;
+
+        // Only requests authorized with the app token can be replayed with a new one.
+        let mut retry_request = match retry_request {
+            Some(retry_request)
+                if response.status() == StatusCode::UNAUTHORIZED
+                    && retry_request.headers().contains_key(AUTHORIZATION) =>
+            {
+                retry_request
+            }
+            _ => return Ok(response),
+        };
+
+        // The cached app token may be revoked before it expires, so request a new one
+        // and replay the request once. On failure the original response is returned.
+        let authorization = self
+            .request_app_access_token()
+            .await
+            .ok()
+            .and_then(|access_token| {
+                HeaderValue::from_str(&format!("Bearer {}", access_token)).ok()
+            });
+        match authorization {
+            // Inserting replaces the stale header, appending would send both.
+            Some(authorization) => {
+                retry_request
+                    .headers_mut()
+                    .insert(AUTHORIZATION, authorization);
+                client.execute(retry_request).await
+            }
+            None => Ok(response),
         }
-
-        return Ok(response)
     }
 }
 
+enum PageState {
+    First,
+    Next(String),
+    Last,
+}
+
 macro_rules! twitch_api_errors {
     (
         $(
@@ -378,8 +786,177 @@ twitch_api_errors! {
     (FAIL_GET_CHANNEL_EMOTES_ERROR, StatusCode::INTERNAL_SERVER_ERROR, "fail get channel emotes");
     (FAIL_GET_GLOBAL_BADGES_ERROR, StatusCode::INTERNAL_SERVER_ERROR, "fail get global badges");
     (FAIL_GET_CHANNEL_BADGES_ERROR, StatusCode::INTERNAL_SERVER_ERROR, "fail get channel badges");
+    (FAIL_GET_GLOBAL_CHEERMOTES_ERROR, StatusCode::INTERNAL_SERVER_ERROR, "fail get global cheermotes");
+    (FAIL_GET_CHANNEL_CHEERMOTES_ERROR, StatusCode::INTERNAL_SERVER_ERROR, "fail get channel cheermotes");
+    (FAIL_GET_CUSTOM_REWARDS_ERROR, StatusCode::INTERNAL_SERVER_ERROR, "fail get custom rewards");
+    (FAIL_GET_PAGE_ERROR, StatusCode::INTERNAL_SERVER_ERROR, "fail get page");
     (FAIL_GET_USER_TOKEN_ERROR, StatusCode::INTERNAL_SERVER_ERROR, "fail get user token");
+    (FAIL_REFRESH_USER_TOKEN_ERROR, StatusCode::INTERNAL_SERVER_ERROR, "fail refresh user token");
+    (UNAUTHORIZED_USER_TOKEN_ERROR, StatusCode::UNAUTHORIZED, "user token is expired or revoked");
+    (FAIL_CREATE_EVENTSUB_SUBSCRIPTION_ERROR, StatusCode::INTERNAL_SERVER_ERROR, "fail create eventsub subscription");
+    (FAIL_DELETE_EVENTSUB_SUBSCRIPTION_ERROR, StatusCode::INTERNAL_SERVER_ERROR, "fail delete eventsub subscription");
     (FAIL_REQUEST_WITH_STATUS_CODE_ERROR, StatusCode::INTERNAL_SERVER_ERROR, "fail request");
     (FAIL_PARSE_JSON_OF_RESPONSE_ERROR, StatusCode::INTERNAL_SERVER_ERROR, "fail parse json of response");
     (FAIL_PARSE_URL_ERROR, StatusCode::INTERNAL_SERVER_ERROR, "fail parse url");
 }
+
+#[cfg(test)]
+mod tests {
+    use std::net::TcpListener;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use std::sync::{Arc, Mutex};
+
+    use axum::extract::{RawQuery, State};
+    use axum::http::{header, HeaderMap, StatusCode};
+    use axum::routing::{get, post};
+    use axum::{Json, Router, Server};
+    use futures_util::TryStreamExt;
+    use serde_json::{json, Value};
+
+    use config::TwitchConfig;
+
+    use crate::TwitchApi;
+
+    #[derive(Default)]
+    struct FakeTwitch {
+        issued_tokens: AtomicUsize,
+        authorization_headers: Mutex<Vec<Vec<String>>>,
+    }
+
+    fn query_values(query: &Option<String>, key: &str) -> Vec<String> {
+        query
+            .as_deref()
+            .unwrap_or_default()
+            .split('&')
+            .filter_map(|pair| pair.split_once('='))
+            .filter(|(k, _)| *k == key)
+            .map(|(_, v)| v.to_string())
+            .collect()
+    }
+
+    async fn issue_token(State(fake): State<Arc<FakeTwitch>>) -> Json<Value> {
+        let issued = fake.issued_tokens.fetch_add(1, Ordering::SeqCst) + 1;
+        Json(json!({ "access_token": format!("token-{}", issued), "expires_in": 3600 }))
+    }
+
+    /// Only the latest token is valid, like after a revocation.
+    async fn users(
+        State(fake): State<Arc<FakeTwitch>>,
+        headers: HeaderMap,
+        RawQuery(query): RawQuery,
+    ) -> (StatusCode, Json<Value>) {
+        let authorization: Vec<String> = headers
+            .get_all(header::AUTHORIZATION)
+            .iter()
+            .map(|value| value.to_str().unwrap().to_string())
+            .collect();
+        fake.authorization_headers
+            .lock()
+            .unwrap()
+            .push(authorization.clone());
+
+        let latest = format!("Bearer token-{}", fake.issued_tokens.load(Ordering::SeqCst));
+        if authorization != [latest] {
+            return (StatusCode::UNAUTHORIZED, Json(json!({})));
+        }
+
+        let data: Vec<Value> = query_values(&query, "login")
+            .iter()
+            .enumerate()
+            .map(|(id, login)| json!({ "id": id.to_string(), "login": login }))
+            .collect();
+        (StatusCode::OK, Json(json!({ "data": data })))
+    }
+
+    async fn items(RawQuery(query): RawQuery) -> Json<Value> {
+        let after = query_values(&query, "after");
+        Json(match after.first().map(String::as_str) {
+            None => json!({ "data": [1, 2], "pagination": { "cursor": "a" } }),
+            Some("a") => json!({ "data": [3], "pagination": { "cursor": "b" } }),
+            _ => json!({ "data": [4], "pagination": {} }),
+        })
+    }
+
+    fn fake_twitch(fake: Arc<FakeTwitch>) -> TwitchApi {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let addr = listener.local_addr().unwrap();
+        let router = Router::new()
+            .route("/token", post(issue_token))
+            .route("/users", get(users))
+            .route("/items", get(items))
+            .route(
+                "/channel_points/custom_rewards",
+                get(|| async { StatusCode::UNAUTHORIZED }),
+            )
+            .with_state(fake);
+        tokio::spawn(
+            Server::from_tcp(listener)
+                .unwrap()
+                .serve(router.into_make_service()),
+        );
+
+        let twitch_config =
+            TwitchConfig::new("http://localhost/callback", "client-id", "client-secret");
+        TwitchApi::new(twitch_config)
+            .helix_url(&format!("http://{}", addr))
+            .token_url(&format!("http://{}/token", addr))
+    }
+
+    #[tokio::test]
+    async fn retry_replaces_stale_token() {
+        let fake = Arc::new(FakeTwitch::default());
+        let twitch_api = fake_twitch(fake.clone());
+
+        // Revokes the cached token before the request.
+        twitch_api.get_app_access_token().await.unwrap();
+        fake.issued_tokens.fetch_add(1, Ordering::SeqCst);
+
+        let user_info = twitch_api.get_user_info("viewer").await.unwrap();
+        assert_eq!(user_info.login, "viewer");
+        assert_eq!(
+            *fake.authorization_headers.lock().unwrap(),
+            vec![
+                vec!["Bearer token-1".to_string()],
+                vec!["Bearer token-3".to_string()],
+            ]
+        );
+    }
+
+    #[tokio::test]
+    async fn get_users_info_in_batches() {
+        let fake = Arc::new(FakeTwitch::default());
+        let twitch_api = fake_twitch(fake.clone());
+        let logins: Vec<String> = (0..150).map(|i| format!("viewer{}", i)).collect();
+
+        let users = twitch_api.get_users_info(&[], &logins).await.unwrap();
+
+        assert_eq!(users.len(), 150);
+        assert_eq!(users[149].login, "viewer149");
+        assert_eq!(fake.authorization_headers.lock().unwrap().len(), 2);
+    }
+
+    #[tokio::test]
+    async fn paginate_follows_cursor() {
+        let twitch_api = fake_twitch(Arc::new(FakeTwitch::default()));
+
+        let items: Vec<i64> = twitch_api
+            .paginate::<i64>("/items", Vec::new())
+            .try_collect()
+            .await
+            .unwrap();
+
+        assert_eq!(items, vec![1, 2, 3, 4]);
+    }
+
+    #[tokio::test]
+    async fn revoked_user_token_is_unauthorized() {
+        let twitch_api = fake_twitch(Arc::new(FakeTwitch::default()));
+
+        let error = twitch_api
+            .get_custom_rewards("revoked", "1")
+            .await
+            .unwrap_err();
+
+        assert_eq!(error.status_code, StatusCode::UNAUTHORIZED);
+    }
+}
@@ -0,0 +1,337 @@
+use std::collections::HashMap;
+use std::time::Duration;
+
+use chrono::{TimeZone, Utc};
+use futures_util::{SinkExt, StreamExt};
+use tokio::sync::mpsc;
+use tokio::time::sleep;
+use tokio_tungstenite::connect_async;
+use tokio_tungstenite::tungstenite::Message;
+use tracing::instrument;
+
+use types::twitch;
+
+use crate::consts::CHAT_WEBSOCKET_URL;
+
+const EVENTS_CAPACITY: usize = 256;
+const RECONNECT_DELAY: Duration = Duration::from_secs(5);
+/// Anonymous logins can read any chat without a token.
+const ANONYMOUS_LOGIN: &str = "justinfan12345";
+
+/// Source of chat events for a channel. The receiver is closed when the source
+/// stops, dropping it makes the source leave the channel.
+pub trait ChatSource: Send + Sync {
+    fn join(&self, channel: &str) -> mpsc::Receiver<twitch::ChatEvent>;
+}
+
+/// Reads chat through the Twitch IRC WebSocket, one connection per channel.
+pub struct TwitchChat {
+    url: String,
+}
+
+impl TwitchChat {
+    pub fn new() -> Self {
+        TwitchChat {
+            url: CHAT_WEBSOCKET_URL.to_string(),
+        }
+    }
+
+    pub fn url(mut self, url: &str) -> Self {
+        self.url = url.to_string();
+        self
+    }
+}
+
+impl Default for TwitchChat {
+    fn default() -> Self {
+        TwitchChat::new()
+    }
+}
+
+impl ChatSource for TwitchChat {
+    fn join(&self, channel: &str) -> mpsc::Receiver<twitch::ChatEvent> {
+        let (sender, receiver) = mpsc::channel(EVENTS_CAPACITY);
+        tokio::spawn(read_chat(
+            self.url.clone(),
+            channel.to_ascii_lowercase(),
+            sender,
+        ));
+        receiver
+    }
+}
+
+#[instrument(skip(url, events))]
+async fn read_chat(url: String, channel: String, events: mpsc::Sender<twitch::ChatEvent>) {
+    while !events.is_closed() {
+        let mut socket = match connect_async(&url).await {
+            Ok((socket, _)) => socket,
+            Err(e) => {
+                tracing::warn!({ error = %e }, "fail connect to chat");
+                sleep(RECONNECT_DELAY).await;
+                continue;
+            }
+        };
+
+        let join = [
+            "CAP REQ :twitch.tv/tags twitch.tv/commands".to_string(),
+            format!("NICK {}", ANONYMOUS_LOGIN),
+            format!("JOIN #{}", channel),
+        ];
+        for line in join {
+            if socket.send(Message::Text(line)).await.is_err() {
+                break;
+            }
+        }
+
+        'connection: while let Some(Ok(message)) = socket.next().await {
+            let Message::Text(text) = message else {
+                continue;
+            };
+
+            for line in text.lines() {
+                let Some(irc_message) = IrcMessage::parse(line) else {
+                    continue;
+                };
+
+                match irc_message.command {
+                    "PING" => {
+                        let pong = format!("PONG :{}", irc_message.trailing().unwrap_or_default());
+                        let _ = socket.send(Message::Text(pong)).await;
+                    }
+                    "RECONNECT" => break 'connection,
+                    _ => {
+                        if let Some(event) = irc_message.to_chat_event() {
+                            if events.send(event).await.is_err() {
+                                return;
+                            }
+                        }
+                    }
+                }
+            }
+        }
+
+        tracing::warn!("chat connection lost, reconnecting");
+        sleep(RECONNECT_DELAY).await;
+    }
+}
+
+struct IrcMessage<'a> {
+    tags: HashMap<&'a str, String>,
+    prefix: Option<&'a str>,
+    command: &'a str,
+    params: Vec<&'a str>,
+}
+
+impl<'a> IrcMessage<'a> {
+    fn parse(line: &'a str) -> Option<Self> {
+        let mut rest = line.trim_end_matches(['\r', '\n']);
+
+        let mut tags = HashMap::new();
+        if let Some(tagged) = rest.strip_prefix('@') {
+            let (raw_tags, tail) = tagged.split_once(' ')?;
+            for tag in raw_tags.split(';') {
+                let (key, value) = tag.split_once('=').unwrap_or((tag, ""));
+                tags.insert(key, unescape_tag_value(value));
+            }
+            rest = tail;
+        }
+
+        let mut prefix = None;
+        if let Some(prefixed) = rest.strip_prefix(':') {
+            let (raw_prefix, tail) = prefixed.split_once(' ')?;
+            prefix = Some(raw_prefix);
+            rest = tail;
+        }
+
+        let (head, trailing) = match rest.split_once(" :") {
+            Some((head, trailing)) => (head, Some(trailing)),
+            None => (rest, None),
+        };
+        let mut words = head.split(' ').filter(|word| !word.is_empty());
+        let command = words.next()?;
+        let mut params: Vec<&str> = words.collect();
+        params.extend(trailing);
+
+        Some(IrcMessage {
+            tags,
+            prefix,
+            command,
+            params,
+        })
+    }
+
+    fn trailing(&self) -> Option<&'a str> {
+        self.params.last().copied()
+    }
+
+    fn tag(&self, key: &str) -> Option<&str> {
+        self.tags
+            .get(key)
+            .map(|value| value.as_str())
+            .filter(|value| !value.is_empty())
+    }
+
+    fn channel(&self) -> Option<String> {
+        self.params
+            .first()
+            .and_then(|channel| channel.strip_prefix('#'))
+            .map(str::to_string)
+    }
+
+    fn to_chat_event(&self) -> Option<twitch::ChatEvent> {
+        match self.command {
+            "PRIVMSG" => self
+                .to_chat_message()
+                .map(|message| twitch::ChatEvent::Message(Box::new(message))),
+            "CLEARMSG" => Some(twitch::ChatEvent::ClearMessage {
+                channel: self.channel()?,
+                message_id: self.tag("target-msg-id")?.to_string(),
+            }),
+            "CLEARCHAT" => Some(twitch::ChatEvent::ClearChat {
+                channel: self.channel()?,
+                user_id: self.tag("target-user-id").map(str::to_string),
+            }),
+            _ => None,
+        }
+    }
+
+    fn to_chat_message(&self) -> Option<twitch::ChatMessage> {
+        let login = self.prefix?.split('!').next()?.to_string();
+        let text = self.params.get(1)?;
+        // `/me` messages are sent as CTCP ACTION.
+        let text = text
+            .strip_prefix("\u{1}ACTION ")
+            .and_then(|text| text.strip_suffix('\u{1}'))
+            .unwrap_or(text);
+
+        let sent_at = self
+            .tag("tmi-sent-ts")
+            .and_then(|timestamp| timestamp.parse::<i64>().ok())
+            .and_then(|timestamp| Utc.timestamp_millis_opt(timestamp).single())
+            .unwrap_or_else(Utc::now);
+
+        let badges = self
+            .tag("badges")
+            .unwrap_or_default()
+            .split(',')
+            .filter_map(|badge| badge.split_once('/'))
+            .map(|(set, version)| twitch::ChatBadge {
+                set: set.to_string(),
+                version: version.to_string(),
+            })
+            .collect();
+
+        Some(twitch::ChatMessage {
+            id: self.tag("id")?.to_string(),
+            channel: self.channel()?,
+            user_id: self.tag("user-id")?.to_string(),
+            display_name: self.tag("display-name").unwrap_or(&login).to_string(),
+            login,
+            color: self.tag("color").map(str::to_string),
+            badges,
+            text: text.to_string(),
+            emotes: self.tag("emotes").unwrap_or_default().to_string(),
+            bits: self.tag("bits").and_then(|bits| bits.parse::<i64>().ok()),
+            reward_id: self.tag("custom-reward-id").map(str::to_string),
+            first_message: self.tag("first-msg") == Some("1"),
+            sent_at,
+        })
+    }
+}
+
+fn unescape_tag_value(value: &str) -> String {
+    let mut unescaped = String::with_capacity(value.len());
+    let mut chars = value.chars();
+
+    while let Some(c) = chars.next() {
+        if c != '\\' {
+            unescaped.push(c);
+            continue;
+        }
+
+        match chars.next() {
+            Some(':') => unescaped.push(';'),
+            Some('s') => unescaped.push(' '),
+            Some('r') => unescaped.push('\r'),
+            Some('n') => unescaped.push('\n'),
+            Some(c) => unescaped.push(c),
+            None => {}
+        }
+    }
+
+    unescaped
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::{TimeZone, Utc};
+
+    use types::twitch;
+
+    use crate::chat::IrcMessage;
+
+    #[test]
+    fn parse_privmsg() {
+        let line = "@badge-info=;badges=broadcaster/1,premium/1;bits=100;color=#FF4500;display-name=Streamer;emotes=25:0-4;first-msg=0;id=b34ccfc7-4977-403a-8a94-33c6bac34fb8;tmi-sent-ts=1697112000000;user-id=1337 :streamer!streamer@streamer.tmi.twitch.tv PRIVMSG #streamer :Kappa cheer100 hello\\sworld";
+
+        let event = IrcMessage::parse(line).unwrap().to_chat_event();
+
+        assert_eq!(
+            event,
+            Some(twitch::ChatEvent::Message(Box::new(twitch::ChatMessage {
+                id: "b34ccfc7-4977-403a-8a94-33c6bac34fb8".to_string(),
+                channel: "streamer".to_string(),
+                user_id: "1337".to_string(),
+                login: "streamer".to_string(),
+                display_name: "Streamer".to_string(),
+                color: Some("#FF4500".to_string()),
+                badges: vec![
+                    twitch::ChatBadge {
+                        set: "broadcaster".to_string(),
+                        version: "1".to_string(),
+                    },
+                    twitch::ChatBadge {
+                        set: "premium".to_string(),
+                        version: "1".to_string(),
+                    },
+                ],
+                text: "Kappa cheer100 hello\\sworld".to_string(),
+                emotes: "25:0-4".to_string(),
+                bits: Some(100),
+                reward_id: None,
+                first_message: false,
+                sent_at: Utc.timestamp_millis_opt(1697112000000).unwrap(),
+            })))
+        );
+    }
+
+    #[test]
+    fn parse_moderation() {
+        let clear_message = "@login=viewer;room-id=;target-msg-id=abc;tmi-sent-ts=1697112000000 :tmi.twitch.tv CLEARMSG #streamer :bad message";
+        let timeout = "@ban-duration=600;room-id=1337;target-user-id=42;tmi-sent-ts=1697112000000 :tmi.twitch.tv CLEARCHAT #streamer :viewer";
+        let clear_chat =
+            "@room-id=1337;tmi-sent-ts=1697112000000 :tmi.twitch.tv CLEARCHAT #streamer";
+
+        assert_eq!(
+            IrcMessage::parse(clear_message).unwrap().to_chat_event(),
+            Some(twitch::ChatEvent::ClearMessage {
+                channel: "streamer".to_string(),
+                message_id: "abc".to_string(),
+            })
+        );
+        assert_eq!(
+            IrcMessage::parse(timeout).unwrap().to_chat_event(),
+            Some(twitch::ChatEvent::ClearChat {
+                channel: "streamer".to_string(),
+                user_id: Some("42".to_string()),
+            })
+        );
+        assert_eq!(
+            IrcMessage::parse(clear_chat).unwrap().to_chat_event(),
+            Some(twitch::ChatEvent::ClearChat {
+                channel: "streamer".to_string(),
+                user_id: None,
+            })
+        );
+    }
+}
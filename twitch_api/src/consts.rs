@@ -1,3 +1,5 @@
 pub const AUTHORIZE_URL: &str = "https://id.twitch.tv/oauth2/authorize";
 pub const TOKEN_URL: &str = "https://id.twitch.tv/oauth2/token";
 pub const HELIX_URL: &str = "https://api.twitch.tv/helix";
+pub const EVENTSUB_WEBSOCKET_URL: &str = "wss://eventsub.wss.twitch.tv/ws";
+pub const CHAT_WEBSOCKET_URL: &str = "wss://irc-ws.chat.twitch.tv:443";
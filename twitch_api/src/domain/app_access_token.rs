@@ -12,7 +12,7 @@ impl AppAccessToken {
     pub fn new(token: &str, expired_at: &NaiveDateTime) -> Self {
         AppAccessToken {
             token: Some(token.to_string()),
-            expired_at: expired_at.clone(),
+            expired_at: *expired_at,
         }
     }
 
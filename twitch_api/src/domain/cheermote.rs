@@ -0,0 +1,69 @@
+use std::collections::HashMap;
+
+use serde::Deserialize;
+
+use types::twitch;
+
+#[derive(Deserialize, Debug)]
+pub struct Cheermote {
+    prefix: String,
+    tiers: Vec<CheermoteTier>,
+}
+
+#[derive(Deserialize, Debug)]
+pub struct CheermoteTier {
+    id: String,
+    min_bits: i64,
+    color: String,
+    images: CheermoteImages,
+}
+
+#[derive(Deserialize, Debug)]
+pub struct CheermoteImages {
+    dark: CheermoteThemeImages,
+}
+
+/// Image urls keyed by scale: "1", "1.5", "2", "3" and "4".
+#[derive(Deserialize, Debug)]
+pub struct CheermoteThemeImages {
+    animated: HashMap<String, String>,
+    #[serde(rename = "static")]
+    still: HashMap<String, String>,
+}
+
+impl Cheermote {
+    pub fn to_twitch_cheermote(&self) -> twitch::Cheermote {
+        twitch::Cheermote {
+            prefix: self.prefix.clone(),
+            tiers: self
+                .tiers
+                .iter()
+                .map(|tier| tier.to_twitch_cheermote_tier())
+                .collect(),
+        }
+    }
+}
+
+impl CheermoteTier {
+    fn to_twitch_cheermote_tier(&self) -> twitch::CheermoteTier {
+        twitch::CheermoteTier {
+            id: self.id.clone(),
+            min_bits: self.min_bits,
+            color: self.color.clone(),
+            animated_image: largest_image(&self.images.dark.animated),
+            static_image: largest_image(&self.images.dark.still),
+        }
+    }
+}
+
+fn largest_image(images: &HashMap<String, String>) -> String {
+    images
+        .iter()
+        .max_by(|(a, _), (b, _)| {
+            let a = a.parse::<f64>().unwrap_or_default();
+            let b = b.parse::<f64>().unwrap_or_default();
+            a.total_cmp(&b)
+        })
+        .map(|(_, url)| url.clone())
+        .unwrap_or_default()
+}
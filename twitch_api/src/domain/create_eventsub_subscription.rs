@@ -0,0 +1,26 @@
+use std::collections::HashMap;
+
+use serde::{Deserialize, Serialize};
+
+use crate::domain::EventSubSubscription;
+
+#[derive(Serialize, Debug)]
+pub struct CreateEventSubSubscriptionRequest<'a> {
+    #[serde(rename = "type")]
+    pub subscription_type: &'a str,
+    pub version: &'a str,
+    pub condition: HashMap<&'static str, &'a str>,
+    pub transport: &'a EventSubTransport,
+}
+
+#[derive(Serialize, Debug, Clone, PartialEq)]
+#[serde(tag = "method", rename_all = "lowercase")]
+pub enum EventSubTransport {
+    Websocket { session_id: String },
+    Webhook { callback: String, secret: String },
+}
+
+#[derive(Deserialize, Debug)]
+pub struct CreateEventSubSubscriptionResponse {
+    pub data: Vec<EventSubSubscription>,
+}
@@ -0,0 +1,37 @@
+use serde::Deserialize;
+
+use types::twitch;
+
+#[derive(Deserialize, Debug)]
+pub struct CustomReward {
+    id: String,
+    title: String,
+    prompt: String,
+    cost: i64,
+    background_color: String,
+    is_enabled: bool,
+    image: Option<CustomRewardImage>,
+    default_image: CustomRewardImage,
+}
+
+#[derive(Deserialize, Debug)]
+pub struct CustomRewardImage {
+    url_4x: String,
+}
+
+impl CustomReward {
+    pub fn to_twitch_reward(&self) -> twitch::Reward {
+        // Rewards without an uploaded image fall back to the Twitch default one.
+        let image = self.image.as_ref().unwrap_or(&self.default_image);
+
+        twitch::Reward {
+            id: self.id.clone(),
+            title: self.title.clone(),
+            prompt: self.prompt.clone(),
+            cost: self.cost,
+            background_color: self.background_color.clone(),
+            is_enabled: self.is_enabled,
+            image: image.url_4x.clone(),
+        }
+    }
+}
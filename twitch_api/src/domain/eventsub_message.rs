@@ -0,0 +1,64 @@
+use serde::Deserialize;
+use serde_json::{json, Value};
+
+use types::twitch;
+
+use crate::domain::SubscriptionType;
+
+#[derive(Deserialize, Debug)]
+pub struct EventSubMessage {
+    pub metadata: EventSubMetadata,
+    pub payload: EventSubPayload,
+}
+
+#[derive(Deserialize, Debug)]
+pub struct EventSubMetadata {
+    pub message_id: String,
+    pub message_type: String,
+}
+
+#[derive(Deserialize, Debug, Default)]
+pub struct EventSubPayload {
+    pub session: Option<EventSubSession>,
+    pub subscription: Option<EventSubSubscription>,
+    pub event: Option<Value>,
+    pub challenge: Option<String>,
+}
+
+#[derive(Deserialize, Debug, Clone)]
+pub struct EventSubSession {
+    pub id: String,
+    pub keepalive_timeout_seconds: Option<u64>,
+    pub reconnect_url: Option<String>,
+}
+
+#[derive(Deserialize, Debug, Clone)]
+pub struct EventSubSubscription {
+    pub id: String,
+    #[serde(rename = "type")]
+    pub subscription_type: String,
+    pub version: String,
+    pub status: String,
+}
+
+impl EventSubPayload {
+    /// Decodes the notification event. Returns `None` for subscription types
+    /// this crate does not know about.
+    pub fn to_twitch_event(&self) -> Result<Option<twitch::Event>, serde_json::Error> {
+        let (subscription, event) = match (&self.subscription, &self.event) {
+            (Some(subscription), Some(event)) => (subscription, event),
+            _ => return Ok(None),
+        };
+
+        if SubscriptionType::from_name(&subscription.subscription_type).is_none() {
+            return Ok(None);
+        }
+
+        let event = serde_json::from_value::<twitch::Event>(json!({
+            "type": subscription.subscription_type,
+            "event": event,
+        }))?;
+
+        Ok(Some(event))
+    }
+}
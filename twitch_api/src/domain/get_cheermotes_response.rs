@@ -0,0 +1,19 @@
+use serde::Deserialize;
+
+use types::twitch;
+
+use crate::domain::Cheermote;
+
+#[derive(Deserialize, Debug)]
+pub struct GetCheermotesResponse {
+    data: Vec<Cheermote>,
+}
+
+impl GetCheermotesResponse {
+    pub fn to_twitch_cheermotes(&self) -> Vec<twitch::Cheermote> {
+        self.data
+            .iter()
+            .map(|cheermote| cheermote.to_twitch_cheermote())
+            .collect()
+    }
+}
@@ -0,0 +1,19 @@
+use serde::Deserialize;
+
+use types::twitch;
+
+use crate::domain::CustomReward;
+
+#[derive(Deserialize, Debug)]
+pub struct GetCustomRewardsResponse {
+    data: Vec<CustomReward>,
+}
+
+impl GetCustomRewardsResponse {
+    pub fn to_twitch_rewards(&self) -> Vec<twitch::Reward> {
+        self.data
+            .iter()
+            .map(|reward| reward.to_twitch_reward())
+            .collect()
+    }
+}
@@ -4,4 +4,5 @@ use serde::Deserialize;
 pub struct GetUserTokenResponse {
     pub access_token: String,
     pub refresh_token: String,
+    pub expires_in: i64,
 }
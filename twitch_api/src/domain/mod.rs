@@ -1,21 +1,37 @@
 pub use app_access_token::*;
 pub use badge::*;
 pub use badge_set::*;
+pub use cheermote::*;
+pub use create_eventsub_subscription::*;
+pub use custom_reward::*;
 pub use emote::*;
+pub use eventsub_message::*;
 pub use get_app_access_token_response::*;
 pub use get_badges_response::*;
+pub use get_cheermotes_response::*;
+pub use get_custom_rewards_response::*;
 pub use get_emotes_response::*;
+pub use get_page_response::*;
 pub use get_user_info_response::*;
 pub use get_user_token_response::*;
 pub use scope::*;
+pub use subscription_type::*;
 
 mod app_access_token;
 mod badge;
 mod badge_set;
+mod cheermote;
+mod create_eventsub_subscription;
+mod custom_reward;
 mod emote;
+mod eventsub_message;
 mod get_app_access_token_response;
 mod get_badges_response;
+mod get_cheermotes_response;
+mod get_custom_rewards_response;
 mod get_emotes_response;
+mod get_page_response;
 mod get_user_info_response;
 mod get_user_token_response;
 mod scope;
+mod subscription_type;
@@ -74,6 +74,7 @@ scopes! {
     (USER_READ_BLOCKED_USERS, "user:read:blocked_users");
     (USER_MANAGE_BLOCKED_USERS, "user:manage:blocked_users");
     (USER_READ_BROADCAST, "user:read:broadcast");
+    (USER_READ_CHAT, "user:read:chat");
     (USER_MANAGE_CHAT_COLOR, "user:manage:chat_color");
     (USER_READ_EMAIL, "user:read:email");
     (USER_READ_FOLLOWS, "user:read:follows");
@@ -0,0 +1,66 @@
+use std::collections::HashMap;
+
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct SubscriptionType {
+    name: &'static str,
+    version: &'static str,
+    condition: &'static [&'static str],
+}
+
+impl SubscriptionType {
+    pub fn name(&self) -> &'static str {
+        self.name
+    }
+
+    pub fn version(&self) -> &'static str {
+        self.version
+    }
+
+    /// Builds the subscription condition for the broadcaster, who is also
+    /// the owner of the user token used to create the subscription.
+    pub fn condition<'a>(&self, broadcaster_id: &'a str) -> HashMap<&'static str, &'a str> {
+        self.condition
+            .iter()
+            .map(|key| (*key, broadcaster_id))
+            .collect()
+    }
+
+    pub fn from_name(name: &str) -> Option<SubscriptionType> {
+        SubscriptionType::ALL
+            .iter()
+            .find(|subscription_type| subscription_type.name == name)
+            .copied()
+    }
+}
+
+macro_rules! subscription_types {
+    (
+        $(
+            $(#[$docs:meta])*
+            ($name:ident, $type_name:expr, $version:expr, [$($condition:expr),+]);
+        )+
+    ) => {
+        impl SubscriptionType {
+        $(
+            $(#[$docs])*
+            pub const $name: SubscriptionType = SubscriptionType {
+                name: $type_name,
+                version: $version,
+                condition: &[$($condition),+],
+            };
+        )+
+
+            pub const ALL: &'static [SubscriptionType] = &[$(SubscriptionType::$name),+];
+        }
+    }
+}
+
+subscription_types! {
+    (CHANNEL_POINTS_REDEMPTION, "channel.channel_points_custom_reward_redemption.add", "1", ["broadcaster_user_id"]);
+    (CHANNEL_FOLLOW, "channel.follow", "2", ["broadcaster_user_id", "moderator_user_id"]);
+    (CHANNEL_SUBSCRIBE, "channel.subscribe", "1", ["broadcaster_user_id"]);
+    (CHANNEL_CHEER, "channel.cheer", "1", ["broadcaster_user_id"]);
+    (CHANNEL_RAID, "channel.raid", "1", ["to_broadcaster_user_id"]);
+    (CHANNEL_CHAT_CLEAR, "channel.chat.clear", "1", ["broadcaster_user_id", "user_id"]);
+    (CHANNEL_CHAT_MESSAGE_DELETE, "channel.chat.message_delete", "1", ["broadcaster_user_id", "user_id"]);
+}
@@ -0,0 +1,281 @@
+use std::collections::HashMap;
+use std::sync::Mutex;
+
+use axum::http::StatusCode;
+use chrono::{DateTime, Duration, Utc};
+use hmac::{Hmac, Mac};
+use sha2::Sha256;
+use tracing::instrument;
+
+use types::error::{AppError, AppResult};
+use types::twitch;
+
+use crate::domain::EventSubPayload;
+
+const SIGNATURE_PREFIX: &str = "sha256=";
+
+/// Twitch retries deliveries for a while, anything older is treated as a replay.
+fn max_message_age() -> Duration {
+    Duration::minutes(10)
+}
+
+#[derive(Debug, PartialEq)]
+pub enum EventSubWebhookMessage {
+    Verification(String),
+    Notification(Box<twitch::Event>),
+    Ignored,
+}
+
+/// Verifies and decodes requests sent by Twitch to the EventSub webhook callback.
+pub struct EventSubWebhook {
+    secret: String,
+    received_messages: Mutex<HashMap<String, DateTime<Utc>>>,
+}
+
+impl EventSubWebhook {
+    pub fn new(secret: &str) -> Self {
+        EventSubWebhook {
+            secret: secret.to_string(),
+            received_messages: Mutex::new(HashMap::new()),
+        }
+    }
+
+    pub fn secret(&self) -> &str {
+        &self.secret
+    }
+
+    #[instrument(skip(self, signature, body))]
+    pub fn handle(
+        &self,
+        message_id: &str,
+        message_type: &str,
+        timestamp: &str,
+        signature: &str,
+        body: &[u8],
+    ) -> AppResult<EventSubWebhookMessage> {
+        self.verify_signature(message_id, timestamp, signature, body)?;
+
+        let timestamp = DateTime::parse_from_rfc3339(timestamp)
+            .map_err(|e| {
+                EventSubWebhook::INVALID_TIMESTAMP_ERROR
+                    .clone()
+                    .cause(e.into())
+            })?
+            .with_timezone(&Utc);
+
+        if (Utc::now() - timestamp).abs() > max_message_age() {
+            return Err(EventSubWebhook::STALE_MESSAGE_ERROR);
+        }
+
+        if !self.remember_message(message_id, timestamp) {
+            return Ok(EventSubWebhookMessage::Ignored);
+        }
+
+        let payload = serde_json::from_slice::<EventSubPayload>(body).map_err(|e| {
+            EventSubWebhook::INVALID_MESSAGE_ERROR
+                .clone()
+                .cause(e.into())
+        })?;
+
+        match message_type {
+            "webhook_callback_verification" => payload
+                .challenge
+                .map(EventSubWebhookMessage::Verification)
+                .ok_or(EventSubWebhook::INVALID_MESSAGE_ERROR),
+            "notification" => match payload.to_twitch_event() {
+                Ok(Some(event)) => Ok(EventSubWebhookMessage::Notification(Box::new(event))),
+                Ok(None) => Ok(EventSubWebhookMessage::Ignored),
+                Err(e) => Err(EventSubWebhook::INVALID_MESSAGE_ERROR
+                    .clone()
+                    .cause(e.into())),
+            },
+            "revocation" => {
+                tracing::warn!(
+                    {
+                        subscription_type = payload
+                            .subscription
+                            .map(|subscription| subscription.subscription_type)
+                    },
+                    "eventsub subscription revoked"
+                );
+                Ok(EventSubWebhookMessage::Ignored)
+            }
+            _ => Ok(EventSubWebhookMessage::Ignored),
+        }
+    }
+
+    fn verify_signature(
+        &self,
+        message_id: &str,
+        timestamp: &str,
+        signature: &str,
+        body: &[u8],
+    ) -> AppResult {
+        let signature = signature
+            .strip_prefix(SIGNATURE_PREFIX)
+            .and_then(|signature| hex::decode(signature).ok())
+            .ok_or(EventSubWebhook::INVALID_SIGNATURE_ERROR)?;
+
+        let mut mac = Hmac::<Sha256>::new_from_slice(self.secret.as_bytes())
+            .map_err(|e| AppError::UNEXPECTED.clone().cause(e.into()))?;
+        mac.update(message_id.as_bytes());
+        mac.update(timestamp.as_bytes());
+        mac.update(body);
+
+        mac.verify_slice(&signature)
+            .map_err(|_| EventSubWebhook::INVALID_SIGNATURE_ERROR)
+    }
+
+    /// Returns `false` if the message was already received.
+    fn remember_message(&self, message_id: &str, timestamp: DateTime<Utc>) -> bool {
+        let mut received_messages = self.received_messages.lock().unwrap();
+
+        let oldest = Utc::now() - max_message_age();
+        received_messages.retain(|_, received_at| *received_at >= oldest);
+
+        if received_messages.contains_key(message_id) {
+            return false;
+        }
+        received_messages.insert(message_id.to_string(), timestamp);
+
+        true
+    }
+}
+
+macro_rules! eventsub_webhook_errors {
+    (
+        $(
+            $(#[$docs:meta])*
+            ($name:ident, $status:expr, $phrase:expr);
+        )+
+    ) => {
+        impl EventSubWebhook {
+        $(
+            $(#[$docs])*
+            pub const $name: AppError = AppError {
+                status_code: $status,
+                message: Some($phrase),
+                cause: None,
+                other: None
+            };
+        )+
+        }
+    }
+}
+
+eventsub_webhook_errors! {
+    (INVALID_SIGNATURE_ERROR, StatusCode::FORBIDDEN, "invalid eventsub signature");
+    (INVALID_TIMESTAMP_ERROR, StatusCode::BAD_REQUEST, "invalid eventsub timestamp");
+    (STALE_MESSAGE_ERROR, StatusCode::FORBIDDEN, "stale eventsub message");
+    (INVALID_MESSAGE_ERROR, StatusCode::BAD_REQUEST, "invalid eventsub message");
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::{Duration, SecondsFormat, Utc};
+    use hmac::{Hmac, Mac};
+    use sha2::Sha256;
+
+    use crate::{EventSubWebhook, EventSubWebhookMessage};
+
+    const SECRET: &str = "0123456789abcdef";
+    const BODY: &[u8] = br#"{"subscription":{"id":"1","type":"channel.follow","version":"2","status":"webhook_callback_verification_pending"},"challenge":"pogchamp"}"#;
+
+    fn sign(message_id: &str, timestamp: &str, body: &[u8]) -> String {
+        let mut mac = Hmac::<Sha256>::new_from_slice(SECRET.as_bytes()).unwrap();
+        mac.update(message_id.as_bytes());
+        mac.update(timestamp.as_bytes());
+        mac.update(body);
+        format!("sha256={}", hex::encode(mac.finalize().into_bytes()))
+    }
+
+    fn now() -> String {
+        Utc::now().to_rfc3339_opts(SecondsFormat::Millis, true)
+    }
+
+    #[test]
+    fn verification_challenge() {
+        let webhook = EventSubWebhook::new(SECRET);
+        let timestamp = now();
+        let signature = sign("1", &timestamp, BODY);
+
+        let message = webhook.handle(
+            "1",
+            "webhook_callback_verification",
+            &timestamp,
+            &signature,
+            BODY,
+        );
+
+        assert_eq!(
+            message.unwrap(),
+            EventSubWebhookMessage::Verification("pogchamp".to_string())
+        );
+    }
+
+    #[test]
+    fn invalid_signature() {
+        let webhook = EventSubWebhook::new(SECRET);
+        let timestamp = now();
+        let signature = sign("1", &timestamp, b"{}");
+
+        let message = webhook.handle(
+            "1",
+            "webhook_callback_verification",
+            &timestamp,
+            &signature,
+            BODY,
+        );
+
+        assert_eq!(
+            message.unwrap_err().status_code,
+            EventSubWebhook::INVALID_SIGNATURE_ERROR.status_code
+        );
+    }
+
+    #[test]
+    fn replayed_message() {
+        let webhook = EventSubWebhook::new(SECRET);
+        let timestamp = now();
+        let signature = sign("1", &timestamp, BODY);
+
+        let first = webhook.handle(
+            "1",
+            "webhook_callback_verification",
+            &timestamp,
+            &signature,
+            BODY,
+        );
+        let second = webhook.handle(
+            "1",
+            "webhook_callback_verification",
+            &timestamp,
+            &signature,
+            BODY,
+        );
+
+        assert!(first.is_ok());
+        assert_eq!(second.unwrap(), EventSubWebhookMessage::Ignored);
+    }
+
+    #[test]
+    fn stale_message() {
+        let webhook = EventSubWebhook::new(SECRET);
+        let timestamp =
+            (Utc::now() - Duration::minutes(11)).to_rfc3339_opts(SecondsFormat::Millis, true);
+        let signature = sign("1", &timestamp, BODY);
+
+        let message = webhook.handle(
+            "1",
+            "webhook_callback_verification",
+            &timestamp,
+            &signature,
+            BODY,
+        );
+
+        assert_eq!(
+            message.unwrap_err().message,
+            EventSubWebhook::STALE_MESSAGE_ERROR.message
+        );
+    }
+}
@@ -0,0 +1,478 @@
+use std::collections::VecDeque;
+use std::future::pending;
+use std::sync::Arc;
+use std::time::Duration;
+
+use axum::http::StatusCode;
+use futures_util::StreamExt;
+use tokio::net::TcpStream;
+use tokio::sync::mpsc::Sender;
+use tokio::time::{sleep, timeout_at, Instant};
+use tokio_tungstenite::tungstenite::Message;
+use tokio_tungstenite::{connect_async, MaybeTlsStream, WebSocketStream};
+use tracing::instrument;
+
+use types::error::{AppError, AppResult};
+use types::twitch;
+
+use crate::consts::EVENTSUB_WEBSOCKET_URL;
+use crate::domain::{EventSubMessage, EventSubTransport, SubscriptionType};
+use crate::TwitchApi;
+
+type Socket = WebSocketStream<MaybeTlsStream<TcpStream>>;
+
+const DEFAULT_KEEPALIVE_TIMEOUT: Duration = Duration::from_secs(10);
+const KEEPALIVE_GRACE: Duration = Duration::from_secs(5);
+const RECONNECT_DELAY: Duration = Duration::from_secs(5);
+const RECENT_MESSAGE_IDS: usize = 100;
+
+/// EventSub client over WebSocket. Subscriptions are created through Helix with
+/// a user token once the session welcome arrives, so no public callback is needed.
+pub struct EventSubWebSocket {
+    twitch_api: Arc<TwitchApi>,
+    url: String,
+}
+
+impl EventSubWebSocket {
+    pub fn new(twitch_api: Arc<TwitchApi>) -> Self {
+        EventSubWebSocket {
+            twitch_api,
+            url: EVENTSUB_WEBSOCKET_URL.to_string(),
+        }
+    }
+
+    /// Overrides the WebSocket url, e.g. to point at the Twitch CLI mock server.
+    pub fn url(mut self, url: &str) -> Self {
+        self.url = url.to_string();
+        self
+    }
+
+    /// Receives events of `subscription_types` for the broadcaster and sends them
+    /// to `events` until the receiving side is dropped.
+    #[instrument(skip(self, user_token, events))]
+    pub async fn run(
+        &self,
+        user_token: &str,
+        broadcaster_id: &str,
+        subscription_types: &[SubscriptionType],
+        events: Sender<twitch::Event>,
+    ) -> AppResult {
+        let mut socket = self.connect(&self.url).await?;
+        // Set while the session moves to another connection, until its welcome arrives.
+        let mut reconnecting: Option<Socket> = None;
+        let mut keepalive_timeout = DEFAULT_KEEPALIVE_TIMEOUT;
+        let mut is_subscribed = false;
+        let mut recent_message_ids: VecDeque<String> = VecDeque::with_capacity(RECENT_MESSAGE_IDS);
+
+        loop {
+            if events.is_closed() {
+                return Ok(());
+            }
+
+            // Whatever the old connection already received goes first.
+            let message = tokio::select! {
+                biased;
+                message = next_message(&mut socket, keepalive_timeout) => match message {
+                    Some(message) => message,
+                    // Twitch may close the old connection before the new one is welcomed.
+                    None => match reconnecting.take() {
+                        Some(new_socket) => {
+                            socket = new_socket;
+                            continue;
+                        }
+                        None => {
+                            tracing::warn!("eventsub connection lost, reconnecting");
+                            sleep(RECONNECT_DELAY).await;
+                            socket = self.connect(&self.url).await?;
+                            keepalive_timeout = DEFAULT_KEEPALIVE_TIMEOUT;
+                            is_subscribed = false;
+                            continue;
+                        }
+                    },
+                },
+                message = next_reconnecting_message(&mut reconnecting, keepalive_timeout) => match message {
+                    Some(message) => message,
+                    None => {
+                        tracing::warn!("eventsub reconnect failed, staying on the old connection");
+                        reconnecting = None;
+                        continue;
+                    }
+                },
+            };
+
+            let message = match serde_json::from_str::<EventSubMessage>(&message) {
+                Ok(message) => message,
+                Err(e) => {
+                    tracing::warn!({ error = %e }, "fail parse eventsub message");
+                    continue;
+                }
+            };
+
+            // Twitch may deliver the same message more than once.
+            if recent_message_ids.contains(&message.metadata.message_id) {
+                continue;
+            }
+            if recent_message_ids.len() == RECENT_MESSAGE_IDS {
+                recent_message_ids.pop_front();
+            }
+            recent_message_ids.push_back(message.metadata.message_id.clone());
+
+            match message.metadata.message_type.as_str() {
+                "session_welcome" => {
+                    let session = message
+                        .payload
+                        .session
+                        .ok_or(EventSubWebSocket::INVALID_MESSAGE_ERROR)?;
+
+                    if let Some(seconds) = session.keepalive_timeout_seconds {
+                        keepalive_timeout = Duration::from_secs(seconds);
+                    }
+
+                    // The new connection took over the session, the old one can go.
+                    if let Some(new_socket) = reconnecting.take() {
+                        let mut old_socket = std::mem::replace(&mut socket, new_socket);
+                        let _ = old_socket.close(None).await;
+                    }
+
+                    // Subscriptions survive a session_reconnect, so only a fresh session needs them.
+                    if !is_subscribed {
+                        let transport = EventSubTransport::Websocket {
+                            session_id: session.id,
+                        };
+                        for subscription_type in subscription_types {
+                            self.twitch_api
+                                .create_eventsub_subscription(
+                                    user_token,
+                                    subscription_type,
+                                    broadcaster_id,
+                                    &transport,
+                                )
+                                .await?;
+                        }
+                        is_subscribed = true;
+                    }
+                }
+                "session_keepalive" => {}
+                "session_reconnect" => {
+                    let reconnect_url = message
+                        .payload
+                        .session
+                        .and_then(|session| session.reconnect_url)
+                        .ok_or(EventSubWebSocket::INVALID_MESSAGE_ERROR)?;
+
+                    // Events keep coming on the old connection until the new one is welcomed.
+                    match self.connect(&reconnect_url).await {
+                        Ok(new_socket) => reconnecting = Some(new_socket),
+                        Err(e) => tracing::warn!({ error = ?e }, "fail follow eventsub reconnect"),
+                    }
+                }
+                "notification" => match message.payload.to_twitch_event() {
+                    Ok(Some(event)) => {
+                        if events.send(event).await.is_err() {
+                            return Ok(());
+                        }
+                    }
+                    Ok(None) => {}
+                    Err(e) => tracing::warn!({ error = %e }, "fail decode eventsub event"),
+                },
+                "revocation" => {
+                    tracing::warn!(
+                        {
+                            subscription_type = message
+                                .payload
+                                .subscription
+                                .map(|subscription| subscription.subscription_type)
+                        },
+                        "eventsub subscription revoked"
+                    );
+                }
+                message_type => {
+                    tracing::warn!({ message_type }, "unknown eventsub message type");
+                }
+            }
+        }
+    }
+
+    #[instrument(skip(self))]
+    async fn connect(&self, url: &str) -> AppResult<Socket> {
+        let (socket, _) = connect_async(url).await.map_err(|e| {
+            EventSubWebSocket::FAIL_CONNECT_ERROR
+                .clone()
+                .cause(e.into())
+        })?;
+
+        Ok(socket)
+    }
+}
+
+/// Waits for the next text message. Twitch sends at least a keepalive message
+/// within the keepalive timeout, so silence for longer means the connection is dead.
+async fn next_message(socket: &mut Socket, keepalive_timeout: Duration) -> Option<String> {
+    let deadline = Instant::now() + keepalive_timeout + KEEPALIVE_GRACE;
+
+    loop {
+        match timeout_at(deadline, socket.next()).await {
+            Ok(Some(Ok(Message::Text(text)))) => return Some(text),
+            Ok(Some(Ok(Message::Close(_)))) | Ok(Some(Err(_))) | Ok(None) | Err(_) => return None,
+            // Pings are answered by the socket itself and don't count as keepalive.
+            Ok(Some(Ok(_))) => continue,
+        }
+    }
+}
+
+async fn next_reconnecting_message(
+    reconnecting: &mut Option<Socket>,
+    keepalive_timeout: Duration,
+) -> Option<String> {
+    match reconnecting {
+        Some(socket) => next_message(socket, keepalive_timeout).await,
+        None => pending().await,
+    }
+}
+
+macro_rules! eventsub_websocket_errors {
+    (
+        $(
+            $(#[$docs:meta])*
+            ($name:ident, $status:expr, $phrase:expr);
+        )+
+    ) => {
+        impl EventSubWebSocket {
+        $(
+            $(#[$docs])*
+            pub const $name: AppError = AppError {
+                status_code: $status,
+                message: Some($phrase),
+                cause: None,
+                other: None
+            };
+        )+
+        }
+    }
+}
+
+eventsub_websocket_errors! {
+    (FAIL_CONNECT_ERROR, StatusCode::INTERNAL_SERVER_ERROR, "fail connect to eventsub");
+    (INVALID_MESSAGE_ERROR, StatusCode::INTERNAL_SERVER_ERROR, "invalid eventsub message");
+}
+
+#[cfg(test)]
+mod tests {
+    use std::net::TcpListener as StdTcpListener;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use std::sync::Arc;
+
+    use axum::extract::State;
+    use axum::routing::post;
+    use axum::{Json, Router, Server};
+    use futures_util::{SinkExt, StreamExt};
+    use serde_json::{json, Value};
+    use tokio::net::TcpListener;
+    use tokio::sync::mpsc;
+    use tokio_tungstenite::tungstenite::Message;
+    use tokio_tungstenite::{accept_async, WebSocketStream};
+
+    use config::TwitchConfig;
+    use types::twitch;
+
+    use crate::{EventSubWebSocket, SubscriptionType, TwitchApi};
+
+    async fn create_subscription(
+        State(subscriptions): State<Arc<AtomicUsize>>,
+        Json(body): Json<Value>,
+    ) -> (axum::http::StatusCode, Json<Value>) {
+        subscriptions.fetch_add(1, Ordering::SeqCst);
+
+        let response = json!({
+            "data": [{
+                "id": "subscription-id",
+                "type": body["type"],
+                "version": body["version"],
+                "status": "enabled",
+            }]
+        });
+
+        (axum::http::StatusCode::ACCEPTED, Json(response))
+    }
+
+    /// Fake Helix which counts created subscriptions.
+    fn helix(subscriptions: Arc<AtomicUsize>) -> String {
+        let listener = StdTcpListener::bind("127.0.0.1:0").unwrap();
+        let addr = listener.local_addr().unwrap();
+        let helix = Router::new()
+            .route("/eventsub/subscriptions", post(create_subscription))
+            .with_state(subscriptions);
+        tokio::spawn(
+            Server::from_tcp(listener)
+                .unwrap()
+                .serve(helix.into_make_service()),
+        );
+
+        format!("http://{}", addr)
+    }
+
+    fn eventsub(helix_url: &str, ws_url: &str) -> EventSubWebSocket {
+        let twitch_config =
+            TwitchConfig::new("http://localhost/callback", "client-id", "client-secret");
+        let twitch_api = TwitchApi::new(twitch_config).helix_url(helix_url);
+
+        EventSubWebSocket::new(Arc::new(twitch_api)).url(ws_url)
+    }
+
+    async fn send(socket: &mut WebSocketStream<tokio::net::TcpStream>, message: Value) {
+        socket
+            .send(Message::Text(message.to_string()))
+            .await
+            .unwrap();
+    }
+
+    fn welcome(message_id: &str) -> Value {
+        json!({
+            "metadata": {
+                "message_id": message_id,
+                "message_type": "session_welcome",
+                "message_timestamp": "2023-10-12T12:00:00Z"
+            },
+            "payload": {
+                "session": { "id": "session-id", "keepalive_timeout_seconds": 10 }
+            }
+        })
+    }
+
+    fn raid(message_id: &str, viewers: u64) -> Value {
+        json!({
+            "metadata": {
+                "message_id": message_id,
+                "message_type": "notification",
+                "message_timestamp": "2023-10-12T12:00:01Z"
+            },
+            "payload": {
+                "subscription": {
+                    "id": "subscription-id",
+                    "type": "channel.raid",
+                    "version": "1",
+                    "status": "enabled"
+                },
+                "event": {
+                    "from_broadcaster_user_id": "1",
+                    "from_broadcaster_user_login": "raider",
+                    "from_broadcaster_user_name": "Raider",
+                    "to_broadcaster_user_id": "2",
+                    "to_broadcaster_user_login": "streamer",
+                    "to_broadcaster_user_name": "Streamer",
+                    "viewers": viewers
+                }
+            }
+        })
+    }
+
+    fn raid_event(viewers: i64) -> twitch::Event {
+        twitch::Event::Raid(twitch::RaidEvent {
+            from_broadcaster_user_id: "1".to_string(),
+            from_broadcaster_user_login: "raider".to_string(),
+            from_broadcaster_user_name: "Raider".to_string(),
+            to_broadcaster_user_id: "2".to_string(),
+            to_broadcaster_user_login: "streamer".to_string(),
+            viewers,
+        })
+    }
+
+    #[tokio::test]
+    async fn run_against_fake_server() {
+        let subscriptions = Arc::new(AtomicUsize::new(0));
+        let helix_url = helix(subscriptions.clone());
+
+        let ws_listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let ws_addr = ws_listener.local_addr().unwrap();
+        tokio::spawn(async move {
+            let (stream, _) = ws_listener.accept().await.unwrap();
+            let mut socket = accept_async(stream).await.unwrap();
+            send(&mut socket, welcome("1")).await;
+            send(&mut socket, raid("2", 42)).await;
+            while socket.next().await.is_some() {}
+        });
+
+        let eventsub = eventsub(&helix_url, &format!("ws://{}", ws_addr));
+        let (sender, mut receiver) = mpsc::channel(8);
+        let client = tokio::spawn(async move {
+            eventsub
+                .run("user-token", "2", &[SubscriptionType::CHANNEL_RAID], sender)
+                .await
+        });
+
+        assert_eq!(receiver.recv().await.unwrap(), raid_event(42));
+        assert_eq!(subscriptions.load(Ordering::SeqCst), 1);
+
+        client.abort();
+    }
+
+    #[tokio::test]
+    async fn reconnect_moves_session_after_welcome() {
+        let subscriptions = Arc::new(AtomicUsize::new(0));
+        let helix_url = helix(subscriptions.clone());
+
+        let old_listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let old_addr = old_listener.local_addr().unwrap();
+        let new_listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let new_addr = new_listener.local_addr().unwrap();
+        let (new_welcomed_sender, new_welcomed_receiver) = tokio::sync::oneshot::channel();
+
+        let old_server = tokio::spawn(async move {
+            let (stream, _) = old_listener.accept().await.unwrap();
+            let mut socket = accept_async(stream).await.unwrap();
+            send(&mut socket, welcome("1")).await;
+            send(
+                &mut socket,
+                json!({
+                    "metadata": {
+                        "message_id": "2",
+                        "message_type": "session_reconnect",
+                        "message_timestamp": "2023-10-12T12:00:01Z"
+                    },
+                    "payload": {
+                        "session": {
+                            "id": "session-id",
+                            "reconnect_url": format!("ws://{}", new_addr)
+                        }
+                    }
+                }),
+            )
+            .await;
+            // Still delivered by the old connection until the new one is welcomed.
+            send(&mut socket, raid("3", 1)).await;
+            new_welcomed_receiver.await.unwrap();
+
+            // The client closes the old connection once the new one took over.
+            while let Some(Ok(message)) = socket.next().await {
+                if let Message::Close(_) = message {
+                    break;
+                }
+            }
+        });
+
+        tokio::spawn(async move {
+            let (stream, _) = new_listener.accept().await.unwrap();
+            let mut socket = accept_async(stream).await.unwrap();
+            send(&mut socket, welcome("4")).await;
+            new_welcomed_sender.send(()).unwrap();
+            send(&mut socket, raid("5", 2)).await;
+            while socket.next().await.is_some() {}
+        });
+
+        let eventsub = eventsub(&helix_url, &format!("ws://{}", old_addr));
+        let (sender, mut receiver) = mpsc::channel(8);
+        let client = tokio::spawn(async move {
+            eventsub
+                .run("user-token", "2", &[SubscriptionType::CHANNEL_RAID], sender)
+                .await
+        });
+
+        assert_eq!(receiver.recv().await.unwrap(), raid_event(1));
+        assert_eq!(receiver.recv().await.unwrap(), raid_event(2));
+        old_server.await.unwrap();
+        // Subscriptions move along with the session.
+        assert_eq!(subscriptions.load(Ordering::SeqCst), 1);
+
+        client.abort();
+    }
+}
@@ -1,6 +1,12 @@
 pub use api::*;
-pub use domain::Scope;
+pub use chat::*;
+pub use domain::{EventSubSubscription, EventSubTransport, Scope, SubscriptionType};
+pub use eventsub_webhook::*;
+pub use eventsub_websocket::*;
 
 mod api;
+mod chat;
 mod consts;
 mod domain;
+mod eventsub_webhook;
+mod eventsub_websocket;
@@ -1,3 +1,4 @@
+use chrono::NaiveDateTime;
 use serde::{Deserialize, Serialize};
 use uuid::Uuid;
 use validator::{Validate, ValidationError};
@@ -9,6 +10,8 @@ pub struct BanWordFilter {
     pub name: String,
     pub ban_words: Vec<String>,
     pub user_id: String,
+    /// Incremented on every change, sent as the `ETag` of the filter.
+    pub revision: i32,
 }
 
 #[derive(Serialize, Deserialize, Debug, PartialEq, Clone)]
@@ -17,6 +20,18 @@ pub struct BanWordFilterInfo {
     pub id: Uuid,
     pub name: String,
     pub user_id: String,
+    pub created_at: NaiveDateTime,
+    pub updated_at: NaiveDateTime,
+    /// Set while the filter is in the trash.
+    pub deleted_at: Option<NaiveDateTime>,
+}
+
+/// Chat settings which use a ban word filter.
+#[derive(Serialize, Deserialize, Debug, PartialEq, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct BanWordFilterUsage {
+    pub chat_settings_id: Uuid,
+    pub chat_settings_name: String,
 }
 
 #[derive(Serialize, Deserialize, Validate, Debug, PartialEq, Clone)]
@@ -29,7 +44,7 @@ pub struct UpdateBanWordFilter {
 }
 
 fn ban_word_vec<const MIN: usize, const MAX: usize>(
-    value: &Vec<String>,
+    value: &[String],
 ) -> Result<(), ValidationError> {
     for s in value.iter() {
         if s.len() > MAX {
@@ -0,0 +1,10 @@
+use serde::{Deserialize, Serialize};
+
+use crate::domain::PreviewMessage;
+
+#[derive(Serialize, Deserialize, Debug, PartialEq, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct ChatPreview {
+    pub css: String,
+    pub messages: Vec<PreviewMessage>,
+}
@@ -0,0 +1,9 @@
+pub use chat_preview::*;
+pub use preview_chat_settings::*;
+pub use preview_message::*;
+pub use sample_message::*;
+
+mod chat_preview;
+mod preview_chat_settings;
+mod preview_message;
+mod sample_message;
@@ -0,0 +1,15 @@
+use serde::{Deserialize, Serialize};
+use validator::Validate;
+
+use crate::domain::{SampleMessage, UpdateChatSettings};
+
+#[derive(Serialize, Deserialize, Validate, Debug, PartialEq, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct PreviewChatSettings {
+    #[validate]
+    pub settings: UpdateChatSettings,
+    /// Built-in samples are used when none are supplied.
+    #[validate(length(max = 100))]
+    #[validate]
+    pub messages: Option<Vec<SampleMessage>>,
+}
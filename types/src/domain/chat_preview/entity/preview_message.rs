@@ -0,0 +1,13 @@
+use serde::{Deserialize, Serialize};
+
+use crate::domain::OverlayMessage;
+
+/// Sample message after the hide settings. Hidden ones are kept as they were
+/// received, so the editor can show what got hidden.
+#[derive(Serialize, Deserialize, Debug, PartialEq, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct PreviewMessage {
+    #[serde(flatten)]
+    pub message: OverlayMessage,
+    pub hidden: bool,
+}
@@ -0,0 +1,32 @@
+use serde::{Deserialize, Serialize};
+use validator::Validate;
+
+/// Chat message to preview settings with, as it would be read from Twitch.
+#[derive(Serialize, Deserialize, Validate, Debug, PartialEq, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct SampleMessage {
+    #[validate(length(min = 1, max = 25))]
+    pub login: String,
+    /// Custom nicknames bound to a Twitch user id match on it.
+    #[serde(default)]
+    #[validate(length(min = 1, max = 32))]
+    pub user_id: Option<String>,
+    #[validate(length(min = 1, max = 25))]
+    pub display_name: Option<String>,
+    pub color: Option<String>,
+    #[validate(length(min = 1, max = 500))]
+    pub text: String,
+    /// IRC `emotes` tag, e.g. `25:0-4`.
+    #[serde(default)]
+    pub emotes: String,
+    #[validate(range(min = 1))]
+    pub bits: Option<i64>,
+    #[validate(length(min = 1, max = 64))]
+    pub reward_id: Option<String>,
+    /// IRC `badges` tag, e.g. `moderator/1,subscriber/3012`.
+    #[serde(default)]
+    #[validate(length(max = 500))]
+    pub badges: String,
+    #[serde(default)]
+    pub first_message: bool,
+}
@@ -0,0 +1,67 @@
+use std::error::Error;
+use std::fmt::{self, Display, Formatter};
+use std::str::FromStr;
+
+use serde::{Deserialize, Serialize};
+
+/// Animation of a message entering or leaving the chat. Slides are named
+/// after the direction the message moves in.
+#[derive(Serialize, Deserialize, Debug, PartialEq, Eq, Clone, Copy)]
+pub enum ChatAnimation {
+    #[serde(rename = "none")]
+    None,
+    #[serde(rename = "fade")]
+    Fade,
+    #[serde(rename = "slide-up")]
+    SlideUp,
+    #[serde(rename = "slide-down")]
+    SlideDown,
+    #[serde(rename = "slide-left")]
+    SlideLeft,
+    #[serde(rename = "slide-right")]
+    SlideRight,
+    #[serde(rename = "scale")]
+    Scale,
+}
+
+impl ChatAnimation {
+    pub fn to_str(&self) -> &str {
+        match *self {
+            ChatAnimation::None => "none",
+            ChatAnimation::Fade => "fade",
+            ChatAnimation::SlideUp => "slide-up",
+            ChatAnimation::SlideDown => "slide-down",
+            ChatAnimation::SlideLeft => "slide-left",
+            ChatAnimation::SlideRight => "slide-right",
+            ChatAnimation::Scale => "scale",
+        }
+    }
+}
+
+impl FromStr for ChatAnimation {
+    type Err = UnknownChatAnimation;
+
+    fn from_str(animation: &str) -> Result<Self, Self::Err> {
+        match animation {
+            "none" => Ok(ChatAnimation::None),
+            "fade" => Ok(ChatAnimation::Fade),
+            "slide-up" => Ok(ChatAnimation::SlideUp),
+            "slide-down" => Ok(ChatAnimation::SlideDown),
+            "slide-left" => Ok(ChatAnimation::SlideLeft),
+            "slide-right" => Ok(ChatAnimation::SlideRight),
+            "scale" => Ok(ChatAnimation::Scale),
+            _ => Err(UnknownChatAnimation(animation.to_string())),
+        }
+    }
+}
+
+#[derive(Debug, PartialEq, Clone)]
+pub struct UnknownChatAnimation(pub String);
+
+impl Display for UnknownChatAnimation {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        write!(f, "unknown chat animation \"{}\"", self.0)
+    }
+}
+
+impl Error for UnknownChatAnimation {}
@@ -0,0 +1,17 @@
+use serde::{Deserialize, Serialize};
+
+use crate::domain::{ChatAnimation, StackingDirection};
+
+#[derive(Serialize, Deserialize, Debug, PartialEq, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct ChatAnimationSettings {
+    /// Seconds a message stays in the chat, `0` keeps it until it's pushed out.
+    pub message_ttl: i32,
+    pub enter_animation: ChatAnimation,
+    /// Milliseconds.
+    pub enter_duration: i32,
+    pub exit_animation: ChatAnimation,
+    /// Milliseconds.
+    pub exit_duration: i32,
+    pub stacking_direction: StackingDirection,
+}
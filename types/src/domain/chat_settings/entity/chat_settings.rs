@@ -2,7 +2,8 @@ use serde::{Deserialize, Serialize};
 use uuid::Uuid;
 
 use crate::domain::{
-    ChatColorSettings, ChatFontSettings, ChatHideSettings, ChatSizeSettings, ChatType,
+    ChatAnimationSettings, ChatColorSettings, ChatFontSettings, ChatHideSettings, ChatSizeSettings,
+    ChatType, ChatTypeOptions,
 };
 
 #[derive(Serialize, Deserialize, Debug, PartialEq, Clone)]
@@ -11,9 +12,15 @@ pub struct ChatSettings {
     pub id: Uuid,
     pub name: String,
     pub chat_type: ChatType,
+    pub chat_type_options: ChatTypeOptions,
     pub color: ChatColorSettings,
     pub size: ChatSizeSettings,
     pub hide: ChatHideSettings,
     pub font: ChatFontSettings,
+    pub animation: ChatAnimationSettings,
     pub user_id: String,
+    /// Incremented on every change, sent as the `ETag` of the settings.
+    /// Snapshots saved before it was added have none.
+    #[serde(default)]
+    pub revision: i32,
 }
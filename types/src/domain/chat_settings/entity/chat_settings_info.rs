@@ -1,3 +1,4 @@
+use chrono::NaiveDateTime;
 use serde::{Deserialize, Serialize};
 use uuid::Uuid;
 
@@ -10,4 +11,8 @@ pub struct ChatSettingsInfo {
     pub name: String,
     pub chat_type: ChatType,
     pub user_id: String,
+    pub created_at: NaiveDateTime,
+    pub updated_at: NaiveDateTime,
+    /// Set while the chat settings are in the trash.
+    pub deleted_at: Option<NaiveDateTime>,
 }
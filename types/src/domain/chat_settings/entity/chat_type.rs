@@ -1,3 +1,7 @@
+use std::error::Error;
+use std::fmt::{self, Display, Formatter};
+use std::str::FromStr;
+
 use serde::{Deserialize, Serialize};
 
 #[derive(Serialize, Deserialize, Debug, PartialEq, Clone)]
@@ -14,20 +18,21 @@ pub enum ChatType {
     AlternativeBlock,
     #[serde(rename = "alternative-block-reverse")]
     AlternativeBlockReverse,
+    /// Messages scroll horizontally on a single line.
+    #[serde(rename = "ticker")]
+    Ticker,
+    /// Speech bubbles, optionally on alternating sides.
+    #[serde(rename = "bubble")]
+    Bubble,
+    /// Dense lines without gaps between messages.
+    #[serde(rename = "compact")]
+    Compact,
+    /// Vertical stream of the chatter names.
+    #[serde(rename = "name-stream")]
+    NameStream,
 }
 
 impl ChatType {
-    pub fn from_str(chat_type: &str) -> ChatType {
-        match chat_type {
-            "default-reverse" => ChatType::DefaultReverse,
-            "block" => ChatType::Block,
-            "block-reverse" => ChatType::BlockReverse,
-            "alternative-block" => ChatType::AlternativeBlock,
-            "alternative-block-reverse" => ChatType::AlternativeBlockReverse,
-            _ => ChatType::Default,
-        }
-    }
-
     pub fn to_str(&self) -> &str {
         match *self {
             ChatType::Default => "default",
@@ -36,6 +41,76 @@ impl ChatType {
             ChatType::BlockReverse => "block-reverse",
             ChatType::AlternativeBlock => "alternative-block",
             ChatType::AlternativeBlockReverse => "alternative-block-reverse",
+            ChatType::Ticker => "ticker",
+            ChatType::Bubble => "bubble",
+            ChatType::Compact => "compact",
+            ChatType::NameStream => "name-stream",
+        }
+    }
+}
+
+impl FromStr for ChatType {
+    type Err = UnknownChatType;
+
+    fn from_str(chat_type: &str) -> Result<Self, Self::Err> {
+        match chat_type {
+            "default" => Ok(ChatType::Default),
+            "default-reverse" => Ok(ChatType::DefaultReverse),
+            "block" => Ok(ChatType::Block),
+            "block-reverse" => Ok(ChatType::BlockReverse),
+            "alternative-block" => Ok(ChatType::AlternativeBlock),
+            "alternative-block-reverse" => Ok(ChatType::AlternativeBlockReverse),
+            "ticker" => Ok(ChatType::Ticker),
+            "bubble" => Ok(ChatType::Bubble),
+            "compact" => Ok(ChatType::Compact),
+            "name-stream" => Ok(ChatType::NameStream),
+            _ => Err(UnknownChatType(chat_type.to_string())),
+        }
+    }
+}
+
+#[derive(Debug, PartialEq, Clone)]
+pub struct UnknownChatType(pub String);
+
+impl Display for UnknownChatType {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        write!(f, "unknown chat type \"{}\"", self.0)
+    }
+}
+
+impl Error for UnknownChatType {}
+
+#[cfg(test)]
+mod tests {
+    use crate::domain::ChatType;
+
+    #[test]
+    fn str_round_trip() {
+        for chat_type in [
+            ChatType::Default,
+            ChatType::DefaultReverse,
+            ChatType::Block,
+            ChatType::BlockReverse,
+            ChatType::AlternativeBlock,
+            ChatType::AlternativeBlockReverse,
+            ChatType::Ticker,
+            ChatType::Bubble,
+            ChatType::Compact,
+            ChatType::NameStream,
+        ] {
+            assert_eq!(chat_type.to_str().parse(), Ok(chat_type.clone()));
+            // Stored and sent the same way.
+            assert_eq!(
+                serde_json::to_string(&chat_type).unwrap(),
+                format!("\"{}\"", chat_type.to_str())
+            );
+        }
+    }
+
+    #[test]
+    fn unknown() {
+        for chat_type in ["", "Default", "bubbles", "default "] {
+            assert!(chat_type.parse::<ChatType>().is_err(), "{}", chat_type);
         }
     }
 }
@@ -0,0 +1,94 @@
+use serde::{Deserialize, Serialize};
+use validator::Validate;
+
+/// Options of the chat types needing more than the common settings. Every
+/// block is kept, so switching the type back restores its options.
+#[derive(Serialize, Deserialize, Validate, Debug, PartialEq, Clone, Default)]
+#[serde(rename_all = "camelCase", default)]
+pub struct ChatTypeOptions {
+    #[validate]
+    pub ticker: TickerOptions,
+    #[validate]
+    pub bubble: BubbleOptions,
+    #[validate]
+    pub compact: CompactOptions,
+    #[validate]
+    pub name_stream: NameStreamOptions,
+}
+
+#[derive(Serialize, Deserialize, Validate, Debug, PartialEq, Clone)]
+#[serde(rename_all = "camelCase", default)]
+pub struct TickerOptions {
+    /// Scrolled distance per second, in the same unit as the sizes.
+    #[validate(range(min = 1, max = 100))]
+    pub speed: f64,
+    #[validate(range(min = 0, max = 100))]
+    pub gap: f64,
+}
+
+impl Default for TickerOptions {
+    fn default() -> Self {
+        TickerOptions {
+            speed: 10.0,
+            gap: 4.0,
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Validate, Debug, PartialEq, Clone)]
+#[serde(rename_all = "camelCase", default)]
+pub struct BubbleOptions {
+    /// Percent of the overlay width.
+    #[validate(range(min = 10, max = 100))]
+    pub max_width: f64,
+    pub alternate_sides: bool,
+    pub show_tail: bool,
+}
+
+impl Default for BubbleOptions {
+    fn default() -> Self {
+        BubbleOptions {
+            max_width: 80.0,
+            alternate_sides: false,
+            show_tail: true,
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Validate, Debug, PartialEq, Clone)]
+#[serde(rename_all = "camelCase", default)]
+pub struct CompactOptions {
+    /// Longer messages are cut with an ellipsis.
+    #[validate(range(min = 1, max = 10))]
+    pub max_lines: i32,
+    /// Space between messages, replacing their margins.
+    #[validate(range(min = 0, max = 100))]
+    pub spacing: f64,
+}
+
+impl Default for CompactOptions {
+    fn default() -> Self {
+        CompactOptions {
+            max_lines: 2,
+            spacing: 0.0,
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Validate, Debug, PartialEq, Clone)]
+#[serde(rename_all = "camelCase", default)]
+pub struct NameStreamOptions {
+    pub show_text: bool,
+    /// A new message moves the name of its chatter to the end instead of
+    /// showing it twice.
+    pub unique_names: bool,
+}
+
+impl Default for NameStreamOptions {
+    fn default() -> Self {
+        NameStreamOptions {
+            show_text: false,
+            unique_names: true,
+        }
+    }
+}
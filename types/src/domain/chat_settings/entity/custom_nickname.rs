@@ -4,6 +4,22 @@ use serde::{Deserialize, Serialize};
 #[serde(rename_all = "camelCase")]
 pub struct CustomNickname {
     pub nickname: String,
+    /// Twitch user id of the nickname, so the style follows the viewer after
+    /// a rename. Resolved from the nickname when the settings are saved.
+    #[serde(default)]
+    pub user_id: Option<String>,
     pub start_color: i64,
+    /// Colors between the start and end ones, spread evenly.
+    #[serde(default)]
+    pub middle_colors: Vec<i64>,
     pub end_color: i64,
+    /// Shown instead of the Twitch display name.
+    #[serde(default)]
+    pub display_name: Option<String>,
+    /// Image shown before the name.
+    #[serde(default)]
+    pub badge_url: Option<String>,
+    /// Overrides the nickname font weight of the settings.
+    #[serde(default)]
+    pub font_weight: Option<i32>,
 }
@@ -1,3 +1,5 @@
+pub use chat_animation::*;
+pub use chat_animation_settings::*;
 pub use chat_color_settings::*;
 pub use chat_font_settings::*;
 pub use chat_hide_settings::*;
@@ -5,14 +7,24 @@ pub use chat_settings::*;
 pub use chat_settings_info::*;
 pub use chat_size_settings::*;
 pub use chat_type::*;
+pub use chat_type_options::*;
 pub use custom_nickname::*;
+pub use point_reward::*;
+pub use stacking_direction::*;
+pub use update_chat_animation_settings::*;
 pub use update_chat_color_settings::*;
 pub use update_chat_font_settings::*;
 pub use update_chat_hide_settings::*;
 pub use update_chat_settings::*;
 pub use update_chat_size_settings::*;
 pub use update_custom_nickname::*;
+pub use update_point_reward::*;
+pub use update_viewer_group_rule::*;
+pub use viewer_group::*;
+pub use viewer_group_rule::*;
 
+mod chat_animation;
+mod chat_animation_settings;
 mod chat_color_settings;
 mod chat_font_settings;
 mod chat_hide_settings;
@@ -20,10 +32,18 @@ mod chat_settings;
 mod chat_settings_info;
 mod chat_size_settings;
 mod chat_type;
+mod chat_type_options;
 mod custom_nickname;
+mod point_reward;
+mod stacking_direction;
+mod update_chat_animation_settings;
 mod update_chat_color_settings;
 mod update_chat_font_settings;
 mod update_chat_hide_settings;
 mod update_chat_settings;
 mod update_chat_size_settings;
 mod update_custom_nickname;
+mod update_point_reward;
+mod update_viewer_group_rule;
+mod viewer_group;
+mod viewer_group_rule;
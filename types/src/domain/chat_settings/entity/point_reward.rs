@@ -0,0 +1,11 @@
+use serde::{Deserialize, Serialize};
+
+/// Per-reward rule on top of `hide_point_rewards`: the redemption is either
+/// hidden or highlighted with `highlight_color`.
+#[derive(Serialize, Deserialize, Debug, PartialEq, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct PointReward {
+    pub reward_id: String,
+    pub hidden: bool,
+    pub highlight_color: Option<i64>,
+}
@@ -0,0 +1,53 @@
+use std::error::Error;
+use std::fmt::{self, Display, Formatter};
+use std::str::FromStr;
+
+use serde::{Deserialize, Serialize};
+
+/// Direction the stack of messages grows in, from the edge it sticks to.
+#[derive(Serialize, Deserialize, Debug, PartialEq, Eq, Clone, Copy)]
+pub enum StackingDirection {
+    /// Follows the chat type, messages stick to the edge of the newest one.
+    #[serde(rename = "auto")]
+    Auto,
+    /// Messages stick to the bottom edge.
+    #[serde(rename = "up")]
+    Up,
+    /// Messages stick to the top edge.
+    #[serde(rename = "down")]
+    Down,
+}
+
+impl StackingDirection {
+    pub fn to_str(&self) -> &str {
+        match *self {
+            StackingDirection::Auto => "auto",
+            StackingDirection::Up => "up",
+            StackingDirection::Down => "down",
+        }
+    }
+}
+
+impl FromStr for StackingDirection {
+    type Err = UnknownStackingDirection;
+
+    fn from_str(stacking_direction: &str) -> Result<Self, Self::Err> {
+        match stacking_direction {
+            "auto" => Ok(StackingDirection::Auto),
+            "up" => Ok(StackingDirection::Up),
+            "down" => Ok(StackingDirection::Down),
+            _ => Err(UnknownStackingDirection(stacking_direction.to_string())),
+        }
+    }
+}
+
+#[derive(Debug, PartialEq, Clone)]
+pub struct UnknownStackingDirection(pub String);
+
+impl Display for UnknownStackingDirection {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        write!(f, "unknown stacking direction \"{}\"", self.0)
+    }
+}
+
+impl Error for UnknownStackingDirection {}
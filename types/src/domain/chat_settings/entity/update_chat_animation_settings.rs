@@ -0,0 +1,58 @@
+use serde::{Deserialize, Serialize};
+use validator::Validate;
+
+use crate::domain::{ChatAnimation, ChatAnimationSettings, StackingDirection};
+
+#[derive(Serialize, Deserialize, Validate, Debug, PartialEq, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct UpdateChatAnimationSettings {
+    #[validate(range(min = 0, max = 3600))]
+    pub message_ttl: i32,
+    pub enter_animation: ChatAnimation,
+    #[validate(range(min = 0, max = 5000))]
+    pub enter_duration: i32,
+    pub exit_animation: ChatAnimation,
+    #[validate(range(min = 0, max = 5000))]
+    pub exit_duration: i32,
+    pub stacking_direction: StackingDirection,
+}
+
+/// Same as the defaults of the database, for clients not sending animations yet.
+impl Default for UpdateChatAnimationSettings {
+    fn default() -> Self {
+        UpdateChatAnimationSettings {
+            message_ttl: 0,
+            enter_animation: ChatAnimation::None,
+            enter_duration: 300,
+            exit_animation: ChatAnimation::None,
+            exit_duration: 300,
+            stacking_direction: StackingDirection::Auto,
+        }
+    }
+}
+
+impl From<UpdateChatAnimationSettings> for ChatAnimationSettings {
+    fn from(value: UpdateChatAnimationSettings) -> Self {
+        ChatAnimationSettings {
+            message_ttl: value.message_ttl,
+            enter_animation: value.enter_animation,
+            enter_duration: value.enter_duration,
+            exit_animation: value.exit_animation,
+            exit_duration: value.exit_duration,
+            stacking_direction: value.stacking_direction,
+        }
+    }
+}
+
+impl From<ChatAnimationSettings> for UpdateChatAnimationSettings {
+    fn from(value: ChatAnimationSettings) -> Self {
+        UpdateChatAnimationSettings {
+            message_ttl: value.message_ttl,
+            enter_animation: value.enter_animation,
+            enter_duration: value.enter_duration,
+            exit_animation: value.exit_animation,
+            exit_duration: value.exit_duration,
+            stacking_direction: value.stacking_direction,
+        }
+    }
+}
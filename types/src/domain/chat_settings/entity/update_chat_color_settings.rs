@@ -1,7 +1,7 @@
 use serde::{Deserialize, Serialize};
 use validator::Validate;
 
-use crate::domain::UpdateCustomNickname;
+use crate::domain::{ChatColorSettings, CustomNickname, UpdateCustomNickname};
 
 #[derive(Serialize, Deserialize, Validate, Debug, PartialEq, Clone)]
 #[serde(rename_all = "camelCase")]
@@ -16,3 +16,35 @@ pub struct UpdateChatColorSettings {
     #[validate]
     pub custom_nicknames: Vec<UpdateCustomNickname>,
 }
+
+impl From<UpdateChatColorSettings> for ChatColorSettings {
+    fn from(value: UpdateChatColorSettings) -> Self {
+        ChatColorSettings {
+            nickname_color: value.nickname_color,
+            background_color: value.background_color,
+            text_color: value.text_color,
+            gradient_only_for_custom_nicknames: value.gradient_only_for_custom_nicknames,
+            custom_nicknames: value
+                .custom_nicknames
+                .into_iter()
+                .map(CustomNickname::from)
+                .collect(),
+        }
+    }
+}
+
+impl From<ChatColorSettings> for UpdateChatColorSettings {
+    fn from(value: ChatColorSettings) -> Self {
+        UpdateChatColorSettings {
+            nickname_color: value.nickname_color,
+            background_color: value.background_color,
+            text_color: value.text_color,
+            gradient_only_for_custom_nicknames: value.gradient_only_for_custom_nicknames,
+            custom_nicknames: value
+                .custom_nicknames
+                .into_iter()
+                .map(UpdateCustomNickname::from)
+                .collect(),
+        }
+    }
+}
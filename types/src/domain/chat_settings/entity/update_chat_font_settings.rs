@@ -1,6 +1,8 @@
 use serde::{Deserialize, Serialize};
 use validator::Validate;
 
+use crate::domain::ChatFontSettings;
+
 #[derive(Serialize, Deserialize, Validate, Debug, PartialEq, Clone)]
 #[serde(rename_all = "camelCase")]
 pub struct UpdateChatFontSettings {
@@ -12,3 +14,25 @@ pub struct UpdateChatFontSettings {
     #[validate(range(min = 0))]
     pub font_size: f64,
 }
+
+impl From<UpdateChatFontSettings> for ChatFontSettings {
+    fn from(value: UpdateChatFontSettings) -> Self {
+        ChatFontSettings {
+            font_family: value.font_family,
+            nickname_font_weight: value.nickname_font_weight,
+            text_font_weight: value.text_font_weight,
+            font_size: value.font_size,
+        }
+    }
+}
+
+impl From<ChatFontSettings> for UpdateChatFontSettings {
+    fn from(value: ChatFontSettings) -> Self {
+        UpdateChatFontSettings {
+            font_family: value.font_family,
+            nickname_font_weight: value.nickname_font_weight,
+            text_font_weight: value.text_font_weight,
+            font_size: value.font_size,
+        }
+    }
+}
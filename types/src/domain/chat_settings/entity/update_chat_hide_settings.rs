@@ -2,21 +2,32 @@ use serde::{Deserialize, Serialize};
 use uuid::Uuid;
 use validator::{Validate, ValidationError};
 
+use crate::domain::{
+    ChatHideSettings, PointReward, UpdatePointReward, UpdateViewerGroupRule, ViewerGroupRule,
+};
+
 #[derive(Serialize, Deserialize, Validate, Debug, PartialEq, Clone)]
 #[serde(rename_all = "camelCase")]
 pub struct UpdateChatHideSettings {
     pub hide_message_pattern: String,
     pub hide_point_rewards: bool,
+    #[serde(default)]
+    #[validate]
+    pub point_rewards: Vec<UpdatePointReward>,
     pub hide_links: bool,
     pub link_replacement: String,
     pub ban_word_replacement: String,
     #[validate(custom(function = "nickname_vec::<4, 25>"))]
     pub nicknames: Vec<String>,
     pub ban_word_filter_id: Option<Uuid>,
+    #[serde(default)]
+    #[validate]
+    #[validate(custom(function = "unique_viewer_groups"))]
+    pub viewer_groups: Vec<UpdateViewerGroupRule>,
 }
 
 fn nickname_vec<const MIN: usize, const MAX: usize>(
-    value: &Vec<String>,
+    value: &[String],
 ) -> Result<(), ValidationError> {
     for s in value.iter() {
         if s.len() > MAX {
@@ -27,3 +38,60 @@ fn nickname_vec<const MIN: usize, const MAX: usize>(
     }
     Ok(())
 }
+
+fn unique_viewer_groups(value: &[UpdateViewerGroupRule]) -> Result<(), ValidationError> {
+    for (i, rule) in value.iter().enumerate() {
+        if value[..i].iter().any(|r| r.group == rule.group) {
+            return Err(ValidationError::new("duplicate viewer group"));
+        }
+    }
+    Ok(())
+}
+
+impl From<UpdateChatHideSettings> for ChatHideSettings {
+    fn from(value: UpdateChatHideSettings) -> Self {
+        ChatHideSettings {
+            hide_message_pattern: value.hide_message_pattern,
+            hide_point_rewards: value.hide_point_rewards,
+            point_rewards: value
+                .point_rewards
+                .into_iter()
+                .map(PointReward::from)
+                .collect(),
+            hide_links: value.hide_links,
+            link_replacement: value.link_replacement,
+            ban_word_replacement: value.ban_word_replacement,
+            nicknames: value.nicknames,
+            ban_word_filter_id: value.ban_word_filter_id,
+            viewer_groups: value
+                .viewer_groups
+                .into_iter()
+                .map(ViewerGroupRule::from)
+                .collect(),
+        }
+    }
+}
+
+impl From<ChatHideSettings> for UpdateChatHideSettings {
+    fn from(value: ChatHideSettings) -> Self {
+        UpdateChatHideSettings {
+            hide_message_pattern: value.hide_message_pattern,
+            hide_point_rewards: value.hide_point_rewards,
+            point_rewards: value
+                .point_rewards
+                .into_iter()
+                .map(UpdatePointReward::from)
+                .collect(),
+            hide_links: value.hide_links,
+            link_replacement: value.link_replacement,
+            ban_word_replacement: value.ban_word_replacement,
+            nicknames: value.nicknames,
+            ban_word_filter_id: value.ban_word_filter_id,
+            viewer_groups: value
+                .viewer_groups
+                .into_iter()
+                .map(UpdateViewerGroupRule::from)
+                .collect(),
+        }
+    }
+}
@@ -2,8 +2,8 @@ use serde::{Deserialize, Serialize};
 use validator::Validate;
 
 use crate::domain::{
-    ChatType, UpdateChatColorSettings, UpdateChatFontSettings, UpdateChatHideSettings,
-    UpdateChatSizeSettings,
+    ChatSettings, ChatType, ChatTypeOptions, UpdateChatAnimationSettings, UpdateChatColorSettings,
+    UpdateChatFontSettings, UpdateChatHideSettings, UpdateChatSizeSettings,
 };
 
 #[derive(Serialize, Deserialize, Validate, Debug, PartialEq, Clone)]
@@ -12,6 +12,9 @@ pub struct UpdateChatSettings {
     #[validate(length(min = 2, max = 32))]
     pub name: String,
     pub chat_type: ChatType,
+    #[serde(default)]
+    #[validate]
+    pub chat_type_options: ChatTypeOptions,
     #[validate]
     pub color: UpdateChatColorSettings,
     #[validate]
@@ -20,4 +23,22 @@ pub struct UpdateChatSettings {
     pub hide: UpdateChatHideSettings,
     #[validate]
     pub font: UpdateChatFontSettings,
+    #[serde(default)]
+    #[validate]
+    pub animation: UpdateChatAnimationSettings,
+}
+
+impl From<ChatSettings> for UpdateChatSettings {
+    fn from(value: ChatSettings) -> Self {
+        UpdateChatSettings {
+            name: value.name,
+            chat_type: value.chat_type,
+            chat_type_options: value.chat_type_options,
+            color: value.color.into(),
+            size: value.size.into(),
+            hide: value.hide.into(),
+            font: value.font.into(),
+            animation: value.animation.into(),
+        }
+    }
 }
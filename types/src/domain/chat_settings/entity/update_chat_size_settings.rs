@@ -1,6 +1,8 @@
 use serde::{Deserialize, Serialize};
 use validator::Validate;
 
+use crate::domain::ChatSizeSettings;
+
 #[derive(Serialize, Deserialize, Validate, Debug, PartialEq, Clone)]
 #[serde(rename_all = "camelCase")]
 pub struct UpdateChatSizeSettings {
@@ -31,3 +33,43 @@ pub struct UpdateChatSizeSettings {
     #[validate(range(min = 5, max = 100))]
     pub max_messages: i32,
 }
+
+impl From<UpdateChatSizeSettings> for ChatSizeSettings {
+    fn from(value: UpdateChatSizeSettings) -> Self {
+        ChatSizeSettings {
+            margin_top: value.margin_top,
+            margin_right: value.margin_right,
+            margin_bottom: value.margin_bottom,
+            margin_left: value.margin_left,
+            padding_top: value.padding_top,
+            padding_right: value.padding_right,
+            padding_bottom: value.padding_bottom,
+            padding_left: value.padding_left,
+            border_top_left_radius: value.border_top_left_radius,
+            border_top_right_radius: value.border_top_right_radius,
+            border_bottom_left_radius: value.border_bottom_left_radius,
+            border_bottom_right_radius: value.border_bottom_right_radius,
+            max_messages: value.max_messages,
+        }
+    }
+}
+
+impl From<ChatSizeSettings> for UpdateChatSizeSettings {
+    fn from(value: ChatSizeSettings) -> Self {
+        UpdateChatSizeSettings {
+            margin_top: value.margin_top,
+            margin_right: value.margin_right,
+            margin_bottom: value.margin_bottom,
+            margin_left: value.margin_left,
+            padding_top: value.padding_top,
+            padding_right: value.padding_right,
+            padding_bottom: value.padding_bottom,
+            padding_left: value.padding_left,
+            border_top_left_radius: value.border_top_left_radius,
+            border_top_right_radius: value.border_top_right_radius,
+            border_bottom_left_radius: value.border_bottom_left_radius,
+            border_bottom_right_radius: value.border_bottom_right_radius,
+            max_messages: value.max_messages,
+        }
+    }
+}
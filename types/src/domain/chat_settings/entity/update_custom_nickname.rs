@@ -1,5 +1,5 @@
 use serde::{Deserialize, Serialize};
-use validator::Validate;
+use validator::{validate_url, Validate, ValidationError};
 
 use crate::domain::CustomNickname;
 
@@ -8,18 +8,76 @@ use crate::domain::CustomNickname;
 pub struct UpdateCustomNickname {
     #[validate(length(min = 4, max = 25))]
     pub nickname: String,
+    /// Kept as is when sent, clients drop it when the nickname is edited.
+    #[serde(default)]
+    #[validate(custom(function = "twitch_user_id"))]
+    pub user_id: Option<String>,
     #[validate(range(min = 0, max = 4294967295))]
     pub start_color: i64,
+    #[serde(default)]
+    #[validate(length(max = 8), custom(function = "color_vec"))]
+    pub middle_colors: Vec<i64>,
     #[validate(range(min = 0, max = 4294967295))]
     pub end_color: i64,
+    #[serde(default)]
+    #[validate(length(min = 1, max = 25))]
+    pub display_name: Option<String>,
+    #[serde(default)]
+    #[validate(length(max = 2048), custom(function = "https_url"))]
+    pub badge_url: Option<String>,
+    #[serde(default)]
+    #[validate(range(min = 100, max = 1000))]
+    pub font_weight: Option<i32>,
 }
 
-impl Into<CustomNickname> for UpdateCustomNickname {
-    fn into(self) -> CustomNickname {
+impl From<UpdateCustomNickname> for CustomNickname {
+    fn from(value: UpdateCustomNickname) -> Self {
         CustomNickname {
-            nickname: self.nickname,
-            start_color: self.start_color,
-            end_color: self.end_color,
+            nickname: value.nickname,
+            user_id: value.user_id,
+            start_color: value.start_color,
+            middle_colors: value.middle_colors,
+            end_color: value.end_color,
+            display_name: value.display_name,
+            badge_url: value.badge_url,
+            font_weight: value.font_weight,
         }
     }
 }
+
+impl From<CustomNickname> for UpdateCustomNickname {
+    fn from(value: CustomNickname) -> Self {
+        UpdateCustomNickname {
+            nickname: value.nickname,
+            user_id: value.user_id,
+            start_color: value.start_color,
+            middle_colors: value.middle_colors,
+            end_color: value.end_color,
+            display_name: value.display_name,
+            badge_url: value.badge_url,
+            font_weight: value.font_weight,
+        }
+    }
+}
+
+fn twitch_user_id(value: &str) -> Result<(), ValidationError> {
+    if value.is_empty() || value.len() > 32 || !value.chars().all(|c| c.is_ascii_digit()) {
+        return Err(ValidationError::new("invalid twitch user id"));
+    }
+    Ok(())
+}
+
+fn color_vec(value: &[i64]) -> Result<(), ValidationError> {
+    if value.iter().any(|color| !(0..=4294967295).contains(color)) {
+        return Err(ValidationError::new("invalid color"));
+    }
+    Ok(())
+}
+
+/// Plain http images are blocked on overlays served over https.
+fn https_url(value: &str) -> Result<(), ValidationError> {
+    if !value.starts_with("https://") || !validate_url(value) {
+        return Err(ValidationError::new("invalid https url"));
+    }
+    Ok(())
+}
@@ -0,0 +1,34 @@
+use serde::{Deserialize, Serialize};
+use validator::Validate;
+
+use crate::domain::PointReward;
+
+#[derive(Serialize, Deserialize, Validate, Debug, PartialEq, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct UpdatePointReward {
+    #[validate(length(min = 1, max = 64))]
+    pub reward_id: String,
+    pub hidden: bool,
+    #[validate(range(min = 0, max = 4294967295))]
+    pub highlight_color: Option<i64>,
+}
+
+impl From<UpdatePointReward> for PointReward {
+    fn from(value: UpdatePointReward) -> Self {
+        PointReward {
+            reward_id: value.reward_id,
+            hidden: value.hidden,
+            highlight_color: value.highlight_color,
+        }
+    }
+}
+
+impl From<PointReward> for UpdatePointReward {
+    fn from(value: PointReward) -> Self {
+        UpdatePointReward {
+            reward_id: value.reward_id,
+            hidden: value.hidden,
+            highlight_color: value.highlight_color,
+        }
+    }
+}
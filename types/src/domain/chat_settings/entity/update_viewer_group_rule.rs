@@ -0,0 +1,44 @@
+use serde::{Deserialize, Serialize};
+use validator::Validate;
+
+use crate::domain::{ViewerGroup, ViewerGroupRule};
+
+#[derive(Serialize, Deserialize, Validate, Debug, PartialEq, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct UpdateViewerGroupRule {
+    pub group: ViewerGroup,
+    pub hidden: bool,
+    pub exempt_from_ban_words: bool,
+    #[validate(range(min = 0, max = 4294967295))]
+    pub nickname_color: Option<i64>,
+    #[validate(range(min = 0, max = 4294967295))]
+    pub background_color: Option<i64>,
+    #[validate(range(min = 100, max = 1000))]
+    pub font_weight: Option<i32>,
+}
+
+impl From<UpdateViewerGroupRule> for ViewerGroupRule {
+    fn from(value: UpdateViewerGroupRule) -> Self {
+        ViewerGroupRule {
+            group: value.group,
+            hidden: value.hidden,
+            exempt_from_ban_words: value.exempt_from_ban_words,
+            nickname_color: value.nickname_color,
+            background_color: value.background_color,
+            font_weight: value.font_weight,
+        }
+    }
+}
+
+impl From<ViewerGroupRule> for UpdateViewerGroupRule {
+    fn from(value: ViewerGroupRule) -> Self {
+        UpdateViewerGroupRule {
+            group: value.group,
+            hidden: value.hidden,
+            exempt_from_ban_words: value.exempt_from_ban_words,
+            nickname_color: value.nickname_color,
+            background_color: value.background_color,
+            font_weight: value.font_weight,
+        }
+    }
+}
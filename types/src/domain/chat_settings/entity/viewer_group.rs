@@ -0,0 +1,113 @@
+use std::error::Error;
+use std::fmt::{self, Display, Formatter};
+use std::str::FromStr;
+
+use serde::{Deserialize, Serialize};
+
+/// Group of chatters recognized by the badges and tags of their message.
+/// Declared from the lowest precedence, a chatter in several groups gets the
+/// styles of the last one.
+#[derive(Serialize, Deserialize, Debug, PartialEq, Eq, PartialOrd, Ord, Hash, Clone, Copy)]
+pub enum ViewerGroup {
+    /// Chatters sending their first message in the channel.
+    #[serde(rename = "first-time-chatter")]
+    FirstTimeChatter,
+    /// Subscribers of any tier, founders included.
+    #[serde(rename = "subscriber")]
+    Subscriber,
+    /// Subscribers of tier 2 or 3.
+    #[serde(rename = "subscriber-tier-2")]
+    SubscriberTier2,
+    #[serde(rename = "subscriber-tier-3")]
+    SubscriberTier3,
+    #[serde(rename = "vip")]
+    Vip,
+    #[serde(rename = "moderator")]
+    Moderator,
+    #[serde(rename = "broadcaster")]
+    Broadcaster,
+    /// Well-known chat bots, matched by login.
+    #[serde(rename = "known-bot")]
+    KnownBot,
+}
+
+impl ViewerGroup {
+    pub fn to_str(&self) -> &str {
+        match *self {
+            ViewerGroup::Broadcaster => "broadcaster",
+            ViewerGroup::Moderator => "moderator",
+            ViewerGroup::Vip => "vip",
+            ViewerGroup::Subscriber => "subscriber",
+            ViewerGroup::SubscriberTier2 => "subscriber-tier-2",
+            ViewerGroup::SubscriberTier3 => "subscriber-tier-3",
+            ViewerGroup::FirstTimeChatter => "first-time-chatter",
+            ViewerGroup::KnownBot => "known-bot",
+        }
+    }
+}
+
+impl FromStr for ViewerGroup {
+    type Err = UnknownViewerGroup;
+
+    fn from_str(viewer_group: &str) -> Result<Self, Self::Err> {
+        match viewer_group {
+            "broadcaster" => Ok(ViewerGroup::Broadcaster),
+            "moderator" => Ok(ViewerGroup::Moderator),
+            "vip" => Ok(ViewerGroup::Vip),
+            "subscriber" => Ok(ViewerGroup::Subscriber),
+            "subscriber-tier-2" => Ok(ViewerGroup::SubscriberTier2),
+            "subscriber-tier-3" => Ok(ViewerGroup::SubscriberTier3),
+            "first-time-chatter" => Ok(ViewerGroup::FirstTimeChatter),
+            "known-bot" => Ok(ViewerGroup::KnownBot),
+            _ => Err(UnknownViewerGroup(viewer_group.to_string())),
+        }
+    }
+}
+
+#[derive(Debug, PartialEq, Clone)]
+pub struct UnknownViewerGroup(pub String);
+
+impl Display for UnknownViewerGroup {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        write!(f, "unknown viewer group \"{}\"", self.0)
+    }
+}
+
+impl Error for UnknownViewerGroup {}
+
+#[cfg(test)]
+mod tests {
+    use crate::domain::ViewerGroup;
+
+    #[test]
+    fn str_round_trip() {
+        for viewer_group in [
+            ViewerGroup::FirstTimeChatter,
+            ViewerGroup::Subscriber,
+            ViewerGroup::SubscriberTier2,
+            ViewerGroup::SubscriberTier3,
+            ViewerGroup::Vip,
+            ViewerGroup::Moderator,
+            ViewerGroup::Broadcaster,
+            ViewerGroup::KnownBot,
+        ] {
+            assert_eq!(viewer_group.to_str().parse(), Ok(viewer_group));
+            // Stored and sent the same way.
+            assert_eq!(
+                serde_json::to_string(&viewer_group).unwrap(),
+                format!("\"{}\"", viewer_group.to_str())
+            );
+        }
+    }
+
+    #[test]
+    fn unknown() {
+        for viewer_group in ["", "Moderator", "founder", "subscriber-tier-1"] {
+            assert!(
+                viewer_group.parse::<ViewerGroup>().is_err(),
+                "{}",
+                viewer_group
+            );
+        }
+    }
+}
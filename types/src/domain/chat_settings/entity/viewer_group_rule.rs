@@ -0,0 +1,17 @@
+use serde::{Deserialize, Serialize};
+
+use crate::domain::ViewerGroup;
+
+/// Rule for the messages of a viewer group. A chatter in several groups is
+/// hidden or exempted by any of their rules.
+#[derive(Serialize, Deserialize, Debug, PartialEq, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct ViewerGroupRule {
+    pub group: ViewerGroup,
+    pub hidden: bool,
+    /// Ban words are left as is in the messages of the group.
+    pub exempt_from_ban_words: bool,
+    pub nickname_color: Option<i64>,
+    pub background_color: Option<i64>,
+    pub font_weight: Option<i32>,
+}
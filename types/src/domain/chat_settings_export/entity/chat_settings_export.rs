@@ -0,0 +1,26 @@
+use chrono::NaiveDateTime;
+use serde::{Deserialize, Serialize};
+use validator::Validate;
+
+use crate::domain::{UpdateBanWordFilter, UpdateChatSettings};
+
+/// Portable chat settings document, to move settings between accounts or
+/// share them. Older documents are upgraded to the current `schema_version`
+/// before they are read.
+#[derive(Serialize, Deserialize, Validate, Debug, PartialEq, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct ChatSettingsExport {
+    pub format: String,
+    pub schema_version: u32,
+    pub exported_at: NaiveDateTime,
+    #[validate]
+    pub settings: UpdateChatSettings,
+    /// Filter of `settings.hide.banWordFilterId`, imported as a new filter.
+    #[validate]
+    pub ban_word_filter: Option<UpdateBanWordFilter>,
+}
+
+impl ChatSettingsExport {
+    pub const FORMAT: &'static str = "yggdrasil/chat-settings";
+    pub const SCHEMA_VERSION: u32 = 2;
+}
@@ -0,0 +1,3 @@
+pub use chat_settings_export::*;
+
+mod chat_settings_export;
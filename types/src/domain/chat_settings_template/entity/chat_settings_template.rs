@@ -0,0 +1,21 @@
+use chrono::NaiveDateTime;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use uuid::Uuid;
+
+/// Settings to start new chat settings from. Built-in templates have no
+/// `user_id`, the others are published by users.
+///
+/// `settings` are stored in the schema version of the export they were
+/// published with and are upgraded like exports when read.
+#[derive(Serialize, Deserialize, Debug, PartialEq, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct ChatSettingsTemplate {
+    pub id: Uuid,
+    pub name: String,
+    pub description: String,
+    pub user_id: Option<String>,
+    pub schema_version: u32,
+    pub settings: Value,
+    pub created_at: NaiveDateTime,
+}
@@ -0,0 +1,28 @@
+use chrono::NaiveDateTime;
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+use crate::domain::ChatType;
+
+#[derive(Serialize, Deserialize, Debug, PartialEq, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct ChatSettingsTemplateInfo {
+    pub id: Uuid,
+    pub name: String,
+    pub description: String,
+    pub user_id: Option<String>,
+    pub preview: ChatSettingsTemplatePreview,
+    pub created_at: NaiveDateTime,
+}
+
+/// Enough of the settings to draw a thumbnail of the template in the gallery.
+#[derive(Serialize, Deserialize, Debug, PartialEq, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct ChatSettingsTemplatePreview {
+    pub chat_type: ChatType,
+    pub nickname_color: i64,
+    pub background_color: i64,
+    pub text_color: i64,
+    pub font_family: String,
+    pub font_size: f64,
+}
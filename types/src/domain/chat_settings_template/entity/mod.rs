@@ -0,0 +1,5 @@
+pub use chat_settings_template::*;
+pub use chat_settings_template_info::*;
+
+mod chat_settings_template;
+mod chat_settings_template_info;
@@ -0,0 +1,3 @@
+pub use entity::*;
+
+mod entity;
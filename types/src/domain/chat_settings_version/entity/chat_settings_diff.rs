@@ -0,0 +1,20 @@
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+#[derive(Serialize, Deserialize, Debug, PartialEq, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct ChatSettingsDiff {
+    pub from_version: i32,
+    pub to_version: i32,
+    pub changes: Vec<ChatSettingsFieldChange>,
+}
+
+/// Changed field, like `size.maxMessages` or `color.customNicknames[Streamer]`.
+/// `from` is `null` for added entries, `to` for removed ones.
+#[derive(Serialize, Deserialize, Debug, PartialEq, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct ChatSettingsFieldChange {
+    pub path: String,
+    pub from: Value,
+    pub to: Value,
+}
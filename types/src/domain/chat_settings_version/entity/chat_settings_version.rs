@@ -0,0 +1,15 @@
+use chrono::NaiveDateTime;
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+use crate::domain::ChatSettings;
+
+/// Immutable snapshot of the chat settings, saved on every change.
+#[derive(Serialize, Deserialize, Debug, PartialEq, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct ChatSettingsVersion {
+    pub chat_settings_id: Uuid,
+    pub version: i32,
+    pub settings: ChatSettings,
+    pub created_at: NaiveDateTime,
+}
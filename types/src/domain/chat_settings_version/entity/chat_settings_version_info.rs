@@ -0,0 +1,12 @@
+use chrono::NaiveDateTime;
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+#[derive(Serialize, Deserialize, Debug, PartialEq, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct ChatSettingsVersionInfo {
+    pub chat_settings_id: Uuid,
+    pub version: i32,
+    pub name: String,
+    pub created_at: NaiveDateTime,
+}
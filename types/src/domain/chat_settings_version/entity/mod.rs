@@ -0,0 +1,7 @@
+pub use chat_settings_diff::*;
+pub use chat_settings_version::*;
+pub use chat_settings_version_info::*;
+
+mod chat_settings_diff;
+mod chat_settings_version;
+mod chat_settings_version_info;
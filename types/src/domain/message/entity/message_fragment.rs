@@ -0,0 +1,53 @@
+use serde::{Deserialize, Serialize};
+
+use crate::twitch::CheermoteTier;
+
+/// Piece of a chat message as the overlay renders it. `text` is always the
+/// original part of the message, so joining all fragments gives it back.
+#[derive(Serialize, Deserialize, Debug, PartialEq, Clone)]
+#[serde(tag = "type", rename_all = "camelCase")]
+pub enum MessageFragment {
+    Text {
+        text: String,
+    },
+    Emote {
+        text: String,
+        id: String,
+        image: String,
+    },
+    #[serde(rename_all = "camelCase")]
+    ThirdPartyEmote {
+        text: String,
+        provider: String,
+        id: String,
+        image: String,
+    },
+    #[serde(rename_all = "camelCase")]
+    Cheermote {
+        text: String,
+        prefix: String,
+        bits: i64,
+        tier: CheermoteTier,
+    },
+    Mention {
+        text: String,
+        login: String,
+    },
+    Link {
+        text: String,
+        url: String,
+    },
+}
+
+impl MessageFragment {
+    pub fn text(&self) -> &str {
+        match self {
+            MessageFragment::Text { text }
+            | MessageFragment::Emote { text, .. }
+            | MessageFragment::ThirdPartyEmote { text, .. }
+            | MessageFragment::Cheermote { text, .. }
+            | MessageFragment::Mention { text, .. }
+            | MessageFragment::Link { text, .. } => text,
+        }
+    }
+}
@@ -0,0 +1,9 @@
+pub use message_fragment::*;
+pub use overlay_event::*;
+pub use overlay_message::*;
+pub use third_party_emote::*;
+
+mod message_fragment;
+mod overlay_event;
+mod overlay_message;
+mod third_party_emote;
@@ -0,0 +1,52 @@
+use serde::{Deserialize, Serialize};
+
+use crate::domain::{ChatSettings, OverlayMessage};
+
+/// Event pushed to connected overlays.
+#[derive(Serialize, Deserialize, Debug, PartialEq, Clone)]
+#[serde(tag = "type", rename_all = "camelCase")]
+pub enum OverlayEvent {
+    Message(Box<OverlayMessage>),
+    /// Messages removed by a moderator, directly or by a timeout or ban.
+    #[serde(rename_all = "camelCase")]
+    Delete {
+        message_ids: Vec<String>,
+    },
+    Clear,
+    /// Channel points reward redeemed by a viewer.
+    #[serde(rename_all = "camelCase")]
+    Redemption {
+        reward_id: String,
+        reward_title: String,
+        cost: i64,
+        user_name: String,
+        user_input: String,
+    },
+    #[serde(rename_all = "camelCase")]
+    Follow {
+        user_name: String,
+    },
+    #[serde(rename_all = "camelCase")]
+    Subscription {
+        user_name: String,
+        tier: String,
+        is_gift: bool,
+    },
+    /// `user_name` is missing when the cheer is anonymous.
+    #[serde(rename_all = "camelCase")]
+    Cheer {
+        user_name: Option<String>,
+        bits: i64,
+        message: String,
+    },
+    #[serde(rename_all = "camelCase")]
+    Raid {
+        from_broadcaster_name: String,
+        viewers: i64,
+    },
+    /// The settings were saved, `css` is their stylesheet.
+    Settings {
+        settings: Box<ChatSettings>,
+        css: String,
+    },
+}
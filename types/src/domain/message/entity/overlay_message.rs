@@ -0,0 +1,25 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+use crate::domain::{MessageFragment, ViewerGroup};
+use crate::twitch::ChatBadge;
+
+#[derive(Serialize, Deserialize, Debug, PartialEq, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct OverlayMessage {
+    pub id: String,
+    pub user_id: String,
+    pub login: String,
+    pub display_name: String,
+    pub color: Option<String>,
+    pub badges: Vec<ChatBadge>,
+    /// Groups of the chatter, matched by the viewer group rules.
+    pub viewer_groups: Vec<ViewerGroup>,
+    pub fragments: Vec<MessageFragment>,
+    pub reward_id: Option<String>,
+    /// Background of highlighted channel point redemptions.
+    pub highlight_color: Option<i64>,
+    /// Image shown before the name, set by a custom nickname.
+    pub badge_url: Option<String>,
+    pub sent_at: DateTime<Utc>,
+}
@@ -0,0 +1,11 @@
+use serde::{Deserialize, Serialize};
+
+/// Emote from BetterTTV, FrankerFaceZ or 7TV, matched by whole words.
+#[derive(Serialize, Deserialize, Debug, PartialEq, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct ThirdPartyEmote {
+    pub provider: String,
+    pub id: String,
+    pub name: String,
+    pub image: String,
+}
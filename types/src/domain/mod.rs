@@ -1,9 +1,23 @@
 pub use ban_word_filter::*;
+pub use chat_preview::*;
 pub use chat_settings::*;
+pub use chat_settings_export::*;
+pub use chat_settings_template::*;
+pub use chat_settings_version::*;
+pub use message::*;
+pub use page::*;
+pub use settings_change::*;
 pub use token::*;
 pub use user::*;
 
 mod ban_word_filter;
+mod chat_preview;
 mod chat_settings;
+mod chat_settings_export;
+mod chat_settings_template;
+mod chat_settings_version;
+mod message;
+mod page;
+mod settings_change;
 mod token;
 mod user;
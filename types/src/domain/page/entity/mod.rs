@@ -0,0 +1,5 @@
+pub use page::*;
+pub use page_request::*;
+
+mod page;
+mod page_request;
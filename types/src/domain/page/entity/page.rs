@@ -0,0 +1,10 @@
+use serde::{Deserialize, Serialize};
+
+/// Page of a list, `next_cursor` is passed as the `cursor` of the next
+/// request and is `None` on the last page.
+#[derive(Serialize, Deserialize, Debug, PartialEq, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct Page<T> {
+    pub items: Vec<T>,
+    pub next_cursor: Option<String>,
+}
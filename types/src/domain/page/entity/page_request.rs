@@ -0,0 +1,54 @@
+use serde::{Deserialize, Serialize};
+use validator::Validate;
+
+#[derive(Serialize, Deserialize, Validate, Debug, PartialEq, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct PageRequest {
+    #[serde(default = "PageRequest::default_limit")]
+    #[validate(range(min = 1, max = 100))]
+    pub limit: i64,
+    pub cursor: Option<String>,
+    #[serde(default)]
+    pub sort: SortBy,
+    #[serde(default)]
+    pub order: SortOrder,
+    /// Part of the name, case insensitive.
+    #[validate(length(max = 32))]
+    pub search: Option<String>,
+}
+
+impl PageRequest {
+    fn default_limit() -> i64 {
+        20
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, PartialEq, Clone, Copy, Default)]
+pub enum SortBy {
+    #[serde(rename = "name")]
+    Name,
+    #[default]
+    #[serde(rename = "createdAt")]
+    CreatedAt,
+    #[serde(rename = "updatedAt")]
+    UpdatedAt,
+}
+
+impl SortBy {
+    pub fn to_str(&self) -> &str {
+        match self {
+            SortBy::Name => "name",
+            SortBy::CreatedAt => "createdAt",
+            SortBy::UpdatedAt => "updatedAt",
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, PartialEq, Clone, Copy, Default)]
+pub enum SortOrder {
+    #[default]
+    #[serde(rename = "asc")]
+    Asc,
+    #[serde(rename = "desc")]
+    Desc,
+}
@@ -0,0 +1,10 @@
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+/// Saved settings that open overlays have to reload.
+#[derive(Serialize, Deserialize, Debug, PartialEq, Eq, Clone, Copy)]
+#[serde(tag = "type", rename_all = "camelCase")]
+pub enum SettingsChange {
+    ChatSettings { id: Uuid },
+    BanWordFilter { id: Uuid },
+}
@@ -130,8 +130,7 @@ impl IntoResponse for AppError {
             "message".to_string(),
             Value::String(self.message.unwrap_or("unexpected error").to_string()),
         );
-        if self.other.is_some() {
-            let other = self.other.unwrap();
+        if let Some(other) = self.other {
             if !other.is_empty() {
                 map.insert("other".to_string(), Value::Object(other));
             }
@@ -139,8 +138,7 @@ impl IntoResponse for AppError {
 
         let id = find_current_trace_id();
 
-        if id.is_some() {
-            let id = id.unwrap();
+        if let Some(id) = id {
             map.insert("traceId".to_string(), Value::String(id));
         }
 
@@ -181,8 +179,8 @@ impl Clone for AppError {
     /// On clone set cause and other to None
     fn clone(&self) -> Self {
         AppError {
-            status_code: self.status_code.clone(),
-            message: self.message.clone(),
+            status_code: self.status_code,
+            message: self.message,
             cause: None,
             other: None,
         }
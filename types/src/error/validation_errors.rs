@@ -32,13 +32,13 @@ impl ValidationErrorsWrapper {
         for (field, err) in err.errors() {
             match err.clone() {
                 ValidationErrorsKind::Struct(err) => {
-                    let struct_map = self.struct_error_to_map(&field, err);
+                    let struct_map = self.struct_error_to_map(field, err);
                     for (key, value) in struct_map {
                         map.insert(key, value);
                     }
                 }
                 ValidationErrorsKind::List(err) => {
-                    let list_map = self.list_error_to_map(&field, err);
+                    let list_map = self.list_error_to_map(field, err);
                     for (key, value) in list_map {
                         map.insert(key, value);
                     }
@@ -71,8 +71,8 @@ impl ValidationErrorsWrapper {
         let mut map = Map::new();
         for err in err {
             let value = err.params.iter().find(|(key, _)| *key == "value");
-            if value.is_some() {
-                map.insert("value".to_string(), value.unwrap().1.to_owned());
+            if let Some(value) = value {
+                map.insert("value".to_string(), value.1.to_owned());
             }
             map.insert("code".to_string(), Value::String(err.code.to_string()));
             let mut params_map = Map::new();
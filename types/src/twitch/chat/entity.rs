@@ -0,0 +1,47 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+/// Event read from a channel chat.
+#[derive(Serialize, Deserialize, Debug, PartialEq, Clone)]
+#[serde(tag = "type", rename_all = "camelCase")]
+pub enum ChatEvent {
+    Message(Box<ChatMessage>),
+    /// A single message was deleted by a moderator.
+    #[serde(rename_all = "camelCase")]
+    ClearMessage {
+        channel: String,
+        message_id: String,
+    },
+    /// The whole chat was cleared, or only the messages of a timed out or banned user.
+    #[serde(rename_all = "camelCase")]
+    ClearChat {
+        channel: String,
+        user_id: Option<String>,
+    },
+}
+
+#[derive(Serialize, Deserialize, Debug, PartialEq, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct ChatMessage {
+    pub id: String,
+    pub channel: String,
+    pub user_id: String,
+    pub login: String,
+    pub display_name: String,
+    pub color: Option<String>,
+    pub badges: Vec<ChatBadge>,
+    pub text: String,
+    pub emotes: String,
+    pub bits: Option<i64>,
+    /// Set when the message was sent by redeeming a channel point reward.
+    pub reward_id: Option<String>,
+    pub first_message: bool,
+    pub sent_at: DateTime<Utc>,
+}
+
+#[derive(Serialize, Deserialize, Debug, PartialEq, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct ChatBadge {
+    pub set: String,
+    pub version: String,
+}
@@ -0,0 +1,28 @@
+use serde::{Deserialize, Serialize};
+
+#[derive(Serialize, Deserialize, Debug, PartialEq, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct Cheermote {
+    pub prefix: String,
+    pub tiers: Vec<CheermoteTier>,
+}
+
+#[derive(Serialize, Deserialize, Debug, PartialEq, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct CheermoteTier {
+    pub id: String,
+    pub min_bits: i64,
+    pub color: String,
+    pub animated_image: String,
+    pub static_image: String,
+}
+
+impl Cheermote {
+    /// Highest tier reached by `bits`, tiers start at 1 bit so only 0 has none.
+    pub fn tier(&self, bits: i64) -> Option<&CheermoteTier> {
+        self.tiers
+            .iter()
+            .filter(|tier| tier.min_bits <= bits)
+            .max_by_key(|tier| tier.min_bits)
+    }
+}
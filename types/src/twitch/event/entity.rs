@@ -0,0 +1,102 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+/// Channel event delivered by EventSub, regardless of the transport it came from.
+#[derive(Serialize, Deserialize, Debug, PartialEq, Clone)]
+#[serde(tag = "type", content = "event")]
+pub enum Event {
+    #[serde(rename = "channel.channel_points_custom_reward_redemption.add")]
+    ChannelPointsRedemption(ChannelPointsRedemptionEvent),
+    #[serde(rename = "channel.follow")]
+    Follow(FollowEvent),
+    #[serde(rename = "channel.subscribe")]
+    Subscribe(SubscribeEvent),
+    #[serde(rename = "channel.cheer")]
+    Cheer(CheerEvent),
+    #[serde(rename = "channel.raid")]
+    Raid(RaidEvent),
+    #[serde(rename = "channel.chat.clear")]
+    ChatClear(ChatClearEvent),
+    #[serde(rename = "channel.chat.message_delete")]
+    ChatMessageDelete(ChatMessageDeleteEvent),
+}
+
+#[derive(Serialize, Deserialize, Debug, PartialEq, Clone)]
+pub struct ChannelPointsRedemptionEvent {
+    pub id: String,
+    pub broadcaster_user_id: String,
+    pub broadcaster_user_login: String,
+    pub user_id: String,
+    pub user_login: String,
+    pub user_name: String,
+    pub user_input: String,
+    pub status: String,
+    pub reward: RedemptionReward,
+    pub redeemed_at: DateTime<Utc>,
+}
+
+#[derive(Serialize, Deserialize, Debug, PartialEq, Clone)]
+pub struct RedemptionReward {
+    pub id: String,
+    pub title: String,
+    pub cost: i64,
+    pub prompt: String,
+}
+
+#[derive(Serialize, Deserialize, Debug, PartialEq, Clone)]
+pub struct FollowEvent {
+    pub broadcaster_user_id: String,
+    pub broadcaster_user_login: String,
+    pub user_id: String,
+    pub user_login: String,
+    pub user_name: String,
+    pub followed_at: DateTime<Utc>,
+}
+
+#[derive(Serialize, Deserialize, Debug, PartialEq, Clone)]
+pub struct SubscribeEvent {
+    pub broadcaster_user_id: String,
+    pub broadcaster_user_login: String,
+    pub user_id: String,
+    pub user_login: String,
+    pub user_name: String,
+    pub tier: String,
+    pub is_gift: bool,
+}
+
+#[derive(Serialize, Deserialize, Debug, PartialEq, Clone)]
+pub struct CheerEvent {
+    pub broadcaster_user_id: String,
+    pub broadcaster_user_login: String,
+    pub is_anonymous: bool,
+    pub user_id: Option<String>,
+    pub user_login: Option<String>,
+    pub user_name: Option<String>,
+    pub message: String,
+    pub bits: i64,
+}
+
+#[derive(Serialize, Deserialize, Debug, PartialEq, Clone)]
+pub struct RaidEvent {
+    pub from_broadcaster_user_id: String,
+    pub from_broadcaster_user_login: String,
+    pub from_broadcaster_user_name: String,
+    pub to_broadcaster_user_id: String,
+    pub to_broadcaster_user_login: String,
+    pub viewers: i64,
+}
+
+#[derive(Serialize, Deserialize, Debug, PartialEq, Clone)]
+pub struct ChatClearEvent {
+    pub broadcaster_user_id: String,
+    pub broadcaster_user_login: String,
+}
+
+#[derive(Serialize, Deserialize, Debug, PartialEq, Clone)]
+pub struct ChatMessageDeleteEvent {
+    pub broadcaster_user_id: String,
+    pub broadcaster_user_login: String,
+    pub target_user_id: String,
+    pub target_user_login: String,
+    pub message_id: String,
+}
@@ -1,9 +1,17 @@
 pub use badge::*;
+pub use chat::*;
+pub use cheermote::*;
 pub use data::*;
 pub use emote::*;
+pub use event::*;
+pub use reward::*;
 pub use user_info::*;
 
 mod badge;
+mod chat;
+mod cheermote;
 mod data;
 mod emote;
+mod event;
+mod reward;
 mod user_info;
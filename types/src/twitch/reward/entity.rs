@@ -0,0 +1,13 @@
+use serde::{Deserialize, Serialize};
+
+#[derive(Serialize, Deserialize, Debug, PartialEq, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct Reward {
+    pub id: String,
+    pub title: String,
+    pub prompt: String,
+    pub cost: i64,
+    pub background_color: String,
+    pub is_enabled: bool,
+    pub image: String,
+}
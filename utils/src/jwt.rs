@@ -35,7 +35,7 @@ impl JwtMaker {
     pub fn validate(&self, token: &str) -> AppResult<Claims> {
         let token_data = decode::<Claims>(
             token,
-            &DecodingKey::from_secret(&self.secret.as_bytes()),
+            &DecodingKey::from_secret(self.secret.as_bytes()),
             &self.validation,
         )
         .map_err(|err| match *err.kind() {
@@ -98,7 +98,7 @@ impl JwtMaker {
         let token = encode(
             &Header::default(),
             &claims,
-            &EncodingKey::from_secret(&self.secret.as_bytes()),
+            &EncodingKey::from_secret(self.secret.as_bytes()),
         )?;
 
         Ok((token, claims))
@@ -2,7 +2,7 @@ use std::net::SocketAddr;
 use std::sync::Arc;
 use std::time::Duration;
 
-use axum::http::header::{ACCEPT, AUTHORIZATION, CONTENT_TYPE, ORIGIN, USER_AGENT};
+use axum::http::header::{ACCEPT, AUTHORIZATION, CONTENT_TYPE, ETAG, IF_MATCH, ORIGIN, USER_AGENT};
 use axum::http::{HeaderValue, Method, StatusCode};
 use axum::middleware::from_fn;
 use axum::routing::get;
@@ -13,12 +13,16 @@ use tower_http::cors::CorsLayer;
 use tower_http::timeout::TimeoutLayer;
 
 use config::HttpConfig;
-use service::{AuthService, BanWordService, ChatService, SessionService, TwitchService};
+use service::{
+    AuthService, BanWordService, ChatService, EventService, OverlayService, SessionService,
+    TwitchService,
+};
 
 use crate::middleware::{error_middleware, TracingLayer};
-use crate::routes::routes;
+use crate::routes::{plain_routes, routes};
 
 mod middleware;
+mod revision;
 mod routes;
 
 pub struct Services {
@@ -27,6 +31,8 @@ pub struct Services {
     pub twitch: Arc<TwitchService>,
     pub chat: Arc<ChatService>,
     pub ban_word: Arc<BanWordService>,
+    pub event: Arc<EventService>,
+    pub overlay: Arc<OverlayService>,
 }
 
 pub async fn run(config: HttpConfig, services: Services) {
@@ -38,14 +44,31 @@ pub async fn run(config: HttpConfig, services: Services) {
         .layer(Extension(services.twitch))
         .layer(Extension(services.chat))
         .layer(Extension(services.ban_word))
+        .layer(Extension(services.overlay))
         .layer(from_fn(error_middleware))
-        .layer(OtelInResponseLayer::default())
+        // The webhook challenge is answered in plain text.
+        .merge(plain_routes().layer(Extension(services.event)))
+        .layer(OtelInResponseLayer)
         .layer(TracingLayer::default())
         .route("/health", get(move || async { StatusCode::NO_CONTENT }))
         .layer(
             CorsLayer::new()
-                .allow_methods([Method::GET, Method::POST, Method::PUT, Method::DELETE])
-                .allow_headers([AUTHORIZATION, ACCEPT, ORIGIN, CONTENT_TYPE, USER_AGENT])
+                .allow_methods([
+                    Method::GET,
+                    Method::POST,
+                    Method::PUT,
+                    Method::PATCH,
+                    Method::DELETE,
+                ])
+                .allow_headers([
+                    AUTHORIZATION,
+                    ACCEPT,
+                    ORIGIN,
+                    CONTENT_TYPE,
+                    USER_AGENT,
+                    IF_MATCH,
+                ])
+                .expose_headers([ETAG])
                 .allow_origin(config.allow_origin().parse::<HeaderValue>().unwrap()),
         )
         .layer(TimeoutLayer::new(Duration::from_secs(10)));
@@ -0,0 +1,156 @@
+use std::convert::Infallible;
+
+use axum::async_trait;
+use axum::extract::FromRequestParts;
+use axum::http::header::{ETAG, IF_MATCH};
+use axum::http::request::Parts;
+use axum::http::{HeaderValue, StatusCode};
+use axum::response::{IntoResponseParts, ResponseParts};
+
+use types::error::AppError;
+
+/// Revision of the resource, sent as a strong `ETag` like `"3"`.
+pub struct ETag(pub i32);
+
+impl IntoResponseParts for ETag {
+    type Error = Infallible;
+
+    fn into_response_parts(self, mut res: ResponseParts) -> Result<ResponseParts, Self::Error> {
+        if let Ok(value) = HeaderValue::try_from(format!("\"{}\"", self.0)) {
+            res.headers_mut().insert(ETAG, value);
+        }
+        Ok(res)
+    }
+}
+
+/// Revision the client expects from the `If-Match` header, `None` when the
+/// header is missing or `*`, so the write happens whatever the revision is.
+pub struct IfMatch(pub Option<i32>);
+
+impl IfMatch {
+    pub const INVALID_IF_MATCH_ERROR: AppError = AppError {
+        status_code: StatusCode::BAD_REQUEST,
+        message: Some("invalid if-match"),
+        cause: None,
+        other: None,
+    };
+
+    /// Weak and unknown tags, as well as lists of tags, never match a revision.
+    pub const UNMATCHED_IF_MATCH_ERROR: AppError = AppError {
+        status_code: StatusCode::PRECONDITION_FAILED,
+        message: Some("if-match can't match a revision"),
+        cause: None,
+        other: None,
+    };
+}
+
+#[async_trait]
+impl<S: Send + Sync> FromRequestParts<S> for IfMatch {
+    type Rejection = AppError;
+
+    async fn from_request_parts(parts: &mut Parts, _state: &S) -> Result<Self, Self::Rejection> {
+        let Some(value) = parts.headers.get(IF_MATCH) else {
+            return Ok(IfMatch(None));
+        };
+
+        let value = value
+            .to_str()
+            .map_err(|_| IfMatch::INVALID_IF_MATCH_ERROR)?
+            .trim();
+        if value == "*" {
+            return Ok(IfMatch(None));
+        }
+
+        let tags = value
+            .split(',')
+            .map(entity_tag)
+            .collect::<Option<Vec<EntityTag>>>()
+            .ok_or(IfMatch::INVALID_IF_MATCH_ERROR)?;
+
+        match tags.as_slice() {
+            [EntityTag {
+                is_weak: false,
+                value,
+            }] => value
+                .parse()
+                .map(|revision| IfMatch(Some(revision)))
+                .map_err(|_| IfMatch::UNMATCHED_IF_MATCH_ERROR),
+            _ => Err(IfMatch::UNMATCHED_IF_MATCH_ERROR),
+        }
+    }
+}
+
+struct EntityTag<'a> {
+    is_weak: bool,
+    value: &'a str,
+}
+
+/// Parses `"value"` or `W/"value"`, `None` when it isn't an entity tag at all.
+fn entity_tag(tag: &str) -> Option<EntityTag<'_>> {
+    let tag = tag.trim();
+    let (is_weak, tag) = match tag.strip_prefix("W/") {
+        Some(tag) => (true, tag),
+        None => (false, tag),
+    };
+
+    let value = tag.strip_prefix('"')?.strip_suffix('"')?;
+    if value.contains('"') {
+        return None;
+    }
+
+    Some(EntityTag { is_weak, value })
+}
+
+#[cfg(test)]
+mod tests {
+    use axum::extract::FromRequestParts;
+    use axum::http::header::IF_MATCH;
+    use axum::http::{Request, StatusCode};
+
+    use crate::revision::IfMatch;
+
+    async fn if_match(value: Option<&str>) -> Result<Option<i32>, StatusCode> {
+        let mut request = Request::builder();
+        if let Some(value) = value {
+            request = request.header(IF_MATCH, value);
+        }
+        let (mut parts, _) = request.body(()).unwrap().into_parts();
+
+        IfMatch::from_request_parts(&mut parts, &())
+            .await
+            .map(|IfMatch(revision)| revision)
+            .map_err(|e| e.status_code)
+    }
+
+    #[tokio::test]
+    async fn revisions() {
+        assert_eq!(if_match(None).await, Ok(None));
+        assert_eq!(if_match(Some("*")).await, Ok(None));
+        assert_eq!(if_match(Some("\"3\"")).await, Ok(Some(3)));
+        assert_eq!(if_match(Some(" \"42\" ")).await, Ok(Some(42)));
+    }
+
+    #[tokio::test]
+    async fn tags_which_never_match() {
+        for value in ["W/\"3\"", "\"3\", \"4\"", "\"abc\"", "\"\""] {
+            assert_eq!(
+                if_match(Some(value)).await,
+                Err(StatusCode::PRECONDITION_FAILED),
+                "{}",
+                value
+            );
+        }
+    }
+
+    #[tokio::test]
+    async fn malformed() {
+        for value in ["", "3", "\"3", "W/3", "\"3\", 4", "\"3\"4\"", "**"] {
+            assert_eq!(
+                if_match(Some(value)).await,
+                Err(StatusCode::BAD_REQUEST),
+                "{}",
+                value
+            );
+        }
+    }
+}
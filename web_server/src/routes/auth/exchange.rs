@@ -16,7 +16,7 @@ pub async fn handler(
 ) -> AppResult<Json<ExchangeResponse>> {
     request
         .validate()
-        .map_err(|e| ValidationErrorsWrapper::from(e))?;
+        .map_err(ValidationErrorsWrapper::from)?;
 
     let ip = ip.0.to_string();
 
@@ -14,7 +14,7 @@ pub async fn handler(
 ) -> AppResult<Json<RefreshResponse>> {
     request
         .validate()
-        .map_err(|e| ValidationErrorsWrapper::from(e))?;
+        .map_err(ValidationErrorsWrapper::from)?;
 
     let refresh_token_claims = auth_service.validate_token(&request.token).await?;
     if refresh_token_claims.typ != TokenType::Refresh {
@@ -1,18 +1,28 @@
 use axum::http::StatusCode;
-use axum::Router;
+use axum::{routing, Router};
 
 use types::error::AppError;
 
 mod auth;
+mod overlay;
 mod v1;
 
 pub fn routes() -> Router {
     Router::new()
         .nest("/auth", auth::routes())
+        .nest("/overlay", overlay::routes())
         .nest("/v1", v1::routes())
         .fallback(handler_404)
 }
 
+/// Routes answering in plain text, kept out of the error middleware.
+pub fn plain_routes() -> Router {
+    Router::new().route(
+        "/v1/twitch/eventsub",
+        routing::post(v1::twitch::eventsub::handler),
+    )
+}
+
 async fn handler_404() -> AppError {
     AppError::new(StatusCode::NOT_FOUND).message("not found")
 }
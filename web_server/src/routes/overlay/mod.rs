@@ -0,0 +1,7 @@
+use axum::{routing, Router};
+
+mod page;
+
+pub fn routes() -> Router {
+    Router::new().route("/:chat_settings_id", routing::get(page::handler))
+}
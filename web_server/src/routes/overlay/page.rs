@@ -0,0 +1,26 @@
+use std::sync::Arc;
+
+use axum::extract::Path;
+use axum::response::Html;
+use axum::Extension;
+use serde::Deserialize;
+use uuid::Uuid;
+
+use service::OverlayService;
+use types::error::AppResult;
+
+pub async fn handler(
+    Extension(overlay_service): Extension<Arc<OverlayService>>,
+    Path(path_params): Path<OverlayPagePathParams>,
+) -> AppResult<Html<String>> {
+    let page = overlay_service
+        .render_page(&path_params.chat_settings_id)
+        .await?;
+
+    Ok(Html(page))
+}
+
+#[derive(Deserialize)]
+pub struct OverlayPagePathParams {
+    chat_settings_id: Uuid,
+}
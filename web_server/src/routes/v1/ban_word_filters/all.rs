@@ -1,24 +1,26 @@
 use std::sync::Arc;
 
+use axum::extract::Query;
 use axum::{Extension, Json};
-use serde::Serialize;
+use validator::Validate;
 
 use service::BanWordService;
-use types::domain::BanWordFilterInfo;
-use types::error::AppResult;
+use types::domain::{BanWordFilterInfo, Page, PageRequest};
+use types::error::{AppResult, ValidationErrorsWrapper};
 use utils::jwt::Claims;
 
 pub async fn handler(
     Extension(ban_word_service): Extension<Arc<BanWordService>>,
     Extension(claims): Extension<Arc<Claims>>,
-) -> AppResult<Json<GetAllBanWordFiltersResponse>> {
-    let ban_word_filters = ban_word_service.get_all_filters(&claims.sub).await?;
+    Query(page_request): Query<PageRequest>,
+) -> AppResult<Json<Page<BanWordFilterInfo>>> {
+    page_request
+        .validate()
+        .map_err(ValidationErrorsWrapper::from)?;
 
-    Ok(Json(GetAllBanWordFiltersResponse { ban_word_filters }))
-}
+    let ban_word_filters = ban_word_service
+        .get_all_filters(&claims.sub, &page_request)
+        .await?;
 
-#[derive(Serialize)]
-#[serde(rename_all = "camelCase")]
-pub struct GetAllBanWordFiltersResponse {
-    ban_word_filters: Vec<BanWordFilterInfo>,
+    Ok(Json(ban_word_filters))
 }
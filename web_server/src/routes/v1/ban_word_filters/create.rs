@@ -9,20 +9,22 @@ use types::domain::BanWordFilter;
 use types::error::{AppResult, ValidationErrorsWrapper};
 use utils::jwt::Claims;
 
+use crate::revision::ETag;
+
 pub async fn handler(
     Extension(ban_word_service): Extension<Arc<BanWordService>>,
     Extension(claims): Extension<Arc<Claims>>,
     Json(request): Json<CreateBanWordFilterRequest>,
-) -> AppResult<Json<BanWordFilter>> {
+) -> AppResult<(ETag, Json<BanWordFilter>)> {
     request
         .validate()
-        .map_err(|e| ValidationErrorsWrapper::from(e))?;
+        .map_err(ValidationErrorsWrapper::from)?;
 
     let filter = ban_word_service
         .create_filter(&claims.sub, &request.name)
         .await?;
 
-    Ok(Json(filter))
+    Ok((ETag(filter.revision), Json(filter)))
 }
 
 #[derive(Deserialize, Validate)]
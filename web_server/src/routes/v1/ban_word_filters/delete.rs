@@ -1,26 +1,42 @@
 use std::sync::Arc;
 
-use axum::{extract::Path, http::StatusCode, Extension};
-use serde::Deserialize;
+use axum::{extract::Path, Extension, Json};
+use serde::{Deserialize, Serialize};
 use uuid::Uuid;
 
 use service::BanWordService;
+use types::domain::BanWordFilterUsage;
 use types::error::AppResult;
 use utils::jwt::Claims;
 
+use crate::revision::IfMatch;
+
+/// The filter goes to the trash, `usedBy` lists the chat settings which still
+/// reference it and will lose it when the trash is purged.
 pub async fn handler(
     Extension(ban_word_service): Extension<Arc<BanWordService>>,
     Extension(claims): Extension<Arc<Claims>>,
     Path(path_params): Path<DeleteBanWordFilterPathParams>,
-) -> AppResult<StatusCode> {
-    ban_word_service
-        .delete_filter(&claims.sub, &path_params.ban_word_filter_id)
+    IfMatch(expected_revision): IfMatch,
+) -> AppResult<Json<DeleteBanWordFilterResponse>> {
+    let used_by = ban_word_service
+        .delete_filter(
+            &claims.sub,
+            &path_params.ban_word_filter_id,
+            expected_revision,
+        )
         .await?;
 
-    Ok(StatusCode::NO_CONTENT)
+    Ok(Json(DeleteBanWordFilterResponse { used_by }))
 }
 
 #[derive(Deserialize)]
 pub struct DeleteBanWordFilterPathParams {
     ban_word_filter_id: Uuid,
 }
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DeleteBanWordFilterResponse {
+    used_by: Vec<BanWordFilterUsage>,
+}
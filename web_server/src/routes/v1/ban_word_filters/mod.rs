@@ -7,14 +7,21 @@ mod all;
 mod create;
 mod delete;
 mod one;
+mod restore;
+mod trash;
 mod update;
 
 pub fn routes() -> Router {
     Router::new()
         .route("/", routing::get(all::handler))
         .route("/", routing::post(create::handler))
+        .route("/trash", routing::get(trash::handler))
         .route("/:ban_word_filter_id", routing::put(update::handler))
         .route("/:ban_word_filter_id", routing::delete(delete::handler))
+        .route(
+            "/:ban_word_filter_id/restore",
+            routing::post(restore::handler),
+        )
         .layer(from_fn(auth_middleware))
         .route("/:ban_word_filter_id", routing::get(one::handler))
 }
@@ -8,15 +8,17 @@ use service::BanWordService;
 use types::domain::BanWordFilter;
 use types::error::AppResult;
 
+use crate::revision::ETag;
+
 pub async fn handler(
     Extension(ban_word_service): Extension<Arc<BanWordService>>,
     Path(path_params): Path<GetBanWordFilterPathParams>,
-) -> AppResult<Json<BanWordFilter>> {
+) -> AppResult<(ETag, Json<BanWordFilter>)> {
     let ban_word_filter = ban_word_service
         .get_filter(&path_params.ban_word_filter_id)
         .await?;
 
-    Ok(Json(ban_word_filter))
+    Ok((ETag(ban_word_filter.revision), Json(ban_word_filter)))
 }
 
 #[derive(Deserialize)]
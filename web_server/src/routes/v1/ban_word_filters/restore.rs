@@ -0,0 +1,29 @@
+use std::sync::Arc;
+
+use axum::{extract::Path, Extension, Json};
+use serde::Deserialize;
+use uuid::Uuid;
+
+use service::BanWordService;
+use types::domain::BanWordFilter;
+use types::error::AppResult;
+use utils::jwt::Claims;
+
+use crate::revision::ETag;
+
+pub async fn handler(
+    Extension(ban_word_service): Extension<Arc<BanWordService>>,
+    Extension(claims): Extension<Arc<Claims>>,
+    Path(path_params): Path<RestoreBanWordFilterPathParams>,
+) -> AppResult<(ETag, Json<BanWordFilter>)> {
+    let ban_word_filter = ban_word_service
+        .restore_filter(&claims.sub, &path_params.ban_word_filter_id)
+        .await?;
+
+    Ok((ETag(ban_word_filter.revision), Json(ban_word_filter)))
+}
+
+#[derive(Deserialize)]
+pub struct RestoreBanWordFilterPathParams {
+    ban_word_filter_id: Uuid,
+}
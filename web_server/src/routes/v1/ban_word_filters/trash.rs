@@ -0,0 +1,17 @@
+use std::sync::Arc;
+
+use axum::{Extension, Json};
+
+use service::BanWordService;
+use types::domain::BanWordFilterInfo;
+use types::error::AppResult;
+use utils::jwt::Claims;
+
+pub async fn handler(
+    Extension(ban_word_service): Extension<Arc<BanWordService>>,
+    Extension(claims): Extension<Arc<Claims>>,
+) -> AppResult<Json<Vec<BanWordFilterInfo>>> {
+    let ban_word_filters = ban_word_service.get_filters_trash(&claims.sub).await?;
+
+    Ok(Json(ban_word_filters))
+}
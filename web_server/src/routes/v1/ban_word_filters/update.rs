@@ -10,21 +10,29 @@ use types::domain::{BanWordFilter, UpdateBanWordFilter};
 use types::error::{AppResult, ValidationErrorsWrapper};
 use utils::jwt::Claims;
 
+use crate::revision::{ETag, IfMatch};
+
 pub async fn handler(
     Extension(ban_word_service): Extension<Arc<BanWordService>>,
     Extension(claims): Extension<Arc<Claims>>,
     Path(path_params): Path<UpdateBanWordFilterPathParams>,
+    IfMatch(expected_revision): IfMatch,
     Json(request): Json<UpdateBanWordFilter>,
-) -> AppResult<Json<BanWordFilter>> {
+) -> AppResult<(ETag, Json<BanWordFilter>)> {
     request
         .validate()
-        .map_err(|e| ValidationErrorsWrapper::from(e))?;
+        .map_err(ValidationErrorsWrapper::from)?;
 
     let filter = ban_word_service
-        .update_filter(&claims.sub, &path_params.ban_word_filter_id, &request)
+        .update_filter(
+            &claims.sub,
+            &path_params.ban_word_filter_id,
+            &request,
+            expected_revision,
+        )
         .await?;
 
-    Ok(Json(filter))
+    Ok((ETag(filter.revision), Json(filter)))
 }
 
 #[derive(Deserialize)]
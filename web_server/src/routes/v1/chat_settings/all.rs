@@ -1,24 +1,26 @@
 use std::sync::Arc;
 
+use axum::extract::Query;
 use axum::{Extension, Json};
-use serde::Serialize;
+use validator::Validate;
 
 use service::ChatService;
-use types::domain::ChatSettingsInfo;
-use types::error::AppResult;
+use types::domain::{ChatSettingsInfo, Page, PageRequest};
+use types::error::{AppResult, ValidationErrorsWrapper};
 use utils::jwt::Claims;
 
 pub async fn handler(
     Extension(chat_service): Extension<Arc<ChatService>>,
     Extension(claims): Extension<Arc<Claims>>,
-) -> AppResult<Json<GetAllChatSettingsResponse>> {
-    let chat_settings = chat_service.get_all_chat_settings(&claims.sub).await?;
+    Query(page_request): Query<PageRequest>,
+) -> AppResult<Json<Page<ChatSettingsInfo>>> {
+    page_request
+        .validate()
+        .map_err(ValidationErrorsWrapper::from)?;
 
-    Ok(Json(GetAllChatSettingsResponse { chat_settings }))
-}
+    let chat_settings = chat_service
+        .get_all_chat_settings(&claims.sub, &page_request)
+        .await?;
 
-#[derive(Serialize)]
-#[serde(rename_all = "camelCase")]
-pub struct GetAllChatSettingsResponse {
-    chat_settings: Vec<ChatSettingsInfo>,
+    Ok(Json(chat_settings))
 }
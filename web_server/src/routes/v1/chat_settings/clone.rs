@@ -0,0 +1,40 @@
+use std::sync::Arc;
+
+use axum::{extract::Path, Extension, Json};
+use serde::Deserialize;
+use uuid::Uuid;
+use validator::Validate;
+
+use service::ChatService;
+use types::domain::ChatSettings;
+use types::error::{AppResult, ValidationErrorsWrapper};
+use utils::jwt::Claims;
+
+use crate::revision::ETag;
+
+pub async fn handler(
+    Extension(chat_service): Extension<Arc<ChatService>>,
+    Extension(claims): Extension<Arc<Claims>>,
+    Path(path_params): Path<CloneChatSettingsPathParams>,
+    Json(request): Json<CloneChatSettingsRequest>,
+) -> AppResult<(ETag, Json<ChatSettings>)> {
+    request.validate().map_err(ValidationErrorsWrapper::from)?;
+
+    let chat_settings = chat_service
+        .clone_chat_settings(&claims.sub, &path_params.chat_settings_id, &request.name)
+        .await?;
+
+    Ok((ETag(chat_settings.revision), Json(chat_settings)))
+}
+
+#[derive(Deserialize)]
+pub struct CloneChatSettingsPathParams {
+    chat_settings_id: Uuid,
+}
+
+#[derive(Deserialize, Validate)]
+#[serde(rename_all = "camelCase")]
+pub struct CloneChatSettingsRequest {
+    #[validate(length(min = 2, max = 32))]
+    name: String,
+}
@@ -2,6 +2,7 @@ use std::sync::Arc;
 
 use axum::{Extension, Json};
 use serde::Deserialize;
+use uuid::Uuid;
 use validator::Validate;
 
 use service::ChatService;
@@ -9,20 +10,25 @@ use types::domain::{ChatSettings, ChatType};
 use types::error::{AppResult, ValidationErrorsWrapper};
 use utils::jwt::Claims;
 
+use crate::revision::ETag;
+
 pub async fn handler(
     Extension(chat_service): Extension<Arc<ChatService>>,
     Extension(claims): Extension<Arc<Claims>>,
     Json(request): Json<CreateChatSettingsRequest>,
-) -> AppResult<Json<ChatSettings>> {
-    request
-        .validate()
-        .map_err(|e| ValidationErrorsWrapper::from(e))?;
+) -> AppResult<(ETag, Json<ChatSettings>)> {
+    request.validate().map_err(ValidationErrorsWrapper::from)?;
 
     let chat_settings = chat_service
-        .create_chat_settings(&claims.sub, &request.name, &request.chat_type)
+        .create_chat_settings(
+            &claims.sub,
+            &request.name,
+            request.chat_type.as_ref(),
+            request.template_id.as_ref(),
+        )
         .await?;
 
-    Ok(Json(chat_settings))
+    Ok((ETag(chat_settings.revision), Json(chat_settings)))
 }
 
 #[derive(Deserialize, Validate)]
@@ -30,5 +36,6 @@ pub async fn handler(
 pub struct CreateChatSettingsRequest {
     #[validate(length(min = 2, max = 32))]
     name: String,
-    chat_type: ChatType,
+    chat_type: Option<ChatType>,
+    template_id: Option<Uuid>,
 }
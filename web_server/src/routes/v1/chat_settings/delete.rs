@@ -8,13 +8,20 @@ use service::ChatService;
 use types::error::AppResult;
 use utils::jwt::Claims;
 
+use crate::revision::IfMatch;
+
 pub async fn handler(
     Extension(chat_service): Extension<Arc<ChatService>>,
     Extension(claims): Extension<Arc<Claims>>,
     Path(path_params): Path<DeleteChatSettingsPathParams>,
+    IfMatch(expected_revision): IfMatch,
 ) -> AppResult<StatusCode> {
     chat_service
-        .delete_chat_settings(&claims.sub, &path_params.chat_settings_id)
+        .delete_chat_settings(
+            &claims.sub,
+            &path_params.chat_settings_id,
+            expected_revision,
+        )
         .await?;
 
     Ok(StatusCode::NO_CONTENT)
@@ -0,0 +1,40 @@
+use std::sync::Arc;
+
+use axum::extract::{Path, Query};
+use axum::{Extension, Json};
+use serde::Deserialize;
+use uuid::Uuid;
+
+use service::ChatService;
+use types::domain::ChatSettingsDiff;
+use types::error::AppResult;
+use utils::jwt::Claims;
+
+pub async fn handler(
+    Extension(chat_service): Extension<Arc<ChatService>>,
+    Extension(claims): Extension<Arc<Claims>>,
+    Path(path_params): Path<DiffChatSettingsVersionsPathParams>,
+    Query(query_params): Query<DiffChatSettingsVersionsQueryParams>,
+) -> AppResult<Json<ChatSettingsDiff>> {
+    let diff = chat_service
+        .diff_chat_settings_versions(
+            &claims.sub,
+            &path_params.chat_settings_id,
+            query_params.from,
+            query_params.to,
+        )
+        .await?;
+
+    Ok(Json(diff))
+}
+
+#[derive(Deserialize)]
+pub struct DiffChatSettingsVersionsPathParams {
+    chat_settings_id: Uuid,
+}
+
+#[derive(Deserialize)]
+pub struct DiffChatSettingsVersionsQueryParams {
+    from: i32,
+    to: i32,
+}
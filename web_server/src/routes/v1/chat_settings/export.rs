@@ -0,0 +1,37 @@
+use std::sync::Arc;
+
+use axum::extract::{Path, Query};
+use axum::{Extension, Json};
+use serde::Deserialize;
+use uuid::Uuid;
+
+use service::ChatService;
+use types::domain::ChatSettingsExport;
+use types::error::AppResult;
+
+pub async fn handler(
+    Extension(chat_service): Extension<Arc<ChatService>>,
+    Path(path_params): Path<ExportChatSettingsPathParams>,
+    Query(query_params): Query<ExportChatSettingsQueryParams>,
+) -> AppResult<Json<ChatSettingsExport>> {
+    let export = chat_service
+        .export_chat_settings(
+            &path_params.chat_settings_id,
+            query_params.include_ban_word_filter,
+        )
+        .await?;
+
+    Ok(Json(export))
+}
+
+#[derive(Deserialize)]
+pub struct ExportChatSettingsPathParams {
+    chat_settings_id: Uuid,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ExportChatSettingsQueryParams {
+    #[serde(default)]
+    include_ban_word_filter: bool,
+}
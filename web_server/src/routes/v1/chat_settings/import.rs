@@ -0,0 +1,25 @@
+use std::sync::Arc;
+
+use axum::{Extension, Json};
+use serde_json::Value;
+
+use service::ChatService;
+use types::domain::ChatSettings;
+use types::error::AppResult;
+use utils::jwt::Claims;
+
+use crate::revision::ETag;
+
+/// The document is read as any JSON, older schema versions are upgraded
+/// before it's validated.
+pub async fn handler(
+    Extension(chat_service): Extension<Arc<ChatService>>,
+    Extension(claims): Extension<Arc<Claims>>,
+    Json(document): Json<Value>,
+) -> AppResult<(ETag, Json<ChatSettings>)> {
+    let chat_settings = chat_service
+        .import_chat_settings(&claims.sub, document)
+        .await?;
+
+    Ok((ETag(chat_settings.revision), Json(chat_settings)))
+}
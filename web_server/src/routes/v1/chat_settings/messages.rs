@@ -0,0 +1,32 @@
+use std::sync::Arc;
+
+use axum::extract::{Path, Query};
+use axum::{Extension, Json};
+use serde::Deserialize;
+use uuid::Uuid;
+
+use service::OverlayService;
+use types::domain::OverlayMessage;
+use types::error::AppResult;
+
+pub async fn handler(
+    Extension(overlay_service): Extension<Arc<OverlayService>>,
+    Path(path_params): Path<GetMessagesPathParams>,
+    Query(query_params): Query<GetMessagesQueryParams>,
+) -> AppResult<Json<Vec<OverlayMessage>>> {
+    let messages = overlay_service
+        .get_recent_messages(&path_params.chat_settings_id, query_params.limit)
+        .await?;
+
+    Ok(Json(messages))
+}
+
+#[derive(Deserialize)]
+pub struct GetMessagesPathParams {
+    chat_settings_id: Uuid,
+}
+
+#[derive(Deserialize)]
+pub struct GetMessagesQueryParams {
+    limit: Option<usize>,
+}
@@ -4,17 +4,60 @@ use axum::{routing, Router};
 use crate::middleware::auth_middleware;
 
 mod all;
+mod clone;
 mod create;
 mod delete;
+mod diff;
+mod export;
+mod import;
+mod messages;
 mod one;
+mod patch;
+mod preview;
+mod restore;
+mod restore_version;
+mod stream;
+mod style;
+mod trash;
 mod update;
+mod version;
+mod versions;
 
 pub fn routes() -> Router {
     Router::new()
         .route("/", routing::get(all::handler))
         .route("/", routing::post(create::handler))
+        .route("/import", routing::post(import::handler))
+        .route("/preview", routing::post(preview::handler))
+        .route("/trash", routing::get(trash::handler))
         .route("/:chat_settings_id", routing::put(update::handler))
+        .route("/:chat_settings_id", routing::patch(patch::handler))
         .route("/:chat_settings_id", routing::delete(delete::handler))
+        .route("/:chat_settings_id/clone", routing::post(clone::handler))
+        .route(
+            "/:chat_settings_id/restore",
+            routing::post(restore::handler),
+        )
+        .route(
+            "/:chat_settings_id/versions",
+            routing::get(versions::handler),
+        )
+        .route(
+            "/:chat_settings_id/versions/diff",
+            routing::get(diff::handler),
+        )
+        .route(
+            "/:chat_settings_id/versions/:version",
+            routing::get(version::handler),
+        )
+        .route(
+            "/:chat_settings_id/versions/:version/restore",
+            routing::post(restore_version::handler),
+        )
         .layer(from_fn(auth_middleware))
         .route("/:chat_settings_id", routing::get(one::handler))
+        .route("/:chat_settings_id/export", routing::get(export::handler))
+        .route("/:chat_settings_id/messages", routing::get(messages::handler))
+        .route("/:chat_settings_id/style.css", routing::get(style::handler))
+        .route("/:chat_settings_id/stream", routing::get(stream::handler))
 }
@@ -8,15 +8,17 @@ use service::ChatService;
 use types::domain::ChatSettings;
 use types::error::AppResult;
 
+use crate::revision::ETag;
+
 pub async fn handler(
     Extension(chat_service): Extension<Arc<ChatService>>,
     Path(path_params): Path<GetChatSettingsPathParams>,
-) -> AppResult<Json<ChatSettings>> {
+) -> AppResult<(ETag, Json<ChatSettings>)> {
     let chat_settings = chat_service
         .get_chat_settings(&path_params.chat_settings_id)
         .await?;
 
-    Ok(Json(chat_settings))
+    Ok((ETag(chat_settings.revision), Json(chat_settings)))
 }
 
 #[derive(Deserialize)]
@@ -0,0 +1,39 @@
+use std::sync::Arc;
+
+use axum::{extract::Path, Extension, Json};
+use serde::Deserialize;
+use serde_json::Value;
+use uuid::Uuid;
+
+use service::ChatService;
+use types::domain::ChatSettings;
+use types::error::AppResult;
+use utils::jwt::Claims;
+
+use crate::revision::{ETag, IfMatch};
+
+/// JSON Merge Patch of the settings, sent as `application/merge-patch+json`
+/// or `application/json`.
+pub async fn handler(
+    Extension(chat_service): Extension<Arc<ChatService>>,
+    Extension(claims): Extension<Arc<Claims>>,
+    Path(path_params): Path<PatchChatSettingsPathParams>,
+    IfMatch(expected_revision): IfMatch,
+    Json(patch): Json<Value>,
+) -> AppResult<(ETag, Json<ChatSettings>)> {
+    let chat_settings = chat_service
+        .patch_chat_settings(
+            &claims.sub,
+            &path_params.chat_settings_id,
+            &patch,
+            expected_revision,
+        )
+        .await?;
+
+    Ok((ETag(chat_settings.revision), Json(chat_settings)))
+}
+
+#[derive(Deserialize)]
+pub struct PatchChatSettingsPathParams {
+    chat_settings_id: Uuid,
+}
@@ -0,0 +1,48 @@
+use std::sync::Arc;
+
+use axum::extract::Query;
+use axum::response::{Html, IntoResponse, Response};
+use axum::{Extension, Json};
+use serde::Deserialize;
+use validator::Validate;
+
+use service::OverlayService;
+use types::domain::PreviewChatSettings;
+use types::error::{AppResult, ValidationErrorsWrapper};
+use utils::jwt::Claims;
+
+pub async fn handler(
+    Extension(overlay_service): Extension<Arc<OverlayService>>,
+    Extension(claims): Extension<Arc<Claims>>,
+    Query(query_params): Query<PreviewQueryParams>,
+    Json(request): Json<PreviewChatSettings>,
+) -> AppResult<Response> {
+    request.validate().map_err(ValidationErrorsWrapper::from)?;
+
+    match query_params.format {
+        PreviewFormat::Json => {
+            let preview = overlay_service.preview(&claims.sub, &request).await?;
+            Ok(Json(preview).into_response())
+        }
+        PreviewFormat::Html => {
+            let page = overlay_service
+                .render_preview_page(&claims.sub, &request)
+                .await?;
+            Ok(Html(page).into_response())
+        }
+    }
+}
+
+#[derive(Deserialize)]
+pub struct PreviewQueryParams {
+    #[serde(default)]
+    format: PreviewFormat,
+}
+
+#[derive(Deserialize, Default)]
+#[serde(rename_all = "lowercase")]
+pub enum PreviewFormat {
+    #[default]
+    Json,
+    Html,
+}
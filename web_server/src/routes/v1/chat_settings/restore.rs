@@ -0,0 +1,29 @@
+use std::sync::Arc;
+
+use axum::{extract::Path, Extension, Json};
+use serde::Deserialize;
+use uuid::Uuid;
+
+use service::ChatService;
+use types::domain::ChatSettings;
+use types::error::AppResult;
+use utils::jwt::Claims;
+
+use crate::revision::ETag;
+
+pub async fn handler(
+    Extension(chat_service): Extension<Arc<ChatService>>,
+    Extension(claims): Extension<Arc<Claims>>,
+    Path(path_params): Path<RestoreChatSettingsPathParams>,
+) -> AppResult<(ETag, Json<ChatSettings>)> {
+    let chat_settings = chat_service
+        .restore_chat_settings(&claims.sub, &path_params.chat_settings_id)
+        .await?;
+
+    Ok((ETag(chat_settings.revision), Json(chat_settings)))
+}
+
+#[derive(Deserialize)]
+pub struct RestoreChatSettingsPathParams {
+    chat_settings_id: Uuid,
+}
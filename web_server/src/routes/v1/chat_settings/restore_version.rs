@@ -0,0 +1,36 @@
+use std::sync::Arc;
+
+use axum::{extract::Path, Extension, Json};
+use serde::Deserialize;
+use uuid::Uuid;
+
+use service::ChatService;
+use types::domain::ChatSettings;
+use types::error::AppResult;
+use utils::jwt::Claims;
+
+use crate::revision::{ETag, IfMatch};
+
+pub async fn handler(
+    Extension(chat_service): Extension<Arc<ChatService>>,
+    Extension(claims): Extension<Arc<Claims>>,
+    Path(path_params): Path<RestoreChatSettingsVersionPathParams>,
+    IfMatch(expected_revision): IfMatch,
+) -> AppResult<(ETag, Json<ChatSettings>)> {
+    let chat_settings = chat_service
+        .restore_chat_settings_version(
+            &claims.sub,
+            &path_params.chat_settings_id,
+            path_params.version,
+            expected_revision,
+        )
+        .await?;
+
+    Ok((ETag(chat_settings.revision), Json(chat_settings)))
+}
+
+#[derive(Deserialize)]
+pub struct RestoreChatSettingsVersionPathParams {
+    chat_settings_id: Uuid,
+    version: i32,
+}
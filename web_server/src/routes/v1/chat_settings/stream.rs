@@ -0,0 +1,59 @@
+use std::sync::Arc;
+
+use axum::extract::ws::{Message, WebSocket, WebSocketUpgrade};
+use axum::extract::{Path, Query};
+use axum::response::Response;
+use axum::Extension;
+use serde::Deserialize;
+use uuid::Uuid;
+
+use service::{OverlayService, OverlayStream};
+use types::error::AppResult;
+
+pub async fn handler(
+    Extension(overlay_service): Extension<Arc<OverlayService>>,
+    Path(path_params): Path<StreamChatPathParams>,
+    Query(query_params): Query<StreamChatQueryParams>,
+    ws: WebSocketUpgrade,
+) -> AppResult<Response> {
+    let stream = overlay_service
+        .subscribe(&path_params.chat_settings_id, query_params.since.as_deref())
+        .await?;
+
+    Ok(ws.on_upgrade(move |socket| forward_events(socket, stream)))
+}
+
+async fn forward_events(mut socket: WebSocket, mut stream: OverlayStream) {
+    loop {
+        tokio::select! {
+            event = stream.next() => {
+                let Some(event) = event else {
+                    break;
+                };
+                let Ok(text) = serde_json::to_string(&event) else {
+                    continue;
+                };
+                if socket.send(Message::Text(text)).await.is_err() {
+                    break;
+                }
+            }
+            message = socket.recv() => {
+                match message {
+                    Some(Ok(Message::Close(_))) | Some(Err(_)) | None => break,
+                    _ => {}
+                }
+            }
+        }
+    }
+}
+
+#[derive(Deserialize)]
+pub struct StreamChatPathParams {
+    chat_settings_id: Uuid,
+}
+
+#[derive(Deserialize)]
+pub struct StreamChatQueryParams {
+    /// Id of the last message the overlay got before reconnecting.
+    since: Option<String>,
+}
@@ -0,0 +1,55 @@
+use std::sync::Arc;
+
+use axum::extract::Path;
+use axum::http::header::{CACHE_CONTROL, CONTENT_TYPE, ETAG, IF_NONE_MATCH};
+use axum::http::{HeaderMap, StatusCode};
+use axum::response::{IntoResponse, Response};
+use axum::Extension;
+use serde::Deserialize;
+use uuid::Uuid;
+
+use service::ChatService;
+use types::error::AppResult;
+
+pub async fn handler(
+    Extension(chat_service): Extension<Arc<ChatService>>,
+    Path(path_params): Path<GetStylePathParams>,
+    headers: HeaderMap,
+) -> AppResult<Response> {
+    let stylesheet = chat_service
+        .get_stylesheet(&path_params.chat_settings_id)
+        .await?;
+
+    // Overlays revalidate on every load, unchanged settings cost a 304.
+    let cache_headers = [
+        (ETAG, stylesheet.etag.clone()),
+        (CACHE_CONTROL, "no-cache".to_string()),
+    ];
+
+    if is_not_modified(&headers, &stylesheet.etag) {
+        return Ok((StatusCode::NOT_MODIFIED, cache_headers).into_response());
+    }
+
+    Ok((
+        cache_headers,
+        [(CONTENT_TYPE, "text/css; charset=utf-8")],
+        stylesheet.css,
+    )
+        .into_response())
+}
+
+/// `If-None-Match` uses the weak comparison, so `W/` prefixes are ignored.
+fn is_not_modified(headers: &HeaderMap, etag: &str) -> bool {
+    headers
+        .get_all(IF_NONE_MATCH)
+        .iter()
+        .filter_map(|value| value.to_str().ok())
+        .flat_map(|value| value.split(','))
+        .map(|tag| tag.trim())
+        .any(|tag| tag == "*" || tag.trim_start_matches("W/") == etag)
+}
+
+#[derive(Deserialize)]
+pub struct GetStylePathParams {
+    chat_settings_id: Uuid,
+}
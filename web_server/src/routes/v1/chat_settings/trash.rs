@@ -0,0 +1,17 @@
+use std::sync::Arc;
+
+use axum::{Extension, Json};
+
+use service::ChatService;
+use types::domain::ChatSettingsInfo;
+use types::error::AppResult;
+use utils::jwt::Claims;
+
+pub async fn handler(
+    Extension(chat_service): Extension<Arc<ChatService>>,
+    Extension(claims): Extension<Arc<Claims>>,
+) -> AppResult<Json<Vec<ChatSettingsInfo>>> {
+    let chat_settings = chat_service.get_chat_settings_trash(&claims.sub).await?;
+
+    Ok(Json(chat_settings))
+}
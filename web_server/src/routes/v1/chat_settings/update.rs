@@ -10,21 +10,27 @@ use types::domain::{ChatSettings, UpdateChatSettings};
 use types::error::{AppResult, ValidationErrorsWrapper};
 use utils::jwt::Claims;
 
+use crate::revision::{ETag, IfMatch};
+
 pub async fn handler(
     Extension(chat_service): Extension<Arc<ChatService>>,
     Extension(claims): Extension<Arc<Claims>>,
     Path(path_params): Path<UpdateChatSettingsPathParams>,
-    Json(mut request): Json<UpdateChatSettings>,
-) -> AppResult<Json<ChatSettings>> {
-    request
-        .validate()
-        .map_err(|e| ValidationErrorsWrapper::from(e))?;
+    IfMatch(expected_revision): IfMatch,
+    Json(request): Json<UpdateChatSettings>,
+) -> AppResult<(ETag, Json<ChatSettings>)> {
+    request.validate().map_err(ValidationErrorsWrapper::from)?;
 
     let filter = chat_service
-        .update_chat_settings(&claims.sub, &path_params.chat_settings_id, &mut request)
+        .update_chat_settings(
+            &claims.sub,
+            &path_params.chat_settings_id,
+            &request,
+            expected_revision,
+        )
         .await?;
 
-    Ok(Json(filter))
+    Ok((ETag(filter.revision), Json(filter)))
 }
 
 #[derive(Deserialize)]
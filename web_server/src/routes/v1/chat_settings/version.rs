@@ -0,0 +1,32 @@
+use std::sync::Arc;
+
+use axum::{extract::Path, Extension, Json};
+use serde::Deserialize;
+use uuid::Uuid;
+
+use service::ChatService;
+use types::domain::ChatSettingsVersion;
+use types::error::AppResult;
+use utils::jwt::Claims;
+
+pub async fn handler(
+    Extension(chat_service): Extension<Arc<ChatService>>,
+    Extension(claims): Extension<Arc<Claims>>,
+    Path(path_params): Path<GetChatSettingsVersionPathParams>,
+) -> AppResult<Json<ChatSettingsVersion>> {
+    let version = chat_service
+        .get_chat_settings_version(
+            &claims.sub,
+            &path_params.chat_settings_id,
+            path_params.version,
+        )
+        .await?;
+
+    Ok(Json(version))
+}
+
+#[derive(Deserialize)]
+pub struct GetChatSettingsVersionPathParams {
+    chat_settings_id: Uuid,
+    version: i32,
+}
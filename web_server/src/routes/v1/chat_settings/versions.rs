@@ -0,0 +1,27 @@
+use std::sync::Arc;
+
+use axum::{extract::Path, Extension, Json};
+use serde::Deserialize;
+use uuid::Uuid;
+
+use service::ChatService;
+use types::domain::ChatSettingsVersionInfo;
+use types::error::AppResult;
+use utils::jwt::Claims;
+
+pub async fn handler(
+    Extension(chat_service): Extension<Arc<ChatService>>,
+    Extension(claims): Extension<Arc<Claims>>,
+    Path(path_params): Path<GetChatSettingsVersionsPathParams>,
+) -> AppResult<Json<Vec<ChatSettingsVersionInfo>>> {
+    let versions = chat_service
+        .get_chat_settings_versions(&claims.sub, &path_params.chat_settings_id)
+        .await?;
+
+    Ok(Json(versions))
+}
+
+#[derive(Deserialize)]
+pub struct GetChatSettingsVersionsPathParams {
+    chat_settings_id: Uuid,
+}
@@ -0,0 +1,24 @@
+use std::sync::Arc;
+
+use axum::extract::Query;
+use axum::{Extension, Json};
+use validator::Validate;
+
+use service::ChatService;
+use types::domain::{ChatSettingsTemplateInfo, Page, PageRequest};
+use types::error::{AppResult, ValidationErrorsWrapper};
+
+pub async fn handler(
+    Extension(chat_service): Extension<Arc<ChatService>>,
+    Query(page_request): Query<PageRequest>,
+) -> AppResult<Json<Page<ChatSettingsTemplateInfo>>> {
+    page_request
+        .validate()
+        .map_err(ValidationErrorsWrapper::from)?;
+
+    let templates = chat_service
+        .get_chat_settings_templates(&page_request)
+        .await?;
+
+    Ok(Json(templates))
+}
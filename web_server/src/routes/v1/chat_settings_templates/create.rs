@@ -0,0 +1,41 @@
+use std::sync::Arc;
+
+use axum::{Extension, Json};
+use serde::Deserialize;
+use uuid::Uuid;
+use validator::Validate;
+
+use service::ChatService;
+use types::domain::ChatSettingsTemplate;
+use types::error::{AppResult, ValidationErrorsWrapper};
+use utils::jwt::Claims;
+
+pub async fn handler(
+    Extension(chat_service): Extension<Arc<ChatService>>,
+    Extension(claims): Extension<Arc<Claims>>,
+    Json(request): Json<CreateChatSettingsTemplateRequest>,
+) -> AppResult<Json<ChatSettingsTemplate>> {
+    request.validate().map_err(ValidationErrorsWrapper::from)?;
+
+    let template = chat_service
+        .publish_chat_settings_template(
+            &claims.sub,
+            &request.chat_settings_id,
+            &request.name,
+            &request.description,
+        )
+        .await?;
+
+    Ok(Json(template))
+}
+
+#[derive(Deserialize, Validate)]
+#[serde(rename_all = "camelCase")]
+pub struct CreateChatSettingsTemplateRequest {
+    chat_settings_id: Uuid,
+    #[validate(length(min = 2, max = 32))]
+    name: String,
+    #[serde(default)]
+    #[validate(length(max = 256))]
+    description: String,
+}
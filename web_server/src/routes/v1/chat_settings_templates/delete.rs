@@ -0,0 +1,26 @@
+use std::sync::Arc;
+
+use axum::{extract::Path, http::StatusCode, Extension};
+use serde::Deserialize;
+use uuid::Uuid;
+
+use service::ChatService;
+use types::error::AppResult;
+use utils::jwt::Claims;
+
+pub async fn handler(
+    Extension(chat_service): Extension<Arc<ChatService>>,
+    Extension(claims): Extension<Arc<Claims>>,
+    Path(path_params): Path<DeleteChatSettingsTemplatePathParams>,
+) -> AppResult<StatusCode> {
+    chat_service
+        .delete_chat_settings_template(&claims.sub, &path_params.template_id)
+        .await?;
+
+    Ok(StatusCode::NO_CONTENT)
+}
+
+#[derive(Deserialize)]
+pub struct DeleteChatSettingsTemplatePathParams {
+    template_id: Uuid,
+}
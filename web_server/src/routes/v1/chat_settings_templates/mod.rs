@@ -0,0 +1,18 @@
+use axum::middleware::from_fn;
+use axum::{routing, Router};
+
+use crate::middleware::auth_middleware;
+
+mod all;
+mod create;
+mod delete;
+mod one;
+
+pub fn routes() -> Router {
+    Router::new()
+        .route("/", routing::post(create::handler))
+        .route("/:template_id", routing::delete(delete::handler))
+        .layer(from_fn(auth_middleware))
+        .route("/", routing::get(all::handler))
+        .route("/:template_id", routing::get(one::handler))
+}
@@ -0,0 +1,25 @@
+use std::sync::Arc;
+
+use axum::{extract::Path, Extension, Json};
+use serde::Deserialize;
+use uuid::Uuid;
+
+use service::ChatService;
+use types::domain::ChatSettingsTemplate;
+use types::error::AppResult;
+
+pub async fn handler(
+    Extension(chat_service): Extension<Arc<ChatService>>,
+    Path(path_params): Path<GetChatSettingsTemplatePathParams>,
+) -> AppResult<Json<ChatSettingsTemplate>> {
+    let template = chat_service
+        .get_chat_settings_template(&path_params.template_id)
+        .await?;
+
+    Ok(Json(template))
+}
+
+#[derive(Deserialize)]
+pub struct GetChatSettingsTemplatePathParams {
+    template_id: Uuid,
+}
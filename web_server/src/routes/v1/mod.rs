@@ -2,11 +2,16 @@ use axum::Router;
 
 mod ban_word_filters;
 mod chat_settings;
-mod twitch;
+mod chat_settings_templates;
+pub(crate) mod twitch;
 
 pub fn routes() -> Router {
     Router::new()
         .nest("/ban-word-filters", ban_word_filters::routes())
         .nest("/chat-settings", chat_settings::routes())
+        .nest(
+            "/chat-settings-templates",
+            chat_settings_templates::routes(),
+        )
         .nest("/twitch", twitch::routes())
 }
@@ -0,0 +1,24 @@
+use std::sync::Arc;
+
+use axum::{extract::Path, Extension, Json};
+use serde::Deserialize;
+
+use service::TwitchService;
+use types::error::AppResult;
+use types::twitch;
+
+pub async fn handler(
+    Extension(twitch_service): Extension<Arc<TwitchService>>,
+    Path(path_params): Path<GetChannelCheermotesPathParams>,
+) -> AppResult<Json<Vec<twitch::Cheermote>>> {
+    let cheermotes = twitch_service
+        .get_channel_cheermotes(&path_params.channel_id)
+        .await?;
+
+    Ok(Json(cheermotes))
+}
+
+#[derive(Deserialize)]
+pub struct GetChannelCheermotesPathParams {
+    channel_id: String,
+}
@@ -0,0 +1,15 @@
+use std::sync::Arc;
+
+use axum::{Extension, Json};
+
+use service::TwitchService;
+use types::error::AppResult;
+use types::twitch;
+
+pub async fn handler(
+    Extension(twitch_service): Extension<Arc<TwitchService>>,
+) -> AppResult<Json<Vec<twitch::Cheermote>>> {
+    let cheermotes = twitch_service.get_global_cheermotes().await?;
+
+    Ok(Json(cheermotes))
+}
@@ -0,0 +1,57 @@
+use std::sync::Arc;
+
+use axum::body::Bytes;
+use axum::http::{HeaderMap, StatusCode};
+use axum::response::{IntoResponse, Response};
+use axum::{debug_handler, Extension};
+
+use service::EventService;
+use types::error::{AppError, AppResult};
+
+const MESSAGE_ID_HEADER: &str = "Twitch-Eventsub-Message-Id";
+const MESSAGE_TYPE_HEADER: &str = "Twitch-Eventsub-Message-Type";
+const MESSAGE_TIMESTAMP_HEADER: &str = "Twitch-Eventsub-Message-Timestamp";
+const MESSAGE_SIGNATURE_HEADER: &str = "Twitch-Eventsub-Message-Signature";
+
+#[debug_handler]
+pub async fn handler(
+    Extension(event_service): Extension<Arc<EventService>>,
+    headers: HeaderMap,
+    body: Bytes,
+) -> AppResult<Response> {
+    let challenge = event_service.handle_webhook(
+        header(
+            &headers,
+            MESSAGE_ID_HEADER,
+            EventService::MISSING_MESSAGE_ID_ERROR,
+        )?,
+        header(
+            &headers,
+            MESSAGE_TYPE_HEADER,
+            EventService::MISSING_MESSAGE_TYPE_ERROR,
+        )?,
+        header(
+            &headers,
+            MESSAGE_TIMESTAMP_HEADER,
+            EventService::MISSING_MESSAGE_TIMESTAMP_ERROR,
+        )?,
+        header(
+            &headers,
+            MESSAGE_SIGNATURE_HEADER,
+            EventService::MISSING_MESSAGE_SIGNATURE_ERROR,
+        )?,
+        &body,
+    )?;
+
+    match challenge {
+        Some(challenge) => Ok((StatusCode::OK, challenge).into_response()),
+        None => Ok(StatusCode::NO_CONTENT.into_response()),
+    }
+}
+
+fn header<'a>(headers: &'a HeaderMap, name: &str, missing: AppError) -> AppResult<&'a str> {
+    headers
+        .get(name)
+        .and_then(|value| value.to_str().ok())
+        .ok_or(missing)
+}
@@ -1,12 +1,17 @@
 use axum::Router;
 
 mod badges;
+mod cheermotes;
 mod emotes;
+pub(crate) mod eventsub;
+mod rewards;
 mod user;
 
 pub fn routes() -> Router {
     Router::new()
         .nest("/badges", badges::routes())
+        .nest("/cheermotes", cheermotes::routes())
         .nest("/emotes", emotes::routes())
+        .nest("/rewards", rewards::routes())
         .nest("/user/:login", user::routes())
 }
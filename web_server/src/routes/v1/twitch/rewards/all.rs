@@ -0,0 +1,17 @@
+use std::sync::Arc;
+
+use axum::{Extension, Json};
+
+use service::TwitchService;
+use types::error::AppResult;
+use types::twitch;
+use utils::jwt::Claims;
+
+pub async fn handler(
+    Extension(twitch_service): Extension<Arc<TwitchService>>,
+    Extension(claims): Extension<Arc<Claims>>,
+) -> AppResult<Json<Vec<twitch::Reward>>> {
+    let rewards = twitch_service.get_rewards(&claims.sub).await?;
+
+    Ok(Json(rewards))
+}
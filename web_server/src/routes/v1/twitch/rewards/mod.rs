@@ -0,0 +1,12 @@
+use axum::middleware::from_fn;
+use axum::{routing, Router};
+
+use crate::middleware::auth_middleware;
+
+mod all;
+
+pub fn routes() -> Router {
+    Router::new()
+        .route("/", routing::get(all::handler))
+        .layer(from_fn(auth_middleware))
+}